@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+
+use crate::analysis::evaluate_hand::HandEvaluation;
+use crate::analysis::game::Game;
+use crate::analysis::search_space::Combinations;
+use crate::cards::card::{ALL_SUITS, NUM_RANKS, Rank};
+use crate::cards::cardset::CardSet;
+
+// A bit per rank, with twos as the lowest bit.
+fn rank_mask(cards: CardSet) -> u16 {
+    cards
+        .iter_desc()
+        .fold(0, |mask, c| mask | (1 << c.rank as usize))
+}
+
+// The ranks of every straight, from the wheel (A-5) up to broadway (T-A).
+fn straight_masks() -> [u16; 10] {
+    let ace = 1 << Rank::Ace as usize;
+    let mut masks = [0; 10];
+    masks[0] = 0b1111 | ace;
+    for (i, mask) in masks.iter_mut().enumerate().skip(1) {
+        *mask = 0b11111 << (i - 1);
+    }
+    masks
+}
+
+fn has_straight(ranks: u16) -> bool {
+    straight_masks().iter().any(|s| ranks & s == *s)
+}
+
+// Whether `pocket` is one card away from a straight that it helps to make, and doesn't have one.
+fn has_straight_draw(pocket: CardSet, board: CardSet) -> bool {
+    let ranks = rank_mask(pocket | board);
+    let board_ranks = rank_mask(board);
+    !has_straight(ranks)
+        && straight_masks()
+            .iter()
+            .any(|s| (ranks & s).count_ones() == 4 && (board_ranks & s).count_ones() < 4)
+}
+
+// Whether `pocket` has four cards of a suit with the board, including one of its own, and no
+// flush.
+fn has_flush_draw(pocket: CardSet, board: CardSet) -> bool {
+    let cards = pocket | board;
+    let counts = ALL_SUITS.map(|suit| {
+        let of_suit = |set: CardSet| set.iter_desc().filter(|c| c.suit == suit).count();
+        (of_suit(cards), of_suit(pocket))
+    });
+    counts.iter().all(|(n, _)| *n < 5) && counts.iter().any(|(n, own)| *n == 4 && *own > 0)
+}
+
+// What a flop, turn or river looks like to the Hold'em pockets that can be dealt with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardTexture {
+    // The most cards of any one rank, and how many ranks have at least two cards.
+    pub most_of_a_rank: usize,
+    pub paired_ranks: usize,
+    pub most_of_a_suit: usize,
+    // The most different ranks of the board that fit in one straight.
+    pub connectedness: usize,
+    // The straights some pocket makes, counting each high card once.
+    pub possible_straights: usize,
+    // The pockets with a flush or straight draw. Always 0 on the river.
+    pub flush_draws: usize,
+    pub straight_draws: usize,
+}
+
+impl BoardTexture {
+    pub fn analyze(board: CardSet) -> Self {
+        let mut rank_counts = [0; NUM_RANKS];
+        for card in board.iter_desc() {
+            rank_counts[card.rank as usize] += 1;
+        }
+        let most_of_a_suit = ALL_SUITS
+            .iter()
+            .map(|suit| board.iter_desc().filter(|c| c.suit == *suit).count())
+            .max()
+            .unwrap_or(0);
+
+        let board_ranks = rank_mask(board);
+        let in_straights = straight_masks().map(|s| (board_ranks & s).count_ones() as usize);
+
+        let pockets = Combinations::new(CardSet::universe() - board, 2).collect::<Vec<CardSet>>();
+        let (flush_draws, straight_draws) = match board.len() {
+            5 => (0, 0),
+            _ => (
+                pockets
+                    .iter()
+                    .filter(|p| has_flush_draw(**p, board))
+                    .count(),
+                pockets
+                    .iter()
+                    .filter(|p| has_straight_draw(**p, board))
+                    .count(),
+            ),
+        };
+
+        Self {
+            most_of_a_rank: rank_counts.iter().copied().max().unwrap_or(0),
+            paired_ranks: rank_counts.iter().filter(|n| **n >= 2).count(),
+            most_of_a_suit,
+            connectedness: in_straights.iter().copied().max().unwrap_or(0),
+            // A pocket adds at most two ranks.
+            possible_straights: in_straights.iter().filter(|n| **n >= 3).count(),
+            flush_draws,
+            straight_draws,
+        }
+    }
+}
+
+// The pockets that make the same hand with the board.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankedHand {
+    pub hand: HandEvaluation,
+    pub pockets: Vec<CardSet>,
+}
+
+// Every hand a Hold'em pocket can make with `board`, from the nuts down.
+pub fn ranked_hands(board: CardSet) -> Vec<RankedHand> {
+    let mut hands = BTreeMap::<HandEvaluation, Vec<CardSet>>::new();
+    for pocket in Combinations::new(CardSet::universe() - board, 2) {
+        let hand = Game::Holdem.evaluate(pocket, board).unwrap();
+        hands.entry(hand).or_default().push(pocket);
+    }
+    hands
+        .into_iter()
+        .rev()
+        .map(|(hand, pockets)| RankedHand { hand, pockets })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::cardset::testing::cards;
+
+    #[test]
+    fn test_board_texture() {
+        let texture = BoardTexture::analyze(cards("9h 8h 2c"));
+        assert_eq!(texture.most_of_a_rank, 1);
+        assert_eq!(texture.most_of_a_suit, 2);
+        assert_eq!(texture.connectedness, 2);
+        assert_eq!(texture.possible_straights, 0);
+        // Two of the other 11 hearts.
+        assert_eq!(texture.flush_draws, 55);
+        assert!(texture.straight_draws > 0);
+
+        let texture = BoardTexture::analyze(cards("9h 8d 7c 7s 2h"));
+        assert_eq!((texture.most_of_a_rank, texture.paired_ranks), (2, 1));
+        assert_eq!(texture.connectedness, 3);
+        // 5-9, 6-T and 7-J.
+        assert_eq!(texture.possible_straights, 3);
+        assert_eq!((texture.flush_draws, texture.straight_draws), (0, 0));
+    }
+
+    #[test]
+    fn test_ranked_hands() {
+        let hands = ranked_hands(cards("Kd 7c 2h"));
+        assert_eq!(
+            hands.iter().map(|h| h.pockets.len()).sum::<usize>(),
+            49 * 48 / 2
+        );
+        // A set of kings.
+        assert_eq!(hands[0].pockets.len(), 3);
+        assert!(matches!(
+            hands[0].hand,
+            HandEvaluation::ThreeOfAKind {
+                rank: Rank::King,
+                ..
+            }
+        ));
+
+        let hands = ranked_hands(cards("Ah Kh Qh"));
+        assert_eq!(hands[0].pockets, vec![cards("Jh Th")]);
+    }
+}
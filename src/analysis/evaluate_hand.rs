@@ -3,8 +3,10 @@ use crate::analysis::rank_counter::RankCounter;
 use crate::analysis::suit_grouping::SuitGrouping;
 use crate::cards::card::{ALL_RANKS, ALL_SUITS, Card, Rank};
 use crate::cards::cardset::CardSet;
+use crate::cards::deck::Deck;
 use crate::datastructures::stack_vec::StackVec;
 use std::cmp::Reverse;
+use std::fmt::Display;
 
 pub const MAX_HAND_SIZE: usize = 7;
 
@@ -46,6 +48,51 @@ pub enum HandEvaluation {
     },
 }
 
+// The kinds of hands, from the weakest to the strongest in the standard ranking.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+pub enum HandCategory {
+    HighCard,
+    Pair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+pub const NUM_HAND_CATEGORIES: usize = 9;
+
+pub const ALL_HAND_CATEGORIES: [HandCategory; NUM_HAND_CATEGORIES] = [
+    HandCategory::HighCard,
+    HandCategory::Pair,
+    HandCategory::TwoPair,
+    HandCategory::ThreeOfAKind,
+    HandCategory::Straight,
+    HandCategory::Flush,
+    HandCategory::FullHouse,
+    HandCategory::FourOfAKind,
+    HandCategory::StraightFlush,
+];
+
+impl HandCategory {
+    // E.g. "Full House".
+    pub fn name(&self) -> &'static str {
+        match self {
+            HandCategory::HighCard => "High Card",
+            HandCategory::Pair => "Pair",
+            HandCategory::TwoPair => "Two Pair",
+            HandCategory::ThreeOfAKind => "Three of a Kind",
+            HandCategory::Straight => "Straight",
+            HandCategory::Flush => "Flush",
+            HandCategory::FullHouse => "Full House",
+            HandCategory::FourOfAKind => "Four of a Kind",
+            HandCategory::StraightFlush => "Straight Flush",
+        }
+    }
+}
+
 fn count_ranks(cards: CardSet) -> RankCounter {
     let mut counter = RankCounter::new();
     for card in cards.iter_desc() {
@@ -62,8 +109,8 @@ fn group_by_suit(cards: CardSet) -> SuitGrouping {
     return groupings;
 }
 
-// `ranks` must be sorted in descending order
-fn straight_high_rank<I: Iterator<Item = Rank>>(mut ranks: I) -> Option<Rank> {
+// `ranks` must be sorted in descending order. An ace can also play below the deck's lowest rank.
+fn straight_high_rank<I: Iterator<Item = Rank>>(mut ranks: I, deck: Deck) -> Option<Rank> {
     let first = match ranks.next() {
         Some(f) => f,
         None => return None,
@@ -90,7 +137,9 @@ fn straight_high_rank<I: Iterator<Item = Rank>>(mut ranks: I) -> Option<Rank> {
         }
     }
 
-    if straight_len == 5 || (straight_len == 4 && high_rank == Rank::Five && first == Rank::Ace) {
+    if straight_len == 5
+        || (straight_len == 4 && high_rank == deck.wheel_high_rank() && first == Rank::Ace)
+    {
         Some(high_rank)
     } else {
         None
@@ -134,9 +183,11 @@ impl Cardinalities {
 }
 
 // Each grouping must be sorted in descending order.
-fn match_straight_flush(by_suit: &SuitGrouping) -> Option<HandEvaluation> {
+fn match_straight_flush(by_suit: &SuitGrouping, deck: Deck) -> Option<HandEvaluation> {
     for suit in ALL_SUITS {
-        if let Some(highest_rank) = straight_high_rank(by_suit.get(suit).into_iter().map(|x| *x)) {
+        if let Some(highest_rank) =
+            straight_high_rank(by_suit.get(suit).into_iter().map(|x| *x), deck)
+        {
             return Some(StraightFlush { highest_rank });
         }
     }
@@ -194,8 +245,8 @@ fn match_flush(by_suit: &SuitGrouping) -> Option<HandEvaluation> {
     return None;
 }
 
-fn match_straight(cards: CardSet) -> Option<HandEvaluation> {
-    if let Some(highest_rank) = straight_high_rank(cards.iter_desc().map(|x| x.rank)) {
+fn match_straight(cards: CardSet, deck: Deck) -> Option<HandEvaluation> {
+    if let Some(highest_rank) = straight_high_rank(cards.iter_desc().map(|x| x.rank), deck) {
         Some(Straight { highest_rank })
     } else {
         None
@@ -275,6 +326,13 @@ fn match_high_card(cardinalities: &Cardinalities) -> HandEvaluation {
 
 impl HandEvaluation {
     pub fn evaluate_postflop(hand: CardSet) -> Option<Self> {
+        Self::evaluate_postflop_with_deck(hand, Deck::Standard)
+    }
+
+    // Like `evaluate_postflop`, but lets the ace make a wheel with the lowest ranks of `deck`.
+    // The result is only the kind of hand and its ranks; how hands compare across kinds is up to
+    // a `HandRanking`.
+    pub fn evaluate_postflop_with_deck(hand: CardSet, deck: Deck) -> Option<Self> {
         if hand.len() < 5 || hand.len() > 7 {
             return None;
         }
@@ -283,17 +341,315 @@ impl HandEvaluation {
         let cardinalities = Cardinalities::new(hand);
 
         return Some(
-            match_straight_flush(&by_suit)
+            match_straight_flush(&by_suit, deck)
                 .or_else(|| match_four_of_a_kind(&cardinalities))
                 .or_else(|| match_full_house(&cardinalities))
                 .or_else(|| match_flush(&by_suit))
-                .or_else(|| match_straight(hand))
+                .or_else(|| match_straight(hand, deck))
                 .or_else(|| match_trips(&cardinalities))
                 .or_else(|| match_two_pair(&cardinalities))
                 .or_else(|| match_pair(&cardinalities))
                 .unwrap_or_else(|| match_high_card(&cardinalities)),
         );
     }
+
+    // The five cards making up the best hand out of 5 to 7 cards, in the order of `ranks`.
+    pub fn best_five(hand: CardSet) -> Option<[Card; 5]> {
+        Self::best_five_with_deck(hand, Deck::Standard)
+    }
+
+    pub fn best_five_with_deck(hand: CardSet, deck: Deck) -> Option<[Card; 5]> {
+        Self::evaluate_postflop_with_deck(hand, deck).map(|e| e.cards(hand, deck))
+    }
+
+    // The ranks of the five cards, in the order the hand is read: the cards that make it, highest
+    // group first, then the kickers. A wheel ends with its ace, e.g. 5 4 3 2 A.
+    pub fn ranks(&self, deck: Deck) -> [Rank; 5] {
+        let straight = |highest_rank: Rank| {
+            let high = highest_rank as usize;
+            if highest_rank == deck.wheel_high_rank() {
+                [
+                    highest_rank,
+                    ALL_RANKS[high - 1],
+                    ALL_RANKS[high - 2],
+                    ALL_RANKS[high - 3],
+                    Rank::Ace,
+                ]
+            } else {
+                [0, 1, 2, 3, 4].map(|i| ALL_RANKS[high - i])
+            }
+        };
+        match *self {
+            HighCard {
+                rank,
+                kickers_sorted_desc: k,
+            } => [rank, k[0], k[1], k[2], k[3]],
+            Pair {
+                rank,
+                kickers_sorted_desc: k,
+            } => [rank, rank, k[0], k[1], k[2]],
+            TwoPair {
+                higher_rank,
+                lower_rank,
+                kicker,
+            } => [higher_rank, higher_rank, lower_rank, lower_rank, kicker],
+            ThreeOfAKind {
+                rank,
+                kickers_sorted_desc: k,
+            } => [rank, rank, rank, k[0], k[1]],
+            Straight { highest_rank } | StraightFlush { highest_rank } => straight(highest_rank),
+            Flush { ranks_sorted_desc } => ranks_sorted_desc,
+            FullHouse { triple, pair } => [triple, triple, triple, pair, pair],
+            FourOfAKind { rank, kicker } => [rank, rank, rank, rank, kicker],
+        }
+    }
+
+    // The five cards of `hand` that make this hand, in the order of `ranks`. `hand` must be the
+    // cards this was evaluated from. Where several cards of a rank could play, the highest suits
+    // are picked.
+    pub fn cards(&self, hand: CardSet, deck: Deck) -> [Card; 5] {
+        let ranks = self.ranks(deck);
+        // Only one suit can hold all five ranks of a flush in at most 7 cards.
+        let pool = match self {
+            Flush { .. } | StraightFlush { .. } => ALL_SUITS
+                .iter()
+                .map(|suit| {
+                    hand.iter_desc()
+                        .filter(|c| c.suit == *suit)
+                        .collect::<CardSet>()
+                })
+                .find(|of_suit| {
+                    ranks
+                        .iter()
+                        .all(|r| of_suit.iter_desc().any(|c| c.rank == *r))
+                })
+                .expect("The hand doesn't have the cards of its flush"),
+            _ => hand,
+        };
+
+        let mut used = CardSet::new();
+        ranks.map(|rank| {
+            let card = (pool - used)
+                .iter_desc()
+                .find(|c| c.rank == rank)
+                .expect("The hand doesn't have the cards of its ranks");
+            used += card;
+            card
+        })
+    }
+
+    pub fn category(&self) -> HandCategory {
+        match self {
+            HighCard { .. } => HandCategory::HighCard,
+            Pair { .. } => HandCategory::Pair,
+            TwoPair { .. } => HandCategory::TwoPair,
+            ThreeOfAKind { .. } => HandCategory::ThreeOfAKind,
+            Straight { .. } => HandCategory::Straight,
+            Flush { .. } => HandCategory::Flush,
+            FullHouse { .. } => HandCategory::FullHouse,
+            FourOfAKind { .. } => HandCategory::FourOfAKind,
+            StraightFlush { .. } => HandCategory::StraightFlush,
+        }
+    }
+
+    // A compact form for tables: the kind of hand, then its ranks in the order they're read,
+    // e.g. "2P KK99A". Straights only give their highest rank, since the wheel depends on the
+    // deck.
+    pub fn short_code(&self) -> String {
+        let code = match self {
+            HighCard { .. } => "HC",
+            Pair { .. } => "1P",
+            TwoPair { .. } => "2P",
+            ThreeOfAKind { .. } => "3K",
+            Straight { .. } => "ST",
+            Flush { .. } => "FL",
+            FullHouse { .. } => "FH",
+            FourOfAKind { .. } => "4K",
+            StraightFlush { .. } => "SF",
+        };
+        let ranks = match self {
+            Straight { highest_rank } | StraightFlush { highest_rank } => {
+                format!("{}-high", highest_rank)
+            }
+            _ => self
+                .ranks(Deck::Standard)
+                .iter()
+                .map(|r| r.to_string())
+                .collect(),
+        };
+        format!("{} {}", code, ranks)
+    }
+
+    // The long form for a game dealt from `deck`, where the wheel is the straight up to
+    // `deck.wheel_high_rank()`, e.g. "Wheel (9-high Straight)" in short deck.
+    pub fn describe(&self, deck: Deck) -> String {
+        match *self {
+            Straight { highest_rank } if highest_rank == deck.wheel_high_rank() => {
+                format!("Wheel ({}-high Straight)", highest_rank)
+            }
+            StraightFlush { highest_rank } if highest_rank == deck.wheel_high_rank() => {
+                format!("Steel Wheel ({}-high Straight Flush)", highest_rank)
+            }
+            _ => self.to_string(),
+        }
+    }
+}
+
+fn rank_name(rank: Rank) -> &'static str {
+    match rank {
+        Rank::Two => "Two",
+        Rank::Three => "Three",
+        Rank::Four => "Four",
+        Rank::Five => "Five",
+        Rank::Six => "Six",
+        Rank::Seven => "Seven",
+        Rank::Eight => "Eight",
+        Rank::Nine => "Nine",
+        Rank::Ten => "Ten",
+        Rank::Jack => "Jack",
+        Rank::Queen => "Queen",
+        Rank::King => "King",
+        Rank::Ace => "Ace",
+    }
+}
+
+fn rank_plural(rank: Rank) -> String {
+    match rank {
+        Rank::Six => "Sixes".to_string(),
+        _ => format!("{}s", rank_name(rank)),
+    }
+}
+
+// E.g. "Ace kicker" or "Ace-King-Nine kickers".
+fn kickers(ranks: &[Rank]) -> String {
+    let names = ranks.iter().map(|r| rank_name(*r)).collect::<Vec<&str>>();
+    let noun = if ranks.len() == 1 {
+        "kicker"
+    } else {
+        "kickers"
+    };
+    format!("{} {}", names.join("-"), noun)
+}
+
+// The long form, e.g. "Full House, Queens full of Fours" or "Pair of Jacks, Ace-King-Nine
+// kickers". Wheels are named for a standard deck; `describe` names them for any deck.
+impl Display for HandEvaluation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            HighCard {
+                rank,
+                kickers_sorted_desc,
+            } => write!(
+                f,
+                "{}-high, {}",
+                rank_name(rank),
+                kickers(&kickers_sorted_desc)
+            ),
+            Pair {
+                rank,
+                kickers_sorted_desc,
+            } => write!(
+                f,
+                "Pair of {}, {}",
+                rank_plural(rank),
+                kickers(&kickers_sorted_desc)
+            ),
+            TwoPair {
+                higher_rank,
+                lower_rank,
+                kicker,
+            } => write!(
+                f,
+                "Two Pair, {} and {}, {}",
+                rank_plural(higher_rank),
+                rank_plural(lower_rank),
+                kickers(&[kicker])
+            ),
+            ThreeOfAKind {
+                rank,
+                kickers_sorted_desc,
+            } => write!(
+                f,
+                "Three of a Kind, {}, {}",
+                rank_plural(rank),
+                kickers(&kickers_sorted_desc)
+            ),
+            Straight {
+                highest_rank: Rank::Five,
+            } => f.write_str("Wheel (5-high Straight)"),
+            Straight { highest_rank } => write!(f, "{}-high Straight", rank_name(highest_rank)),
+            Flush { ranks_sorted_desc } => {
+                write!(f, "{}-high Flush", rank_name(ranks_sorted_desc[0]))
+            }
+            FullHouse { triple, pair } => write!(
+                f,
+                "Full House, {} full of {}",
+                rank_plural(triple),
+                rank_plural(pair)
+            ),
+            FourOfAKind { rank, kicker } => write!(
+                f,
+                "Four of a Kind, {}, {}",
+                rank_plural(rank),
+                kickers(&[kicker])
+            ),
+            StraightFlush {
+                highest_rank: Rank::Ace,
+            } => f.write_str("Royal Flush"),
+            StraightFlush {
+                highest_rank: Rank::Five,
+            } => f.write_str("Steel Wheel (5-high Straight Flush)"),
+            StraightFlush { highest_rank } => {
+                write!(f, "{}-high Straight Flush", rank_name(highest_rank))
+            }
+        }
+    }
+}
+
+// The order of the kinds of hands. Hands of the same kind always compare by their ranks.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum HandRanking {
+    // The order `HandEvaluation` is declared in.
+    Standard,
+    // Short deck, where flushes are rarer than full houses and so beat them. Some rooms also rank
+    // three of a kind above a straight.
+    ShortDeck { trips_beat_straights: bool },
+}
+
+impl HandRanking {
+    // The position of `category` in this ranking, where a higher position wins.
+    pub fn category_position(&self, category: HandCategory) -> u8 {
+        // The category at the same position in the standard ranking.
+        let standard = match (self, category) {
+            (HandRanking::Standard, _) => category,
+            (HandRanking::ShortDeck { .. }, HandCategory::Flush) => HandCategory::FullHouse,
+            (HandRanking::ShortDeck { .. }, HandCategory::FullHouse) => HandCategory::Flush,
+            (
+                HandRanking::ShortDeck {
+                    trips_beat_straights: true,
+                },
+                HandCategory::ThreeOfAKind,
+            ) => HandCategory::Straight,
+            (
+                HandRanking::ShortDeck {
+                    trips_beat_straights: true,
+                },
+                HandCategory::Straight,
+            ) => HandCategory::ThreeOfAKind,
+            (HandRanking::ShortDeck { .. }, _) => category,
+        };
+        standard as u8
+    }
+
+    // The position of `hand`'s kind in this ranking, where a higher position wins.
+    pub fn category(&self, hand: &HandEvaluation) -> u8 {
+        self.category_position(hand.category())
+    }
+
+    // A value that orders hands from weakest to strongest under this ranking.
+    pub fn key(&self, hand: HandEvaluation) -> (u8, HandEvaluation) {
+        (self.category(&hand), hand)
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
@@ -324,6 +680,30 @@ impl PreflopEvaluation {
             }
         })
     }
+
+    // A compact form for tables, e.g. "KK" or "AK".
+    pub fn short_code(&self) -> String {
+        match self {
+            PreflopEvaluation::PocketPair { rank } => format!("{}{}", rank, rank),
+            PreflopEvaluation::HighCard {
+                higher_rank,
+                lower_rank,
+            } => format!("{}{}", higher_rank, lower_rank),
+        }
+    }
+}
+
+// E.g. "Pair of Kings" or "Ace-King".
+impl Display for PreflopEvaluation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreflopEvaluation::PocketPair { rank } => write!(f, "Pair of {}", rank_plural(*rank)),
+            PreflopEvaluation::HighCard {
+                higher_rank,
+                lower_rank,
+            } => write!(f, "{}-{}", rank_name(*higher_rank), rank_name(*lower_rank)),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1540,4 +1920,187 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_best_five_two_pair() {
+        let hand = CardSet::from(&[
+            Card::ACE_SPADE,
+            Card::KING_SPADE,
+            Card::KING_DIAMOND,
+            Card::NINE_CLUB,
+            Card::NINE_HEART,
+            Card::FOUR_SPADE,
+            Card::TWO_CLUB,
+        ]);
+
+        assert_eq!(
+            HandEvaluation::best_five(hand),
+            Some([
+                Card::KING_SPADE,
+                Card::KING_DIAMOND,
+                Card::NINE_HEART,
+                Card::NINE_CLUB,
+                Card::ACE_SPADE,
+            ])
+        );
+    }
+
+    #[test]
+    fn test_best_five_flush_keeps_its_suit() {
+        let hand = CardSet::from(&[
+            Card::ACE_CLUB,
+            Card::KING_HEART,
+            Card::NINE_HEART,
+            Card::SEVEN_HEART,
+            Card::FOUR_HEART,
+            Card::TWO_HEART,
+            Card::KING_CLUB,
+        ]);
+
+        assert_eq!(
+            HandEvaluation::best_five(hand),
+            Some([
+                Card::KING_HEART,
+                Card::NINE_HEART,
+                Card::SEVEN_HEART,
+                Card::FOUR_HEART,
+                Card::TWO_HEART,
+            ])
+        );
+    }
+
+    #[test]
+    fn test_best_five_wheel() {
+        let hand = CardSet::from(&[
+            Card::ACE_CLUB,
+            Card::TWO_HEART,
+            Card::THREE_SPADE,
+            Card::FOUR_DIAMOND,
+            Card::FIVE_CLUB,
+            Card::KING_CLUB,
+        ]);
+        assert_eq!(
+            HandEvaluation::best_five(hand),
+            Some([
+                Card::FIVE_CLUB,
+                Card::FOUR_DIAMOND,
+                Card::THREE_SPADE,
+                Card::TWO_HEART,
+                Card::ACE_CLUB,
+            ])
+        );
+
+        let hand = CardSet::from(&[
+            Card::ACE_CLUB,
+            Card::SIX_HEART,
+            Card::SEVEN_SPADE,
+            Card::EIGHT_DIAMOND,
+            Card::NINE_CLUB,
+        ]);
+        assert_eq!(
+            HandEvaluation::best_five_with_deck(hand, Deck::Short),
+            Some([
+                Card::NINE_CLUB,
+                Card::EIGHT_DIAMOND,
+                Card::SEVEN_SPADE,
+                Card::SIX_HEART,
+                Card::ACE_CLUB,
+            ])
+        );
+        assert_eq!(
+            HandEvaluation::best_five(CardSet::from(&[Card::ACE_CLUB])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_display_hand_evaluation() {
+        let full_house = FullHouse {
+            triple: Rank::Queen,
+            pair: Rank::Four,
+        };
+        assert_eq!(full_house.to_string(), "Full House, Queens full of Fours");
+        assert_eq!(full_house.short_code(), "FH QQQ44");
+
+        let flush = Flush {
+            ranks_sorted_desc: [Rank::Ace, Rank::Jack, Rank::Nine, Rank::Seven, Rank::Two],
+        };
+        assert_eq!(flush.to_string(), "Ace-high Flush");
+        assert_eq!(flush.short_code(), "FL AJ972");
+
+        let wheel = Straight {
+            highest_rank: Rank::Five,
+        };
+        assert_eq!(wheel.to_string(), "Wheel (5-high Straight)");
+        assert_eq!(wheel.short_code(), "ST 5-high");
+        assert_eq!(wheel.describe(Deck::Standard), "Wheel (5-high Straight)");
+
+        let nine_high = Straight {
+            highest_rank: Rank::Nine,
+        };
+        assert_eq!(nine_high.to_string(), "Nine-high Straight");
+        assert_eq!(nine_high.describe(Deck::Standard), "Nine-high Straight");
+        assert_eq!(nine_high.describe(Deck::Short), "Wheel (9-high Straight)");
+        assert_eq!(
+            StraightFlush {
+                highest_rank: Rank::Nine
+            }
+            .describe(Deck::Short),
+            "Steel Wheel (9-high Straight Flush)"
+        );
+
+        let pair = Pair {
+            rank: Rank::Jack,
+            kickers_sorted_desc: [Rank::Ace, Rank::King, Rank::Nine],
+        };
+        assert_eq!(pair.to_string(), "Pair of Jacks, Ace-King-Nine kickers");
+        assert_eq!(pair.short_code(), "1P JJAK9");
+
+        let two_pair = TwoPair {
+            higher_rank: Rank::King,
+            lower_rank: Rank::Six,
+            kicker: Rank::Ace,
+        };
+        assert_eq!(
+            two_pair.to_string(),
+            "Two Pair, Kings and Sixes, Ace kicker"
+        );
+    }
+
+    #[test]
+    fn test_display_preflop_evaluation() {
+        let pair = test_preflop([Card::KING_CLUB, Card::KING_SPADE]);
+        assert_eq!(pair.to_string(), "Pair of Kings");
+        assert_eq!(pair.short_code(), "KK");
+
+        let high_card = test_preflop([Card::ACE_CLUB, Card::KING_SPADE]);
+        assert_eq!(high_card.to_string(), "Ace-King");
+        assert_eq!(high_card.short_code(), "AK");
+    }
+
+    #[test]
+    fn test_hand_categories() {
+        let full_house = FullHouse {
+            triple: Rank::Queen,
+            pair: Rank::Four,
+        };
+        assert_eq!(full_house.category(), HandCategory::FullHouse);
+        assert_eq!(full_house.category().name(), "Full House");
+        assert!(ALL_HAND_CATEGORIES.is_sorted());
+
+        let short_deck = HandRanking::ShortDeck {
+            trips_beat_straights: true,
+        };
+        let mut categories = ALL_HAND_CATEGORIES;
+        categories.sort_by_key(|c| short_deck.category_position(*c));
+        assert_eq!(
+            categories[3..7],
+            [
+                HandCategory::Straight,
+                HandCategory::ThreeOfAKind,
+                HandCategory::FullHouse,
+                HandCategory::Flush
+            ]
+        );
+    }
 }
@@ -0,0 +1,400 @@
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+
+use crate::analysis::evaluate_hand::{HandEvaluation, HandRanking};
+use crate::analysis::hand_evaluator::HandEvaluator;
+use crate::analysis::hi_lo::LowHand;
+use crate::analysis::search_space::Combinations;
+use crate::analysis::table_evaluator::{HandStrength, TableEvaluator};
+use crate::cards::card::Card;
+use crate::cards::cardset::CardSet;
+use crate::cards::deck::Deck;
+use crate::datastructures::stack_vec::StackVec;
+
+pub const MAX_POCKET_SIZE: usize = 6;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Game {
+    // Each player has 2 hole cards and makes the best hand out of any 5 of their hole cards and
+    // the board.
+    Holdem,
+    // Each player has 4 to 6 hole cards and must use exactly 2 of them with exactly 3 board
+    // cards.
+    Omaha,
+    // Omaha where the pot is split between the best high hand and the best 8-or-better low, if
+    // anyone makes one.
+    OmahaHiLo,
+    // Hold'em dealt from a deck without the 2s to 5s. Flushes beat full houses, and optionally
+    // three of a kind beats a straight.
+    ShortDeck { trips_beat_straights: bool },
+}
+
+pub const ALL_GAMES: [Game; 5] = [
+    Game::Holdem,
+    Game::Omaha,
+    Game::OmahaHiLo,
+    Game::ShortDeck {
+        trips_beat_straights: false,
+    },
+    Game::ShortDeck {
+        trips_beat_straights: true,
+    },
+];
+
+impl Game {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Game::Holdem => "holdem",
+            Game::Omaha => "omaha",
+            Game::OmahaHiLo => "omaha8",
+            Game::ShortDeck {
+                trips_beat_straights: false,
+            } => "shortdeck",
+            Game::ShortDeck {
+                trips_beat_straights: true,
+            } => "shortdeck-trips",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        ALL_GAMES
+            .into_iter()
+            .find(|g| g.name().eq_ignore_ascii_case(name))
+    }
+
+    // The number of hole cards each player may be dealt.
+    pub fn pocket_sizes(&self) -> RangeInclusive<usize> {
+        match self {
+            Game::Holdem | Game::ShortDeck { .. } => 2..=2,
+            Game::Omaha | Game::OmahaHiLo => 4..=MAX_POCKET_SIZE,
+        }
+    }
+
+    pub fn deck(&self) -> Deck {
+        match self {
+            Game::Holdem | Game::Omaha | Game::OmahaHiLo => Deck::Standard,
+            Game::ShortDeck { .. } => Deck::Short,
+        }
+    }
+
+    // How the kinds of hands rank against each other.
+    pub fn hand_ranking(&self) -> HandRanking {
+        match self {
+            Game::Holdem | Game::Omaha | Game::OmahaHiLo => HandRanking::Standard,
+            Game::ShortDeck {
+                trips_beat_straights,
+            } => HandRanking::ShortDeck {
+                trips_beat_straights: *trips_beat_straights,
+            },
+        }
+    }
+
+    // Whether half of each pot goes to the best low hand.
+    pub fn is_hi_lo(&self) -> bool {
+        matches!(self, Game::OmahaHiLo)
+    }
+
+    // The game that is played with pockets of this size, if any.
+    pub fn for_pocket_size(size: usize) -> Option<Self> {
+        ALL_GAMES
+            .into_iter()
+            .find(|g| g.pocket_sizes().contains(&size))
+    }
+
+    // The best hand a player can make with `pocket` on `board`. Returns None if the cards can't
+    // make a hand under this game's rules.
+    pub fn evaluate(&self, pocket: CardSet, board: CardSet) -> Option<HandEvaluation> {
+        match self {
+            Game::Holdem => TableEvaluator::evaluate(pocket | board),
+            Game::Omaha | Game::OmahaHiLo => {
+                evaluate_omaha(pocket, board).map(HandEvaluation::from)
+            }
+            Game::ShortDeck { .. } => {
+                HandEvaluation::evaluate_postflop_with_deck(pocket | board, Deck::Short)
+            }
+        }
+    }
+
+    // The best qualifying low a player can make with `pocket` on `board`. Always None in games
+    // without a low half.
+    pub fn evaluate_low(&self, pocket: CardSet, board: CardSet) -> Option<LowHand> {
+        match self {
+            Game::Holdem | Game::Omaha | Game::ShortDeck { .. } => None,
+            Game::OmahaHiLo => LowHand::evaluate_omaha(pocket, board),
+        }
+    }
+
+    // The five cards that make the hand `evaluate` returns, in the order of
+    // `HandEvaluation::ranks`. Uses as few hole cards as this game's rules allow. Returns None if
+    // the cards can't make a hand.
+    pub fn best_five(&self, pocket: CardSet, board: CardSet) -> Option<[Card; 5]> {
+        let candidates: Vec<CardSet> = match self {
+            Game::Holdem | Game::ShortDeck { .. } => Combinations::new(pocket | board, 5).collect(),
+            Game::Omaha | Game::OmahaHiLo => {
+                let board_triples = subsets(board, 3);
+                subsets(pocket, 2)
+                    .iter()
+                    .flat_map(|hole| board_triples.iter().map(move |triple| *hole | *triple))
+                    .collect()
+            }
+        };
+
+        let ranking = self.hand_ranking();
+        let mut best: Option<(HandEvaluation, CardSet)> = None;
+        for five in candidates {
+            let Some(hand) = self.evaluate(five & pocket, five & board) else {
+                continue;
+            };
+            let better = match &best {
+                None => true,
+                Some((best_hand, best_five)) => {
+                    let (key, best_key) = (ranking.key(hand), ranking.key(*best_hand));
+                    key > best_key
+                        || (key == best_key && (five & pocket).len() < (*best_five & pocket).len())
+                }
+            };
+            if better {
+                best = Some((hand, five));
+            }
+        }
+        best.map(|(hand, five)| hand.cards(five, self.deck()))
+    }
+
+    // The hole cards that are part of the best five cards.
+    pub fn hole_cards_played(&self, pocket: CardSet, board: CardSet) -> Option<CardSet> {
+        self.best_five(pocket, board)
+            .map(|five| CardSet::from(&five) & pocket)
+    }
+}
+
+impl Display for Game {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Game::Holdem => "Hold'em",
+            Game::Omaha => "Omaha",
+            Game::OmahaHiLo => "Omaha Hi/Lo",
+            Game::ShortDeck { .. } => "Short Deck",
+        })
+    }
+}
+
+// Every way of picking `size` cards out of `cards`, which must have at most 6 cards.
+fn subsets(cards: CardSet, size: usize) -> StackVec<CardSet, 20> {
+    let mut all = StackVec::<Card, MAX_POCKET_SIZE>::new();
+    for card in cards.iter_desc() {
+        all.push(card);
+    }
+
+    let mut ret = StackVec::new();
+    for mask in 0u32..(1 << all.len()) {
+        if mask.count_ones() as usize == size {
+            ret.push(
+                (0..all.len())
+                    .filter(|i| mask & (1 << i) != 0)
+                    .map(|i| all[i])
+                    .collect(),
+            );
+        }
+    }
+    ret
+}
+
+// The best hand made of exactly 2 hole cards and exactly 3 board cards.
+fn evaluate_omaha(pocket: CardSet, board: CardSet) -> Option<HandStrength> {
+    if pocket.len() < 2
+        || pocket.len() > MAX_POCKET_SIZE
+        || board.len() < 3
+        || board.len() > 5
+        || !pocket.disjoint_with(board)
+    {
+        return None;
+    }
+
+    let hole_pairs = subsets(pocket, 2);
+    let board_triples = subsets(board, 3);
+
+    let mut best = None;
+    for hole in hole_pairs.iter() {
+        for triple in board_triples.iter() {
+            best = best.max(TableEvaluator::strength(*hole | *triple));
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::card::Rank;
+    use crate::cards::cardset::testing::cards;
+
+    #[test]
+    fn test_subsets() {
+        let pocket = cards("As Ks Qs Js Ts 9s");
+
+        assert_eq!(subsets(pocket, 2).len(), 15);
+        assert_eq!(subsets(cards("As Ks Qs Js Ts"), 3).len(), 10);
+        assert!(subsets(pocket, 2).iter().all(|s| s.len() == 2));
+    }
+
+    #[test]
+    fn test_omaha_needs_two_hole_cards_for_a_flush() {
+        let board = cards("2s 7s 9s Ks 3d");
+
+        assert!(matches!(
+            Game::Omaha.evaluate(cards("As Qh Jh Th"), board),
+            Some(HandEvaluation::HighCard { .. })
+        ));
+        assert!(matches!(
+            Game::Holdem.evaluate(cards("As Qh"), board),
+            Some(HandEvaluation::Flush { .. })
+        ));
+        assert_eq!(
+            Game::Omaha.evaluate(cards("As Qs Jh Th"), board),
+            Some(HandEvaluation::Flush {
+                ranks_sorted_desc: [Rank::Ace, Rank::King, Rank::Queen, Rank::Nine, Rank::Seven]
+            })
+        );
+    }
+
+    #[test]
+    fn test_omaha_can_use_only_three_board_cards() {
+        // Four aces on the board are only trips in Omaha.
+        let board = cards("Ac Ad Ah As 2c");
+
+        assert_eq!(
+            Game::Omaha.evaluate(cards("Kc Kd 7h 8h"), board),
+            Some(HandEvaluation::FullHouse {
+                triple: Rank::Ace,
+                pair: Rank::King
+            })
+        );
+        assert_eq!(
+            Game::Omaha.evaluate(cards("Kc Qd 7h 8h"), board),
+            Some(HandEvaluation::ThreeOfAKind {
+                rank: Rank::Ace,
+                kickers_sorted_desc: [Rank::King, Rank::Queen]
+            })
+        );
+    }
+
+    #[test]
+    fn test_omaha_must_use_two_hole_cards() {
+        // The board has a straight, but an Omaha player has to play two of their own cards.
+        let board = cards("5c 6d 7h 8s 9c");
+
+        assert_eq!(
+            Game::Omaha.evaluate(cards("Ac Ad Kh Ks"), board),
+            Some(HandEvaluation::Pair {
+                rank: Rank::Ace,
+                kickers_sorted_desc: [Rank::Nine, Rank::Eight, Rank::Seven]
+            })
+        );
+        assert_eq!(
+            Game::Omaha.evaluate(cards("Tc Jd 2h 2s"), board),
+            Some(HandEvaluation::Straight {
+                highest_rank: Rank::Jack
+            })
+        );
+    }
+
+    #[test]
+    fn test_omaha_rejects_bad_input() {
+        assert_eq!(
+            Game::Omaha.evaluate(cards("Ac Ad Kh Ks"), cards("2c 3c")),
+            None
+        );
+        assert_eq!(
+            Game::Omaha.evaluate(cards("Ac Ad Kh Ks"), cards("Ac 3c 4d")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_short_deck_wheel() {
+        let game = Game::ShortDeck {
+            trips_beat_straights: false,
+        };
+
+        assert_eq!(
+            game.evaluate(cards("Ac 6d"), cards("7h 8s 9c Kd Kh")),
+            Some(HandEvaluation::Straight {
+                highest_rank: Rank::Nine
+            })
+        );
+        assert!(matches!(
+            Game::Holdem.evaluate(cards("Ac 6d"), cards("7h 8s 9c Kd Kh")),
+            Some(HandEvaluation::Pair { .. })
+        ));
+    }
+
+    #[test]
+    fn test_short_deck_hand_ranking() {
+        let flush = Game::Holdem
+            .evaluate(cards("Ac Kc"), cards("7c 8c 9d 6c Kh"))
+            .unwrap();
+        let full_house = Game::Holdem
+            .evaluate(cards("Ac Ad"), cards("Ah Kc Kd 6c 7h"))
+            .unwrap();
+        let trips = Game::Holdem
+            .evaluate(cards("Ac Ad"), cards("Ah Kc Td 6c 7h"))
+            .unwrap();
+        let straight = Game::Holdem
+            .evaluate(cards("Tc Jd"), cards("Qh Kc Ad 6c 7h"))
+            .unwrap();
+
+        let standard = Game::Holdem.hand_ranking();
+        assert!(standard.key(full_house) > standard.key(flush));
+        assert!(standard.key(straight) > standard.key(trips));
+
+        let short = Game::from_name("shortdeck").unwrap().hand_ranking();
+        assert!(short.key(flush) > short.key(full_house));
+        assert!(short.key(straight) > short.key(trips));
+
+        let trips_first = Game::from_name("shortdeck-trips").unwrap().hand_ranking();
+        assert!(trips_first.key(flush) > trips_first.key(full_house));
+        assert!(trips_first.key(trips) > trips_first.key(straight));
+    }
+
+    #[test]
+    fn test_game_names() {
+        for game in ALL_GAMES {
+            assert_eq!(Game::from_name(game.name()), Some(game));
+        }
+        assert_eq!(Game::from_name("OMAHA"), Some(Game::Omaha));
+        assert_eq!(Game::from_name("omaha8"), Some(Game::OmahaHiLo));
+        assert_eq!(Game::for_pocket_size(2), Some(Game::Holdem));
+        assert_eq!(Game::for_pocket_size(5), Some(Game::Omaha));
+        assert_eq!(Game::for_pocket_size(3), None);
+    }
+
+    #[test]
+    fn test_best_five() {
+        let board = cards("Kd 9c 9h 4s 2c");
+        assert_eq!(
+            Game::Holdem.best_five(cards("As Ks"), board),
+            Some([
+                Card::KING_SPADE,
+                Card::KING_DIAMOND,
+                Card::NINE_HEART,
+                Card::NINE_CLUB,
+                Card::ACE_SPADE
+            ])
+        );
+        assert_eq!(
+            Game::Holdem.hole_cards_played(cards("As 3d"), board),
+            Some(cards("As"))
+        );
+        // The board plays.
+        assert_eq!(
+            Game::Holdem.hole_cards_played(cards("3h 5d"), cards("Ah Kh Qh Jh Th")),
+            Some(CardSet::new())
+        );
+        // Exactly two hole cards and three board cards.
+        assert_eq!(
+            Game::Omaha.hole_cards_played(cards("Ac Ad Kh Ks"), cards("5c 6d 7h 8s 9c")),
+            Some(cards("Ac Ad"))
+        );
+        assert_eq!(Game::Holdem.best_five(cards("As Ks"), cards("2c 3c")), None);
+    }
+}
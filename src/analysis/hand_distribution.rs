@@ -1,10 +1,13 @@
-use std::ops::Add;
+use std::ops::{Add, Mul};
 
-use crate::analysis::evaluate_hand::HandEvaluation;
+use crate::analysis::evaluate_hand::{
+    ALL_HAND_CATEGORIES, HandCategory, HandEvaluation, HandRanking, NUM_HAND_CATEGORIES,
+};
 use crate::cards::cardset::CardSet;
+use crate::cards::deck::Deck;
 use crate::parallelism::algorithms::{into_parallel_reduce, parallel_map};
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
 pub struct HandDistribution {
     pub straight_flushes: usize,
     pub four_of_a_kinds: usize,
@@ -94,42 +97,87 @@ impl HandDistribution {
     pub fn high_card_percentage(&self) -> f64 {
         (self.high_cards * 100) as f64 / self.total_num_hands() as f64
     }
+
+    pub fn count(&self, category: HandCategory) -> usize {
+        match category {
+            HandCategory::HighCard => self.high_cards,
+            HandCategory::Pair => self.pairs,
+            HandCategory::TwoPair => self.two_pairs,
+            HandCategory::ThreeOfAKind => self.three_of_a_kinds,
+            HandCategory::Straight => self.straights,
+            HandCategory::Flush => self.flushes,
+            HandCategory::FullHouse => self.full_houses,
+            HandCategory::FourOfAKind => self.four_of_a_kinds,
+            HandCategory::StraightFlush => self.straight_flushes,
+        }
+    }
+
+    pub fn percentage(&self, category: HandCategory) -> f64 {
+        (self.count(category) * 100) as f64 / self.total_num_hands() as f64
+    }
+
+    // The percentage of every kind of hand, from the strongest to the weakest under `ranking`.
+    pub fn percentages_by_rank(
+        &self,
+        ranking: HandRanking,
+    ) -> [(HandCategory, f64); NUM_HAND_CATEGORIES] {
+        let mut categories = ALL_HAND_CATEGORIES;
+        categories.sort_by_key(|c| std::cmp::Reverse(ranking.category_position(*c)));
+        categories.map(|c| (c, self.percentage(c)))
+    }
+
+    // The distribution of a single 5 to 7 card hand dealt from `deck`.
+    pub fn from_hand(hand: CardSet, deck: Deck) -> Self {
+        match HandEvaluation::evaluate_postflop_with_deck(hand, deck) {
+            Some(evaluation) => Self::from(evaluation),
+            None => {
+                let mut dist = Self::new();
+                dist.discarded_hands += 1;
+                dist
+            }
+        }
+    }
 }
 
-impl From<CardSet> for HandDistribution {
-    fn from(value: CardSet) -> Self {
+impl From<HandEvaluation> for HandDistribution {
+    fn from(value: HandEvaluation) -> Self {
         let mut dist = Self::new();
-        match HandEvaluation::evaluate_postflop(value) {
-            None => dist.discarded_hands += 1,
-            Some(HandEvaluation::StraightFlush { highest_rank: _ }) => dist.straight_flushes += 1,
-            Some(HandEvaluation::FourOfAKind { rank: _, kicker: _ }) => dist.four_of_a_kinds += 1,
-            Some(HandEvaluation::FullHouse { triple: _, pair: _ }) => dist.full_houses += 1,
-            Some(HandEvaluation::Flush {
+        match value {
+            HandEvaluation::StraightFlush { highest_rank: _ } => dist.straight_flushes += 1,
+            HandEvaluation::FourOfAKind { rank: _, kicker: _ } => dist.four_of_a_kinds += 1,
+            HandEvaluation::FullHouse { triple: _, pair: _ } => dist.full_houses += 1,
+            HandEvaluation::Flush {
                 ranks_sorted_desc: _,
-            }) => dist.flushes += 1,
-            Some(HandEvaluation::Straight { highest_rank: _ }) => dist.straights += 1,
-            Some(HandEvaluation::ThreeOfAKind {
+            } => dist.flushes += 1,
+            HandEvaluation::Straight { highest_rank: _ } => dist.straights += 1,
+            HandEvaluation::ThreeOfAKind {
                 rank: _,
                 kickers_sorted_desc: _,
-            }) => dist.three_of_a_kinds += 1,
-            Some(HandEvaluation::TwoPair {
+            } => dist.three_of_a_kinds += 1,
+            HandEvaluation::TwoPair {
                 higher_rank: _,
                 lower_rank: _,
                 kicker: _,
-            }) => dist.two_pairs += 1,
-            Some(HandEvaluation::Pair {
+            } => dist.two_pairs += 1,
+            HandEvaluation::Pair {
                 rank: _,
                 kickers_sorted_desc: _,
-            }) => dist.pairs += 1,
-            Some(HandEvaluation::HighCard {
+            } => dist.pairs += 1,
+            HandEvaluation::HighCard {
                 rank: _,
                 kickers_sorted_desc: _,
-            }) => dist.high_cards += 1,
+            } => dist.high_cards += 1,
         }
         dist
     }
 }
 
+impl From<CardSet> for HandDistribution {
+    fn from(value: CardSet) -> Self {
+        Self::from_hand(value, Deck::Standard)
+    }
+}
+
 impl Add<HandDistribution> for HandDistribution {
     type Output = HandDistribution;
 
@@ -149,10 +197,56 @@ impl Add<HandDistribution> for HandDistribution {
     }
 }
 
+impl Mul<usize> for HandDistribution {
+    type Output = HandDistribution;
+
+    fn mul(self, rhs: usize) -> Self::Output {
+        Self {
+            straight_flushes: self.straight_flushes * rhs,
+            four_of_a_kinds: self.four_of_a_kinds * rhs,
+            full_houses: self.full_houses * rhs,
+            flushes: self.flushes * rhs,
+            straights: self.straights * rhs,
+            three_of_a_kinds: self.three_of_a_kinds * rhs,
+            two_pairs: self.two_pairs * rhs,
+            pairs: self.pairs * rhs,
+            high_cards: self.high_cards * rhs,
+            discarded_hands: self.discarded_hands * rhs,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{analysis::search_space::all_seven_card_hands, cards::card::Card};
+    use crate::{analysis::search_space::fold_combinations, cards::card::Card};
+
+    #[test]
+    fn test_percentages_by_rank() {
+        let mut distribution = HandDistribution::new();
+        distribution.flushes = 3;
+        distribution.pairs = 1;
+
+        let standard = distribution.percentages_by_rank(HandRanking::Standard);
+        assert_eq!(standard[0], (HandCategory::StraightFlush, 0.0));
+        assert_eq!(standard[3], (HandCategory::Flush, 75.0));
+        assert_eq!(standard[7], (HandCategory::Pair, 25.0));
+        assert_eq!(standard[8].0, HandCategory::HighCard);
+
+        let short_deck = distribution.percentages_by_rank(HandRanking::ShortDeck {
+            trips_beat_straights: true,
+        });
+        let categories = short_deck.map(|(category, _)| category);
+        assert_eq!(
+            categories[2..6],
+            [
+                HandCategory::Flush,
+                HandCategory::FullHouse,
+                HandCategory::ThreeOfAKind,
+                HandCategory::Straight
+            ]
+        );
+    }
 
     #[test]
     fn test_hands_distribution() {
@@ -275,11 +369,17 @@ mod tests {
     #[test]
     #[ignore = "This test is computationally intensive. Run it with `cargo test -- --include-ignored`"]
     fn test_all_cards_distribution() {
-        let hands = all_seven_card_hands();
+        let distribution = fold_combinations(
+            CardSet::universe(),
+            7,
+            HandDistribution::new,
+            |distribution, hand| distribution + HandDistribution::from(hand),
+            |a, b| a + b,
+        );
 
         // From https://en.wikipedia.org/wiki/Poker_probability#7-card_poker_hands
         assert_eq!(
-            HandDistribution::evaluate(hands.as_slice()),
+            distribution,
             HandDistribution {
                 straight_flushes: 41_584,
                 four_of_a_kinds: 224_848,
@@ -293,6 +393,6 @@ mod tests {
                 discarded_hands: 0,
             }
         );
-        assert_eq!(hands.len(), 133_784_560);
+        assert_eq!(distribution.total_num_hands(), 133_784_560);
     }
 }
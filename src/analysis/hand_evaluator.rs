@@ -0,0 +1,27 @@
+use crate::analysis::evaluate_hand::HandEvaluation;
+use crate::cards::cardset::CardSet;
+
+// Something that can find the best 5-card hand out of 5 to 7 cards.
+pub trait HandEvaluator {
+    // A compact value where a stronger hand always compares greater than a weaker one.
+    type Strength: Ord + Copy + Into<HandEvaluation>;
+
+    // Returns None if the hand doesn't have between 5 and 7 cards.
+    fn strength(hand: CardSet) -> Option<Self::Strength>;
+
+    fn evaluate(hand: CardSet) -> Option<HandEvaluation> {
+        Self::strength(hand).map(Into::into)
+    }
+}
+
+// The original evaluator, which counts ranks and groups suits on every call and then matches
+// each kind of hand from strongest to weakest.
+pub struct MatchingEvaluator;
+
+impl HandEvaluator for MatchingEvaluator {
+    type Strength = HandEvaluation;
+
+    fn strength(hand: CardSet) -> Option<Self::Strength> {
+        HandEvaluation::evaluate_postflop(hand)
+    }
+}
@@ -0,0 +1,485 @@
+use std::fmt::Display;
+use std::ops::Add;
+
+use crate::analysis::evaluate_hand::HandEvaluation;
+use crate::analysis::math::SatisfactionFraction;
+use crate::cards::card::Rank;
+use crate::cards::cardset::CardSet;
+use crate::util::array::add_elementwise;
+
+// The value of a rank for ace-to-five lows, where aces are low.
+pub fn low_value(rank: Rank) -> u8 {
+    match rank {
+        Rank::Ace => 1,
+        r => r as u8 + 2,
+    }
+}
+
+// A bitmask with bit `v` set for each low value `v` from 1 to 8 among `cards`.
+fn low_value_mask(cards: CardSet) -> u16 {
+    cards
+        .iter_desc()
+        .map(|c| low_value(c.rank))
+        .filter(|v| *v <= 8)
+        .fold(0, |mask, v| mask | 1 << v)
+}
+
+// A qualifying 8-or-better low: five different ranks no higher than 8, with aces counting as 1
+// and straights and flushes ignored.
+//
+// The values are sorted in descending order and compared from the highest down, so unlike
+// `HandEvaluation`, a *smaller* `LowHand` is a better hand.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+pub struct LowHand {
+    pub values_sorted_desc: [u8; 5],
+}
+
+impl LowHand {
+    // `mask` must have exactly 5 of the bits 1 to 8 set.
+    fn from_mask(mask: u16) -> Self {
+        let mut values_sorted_desc = [0; 5];
+        let mut i = 0;
+        for value in (1..=8).rev() {
+            if mask & (1 << value) != 0 {
+                values_sorted_desc[i] = value;
+                i += 1;
+            }
+        }
+        Self { values_sorted_desc }
+    }
+
+    // The low made by exactly these five cards, if it qualifies.
+    pub fn of_five(cards: CardSet) -> Option<Self> {
+        let mask = low_value_mask(cards);
+        (cards.len() == 5 && mask.count_ones() == 5).then(|| Self::from_mask(mask))
+    }
+
+    // The best low made of exactly 2 hole cards and exactly 3 board cards, if any qualifies.
+    //
+    // Two sets of 5 low values compare the same way as their bitmasks do, so this looks for the
+    // smallest mask made of 2 hole values and the 3 lowest board values that don't repeat them.
+    pub fn evaluate_omaha(pocket: CardSet, board: CardSet) -> Option<Self> {
+        if board.len() < 3 || !pocket.disjoint_with(board) {
+            return None;
+        }
+
+        let hole = low_value_mask(pocket);
+        let board = low_value_mask(board);
+        if board.count_ones() < 3 {
+            return None;
+        }
+
+        let mut best = None;
+        for first in 1..=8 {
+            for second in first + 1..=8 {
+                let pair = (1 << first) | (1 << second);
+                if hole & pair != pair {
+                    continue;
+                }
+
+                let mut rest = board & !pair;
+                if rest.count_ones() < 3 {
+                    continue;
+                }
+                while rest.count_ones() > 3 {
+                    rest &= !(1 << (15 - rest.leading_zeros()));
+                }
+                let low = pair | rest;
+                best = Some(best.map_or(low, |b: u16| b.min(low)));
+            }
+        }
+        best.map(Self::from_mask)
+    }
+}
+
+impl Display for LowHand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, value) in self.values_sorted_desc.iter().enumerate() {
+            if i > 0 {
+                f.write_str("-")?;
+            }
+            match value {
+                1 => f.write_str("A")?,
+                v => write!(f, "{}", v)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// Which of the counts of a `HiLoOutcome` a single showdown lands in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HiLoResult {
+    Scoop,
+    Quartered,
+    HighAndLow,
+    HighOnly,
+    LowOnly,
+    Loss,
+}
+
+// What one player won in a single split-pot showdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HiLoShare {
+    // How many players split the high half, if a low qualified and this player is one of them.
+    pub high_winners: Option<usize>,
+    // How many players split the low half, if this player is one of them.
+    pub low_winners: Option<usize>,
+    // How many players split the whole pot, if no low qualified and this player is one of them.
+    pub pot_winners: Option<usize>,
+    pub result: HiLoResult,
+    pub made_low: bool,
+}
+
+impl HiLoShare {
+    // Each player's share of a single showdown given their best high hand and qualifying low.
+    pub fn of_showdown<'a>(
+        highs: &'a [HandEvaluation],
+        lows: &'a [Option<LowHand>],
+    ) -> impl Iterator<Item = HiLoShare> + 'a {
+        let best_high = highs.iter().max().unwrap();
+        let high_winners = highs.iter().filter(|h| *h == best_high).count();
+        let best_low = lows.iter().flatten().min();
+        let low_winners = lows
+            .iter()
+            .filter(|l| l.is_some() && l.as_ref() == best_low)
+            .count();
+
+        highs.iter().zip(lows).map(move |(high, low)| {
+            let wins_high = high == best_high;
+            let wins_low = low.is_some() && low.as_ref() == best_low;
+
+            let scoops = wins_high
+                && high_winners == 1
+                && (best_low.is_none() || (wins_low && low_winners == 1));
+            let quartered = best_low.is_some()
+                && ((wins_high && high_winners == 2 && !wins_low)
+                    || (wins_low && low_winners == 2 && !wins_high));
+
+            HiLoShare {
+                high_winners: (wins_high && best_low.is_some()).then_some(high_winners),
+                low_winners: wins_low.then_some(low_winners),
+                pot_winners: (wins_high && best_low.is_none()).then_some(high_winners),
+                result: if scoops {
+                    HiLoResult::Scoop
+                } else if quartered {
+                    HiLoResult::Quartered
+                } else if wins_high && wins_low {
+                    HiLoResult::HighAndLow
+                } else if wins_high {
+                    HiLoResult::HighOnly
+                } else if wins_low {
+                    HiLoResult::LowOnly
+                } else {
+                    HiLoResult::Loss
+                },
+                made_low: low.is_some(),
+            }
+        })
+    }
+}
+
+// How a player fared in a series of split-pot showdowns.
+//
+// Every showdown lands in exactly one of `scoops`, `high_only`, `low_only`, `quartered`,
+// `high_and_low` and `losses`. The `*_draws_with` arrays record how many ways each share was
+// split, which is what the pot share is computed from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HiLoOutcome {
+    // Won the high half alone (index 0) or with `i` other players, when a low qualified.
+    pub high_draws_with: Vec<usize>,
+    // Won the low half alone (index 0) or with `i` other players.
+    pub low_draws_with: Vec<usize>,
+    // Won the whole pot alone (index 0) or with `i` other players, when no low qualified.
+    pub pot_draws_with: Vec<usize>,
+    // Won the whole pot without sharing any of it.
+    pub scoops: usize,
+    // Won some of the high but none of the low, without scooping or being quartered.
+    pub high_only: usize,
+    // Won some of the low but none of the high, without being quartered.
+    pub low_only: usize,
+    // Shared one half with exactly one other player and won none of the other half.
+    pub quartered: usize,
+    // Won some of both halves without scooping.
+    pub high_and_low: usize,
+    pub losses: usize,
+    // Showdowns where this player had a qualifying low, whether or not it won.
+    pub lows_made: usize,
+}
+
+impl HiLoOutcome {
+    pub fn new(n_players: usize) -> Self {
+        Self {
+            high_draws_with: vec![0; n_players],
+            low_draws_with: vec![0; n_players],
+            pot_draws_with: vec![0; n_players],
+            scoops: 0,
+            high_only: 0,
+            low_only: 0,
+            quartered: 0,
+            high_and_low: 0,
+            losses: 0,
+            lows_made: 0,
+        }
+    }
+
+    // Adds `weight` showdowns where this player won `share`.
+    pub fn add_share(&mut self, share: HiLoShare, weight: usize) {
+        if let Some(winners) = share.high_winners {
+            self.high_draws_with[winners - 1] += weight;
+        }
+        if let Some(winners) = share.low_winners {
+            self.low_draws_with[winners - 1] += weight;
+        }
+        if let Some(winners) = share.pot_winners {
+            self.pot_draws_with[winners - 1] += weight;
+        }
+        if share.made_low {
+            self.lows_made += weight;
+        }
+        *match share.result {
+            HiLoResult::Scoop => &mut self.scoops,
+            HiLoResult::Quartered => &mut self.quartered,
+            HiLoResult::HighAndLow => &mut self.high_and_low,
+            HiLoResult::HighOnly => &mut self.high_only,
+            HiLoResult::LowOnly => &mut self.low_only,
+            HiLoResult::Loss => &mut self.losses,
+        } += weight;
+    }
+
+    pub fn total_hand_count(&self) -> usize {
+        self.scoops
+            + self.high_only
+            + self.low_only
+            + self.quartered
+            + self.high_and_low
+            + self.losses
+    }
+
+    fn fraction(&self, satisfying: usize) -> SatisfactionFraction {
+        SatisfactionFraction {
+            satisfying,
+            total: self.total_hand_count(),
+        }
+    }
+
+    pub fn scoop_ratio(&self) -> SatisfactionFraction {
+        self.fraction(self.scoops)
+    }
+
+    pub fn high_only_ratio(&self) -> SatisfactionFraction {
+        self.fraction(self.high_only)
+    }
+
+    pub fn low_only_ratio(&self) -> SatisfactionFraction {
+        self.fraction(self.low_only)
+    }
+
+    pub fn quartered_ratio(&self) -> SatisfactionFraction {
+        self.fraction(self.quartered)
+    }
+
+    pub fn high_and_low_ratio(&self) -> SatisfactionFraction {
+        self.fraction(self.high_and_low)
+    }
+
+    pub fn loss_ratio(&self) -> SatisfactionFraction {
+        self.fraction(self.losses)
+    }
+
+    pub fn low_made_ratio(&self) -> SatisfactionFraction {
+        self.fraction(self.lows_made)
+    }
+
+    // The average percentage of the pot this player wins, counting a half shared `n` ways as
+    // `1 / (2n)` of the pot.
+    pub fn pot_share_percentage(&self) -> f64 {
+        let share = |draws_with: &[usize], pot_fraction: f64| {
+            draws_with
+                .iter()
+                .enumerate()
+                .map(|(i, n)| *n as f64 * pot_fraction / (i + 1) as f64)
+                .sum::<f64>()
+        };
+
+        let won = share(&self.high_draws_with, 0.5)
+            + share(&self.low_draws_with, 0.5)
+            + share(&self.pot_draws_with, 1.0);
+        won * 100.0 / self.total_hand_count() as f64
+    }
+}
+
+// Both outcomes must be of showdowns with as many players.
+impl Add<HiLoOutcome> for HiLoOutcome {
+    type Output = HiLoOutcome;
+
+    fn add(mut self, rhs: HiLoOutcome) -> Self::Output {
+        add_elementwise(&mut self.high_draws_with, &rhs.high_draws_with);
+        add_elementwise(&mut self.low_draws_with, &rhs.low_draws_with);
+        add_elementwise(&mut self.pot_draws_with, &rhs.pot_draws_with);
+        self.scoops += rhs.scoops;
+        self.high_only += rhs.high_only;
+        self.low_only += rhs.low_only;
+        self.quartered += rhs.quartered;
+        self.high_and_low += rhs.high_and_low;
+        self.losses += rhs.losses;
+        self.lows_made += rhs.lows_made;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::search_space::Combinations;
+    use crate::cards::cardset::testing::cards;
+
+    fn low(values_sorted_desc: [u8; 5]) -> Option<LowHand> {
+        Some(LowHand { values_sorted_desc })
+    }
+
+    fn high_card(rank: Rank) -> HandEvaluation {
+        HandEvaluation::HighCard {
+            rank,
+            kickers_sorted_desc: [Rank::Six, Rank::Five, Rank::Four, Rank::Two],
+        }
+    }
+
+    #[test]
+    fn test_low_of_five() {
+        assert_eq!(
+            LowHand::of_five(cards("As 2d 3c 4h 5s")),
+            low([5, 4, 3, 2, 1])
+        );
+        assert_eq!(
+            LowHand::of_five(cards("8s 7d 6c 4h 2s")),
+            low([8, 7, 6, 4, 2])
+        );
+        // Too high, and paired.
+        assert_eq!(LowHand::of_five(cards("9s 7d 6c 4h 2s")), None);
+        assert_eq!(LowHand::of_five(cards("As Ad 3c 4h 5s")), None);
+        assert!(
+            LowHand::of_five(cards("6s 4d 3c 2h As")) < LowHand::of_five(cards("7s 4d 3c 2h As"))
+        );
+        assert!(
+            LowHand::of_five(cards("8s 5d 4c 3h 2s")) < LowHand::of_five(cards("8s 6d 3c 2h As"))
+        );
+    }
+
+    #[test]
+    fn test_omaha_low_uses_two_hole_cards() {
+        let board = cards("2c 4d 7h Ks Qd");
+
+        assert_eq!(
+            LowHand::evaluate_omaha(cards("As 3s Kh Kd"), board),
+            low([7, 4, 3, 2, 1])
+        );
+        // Only one low card in the pocket can't make a low.
+        assert_eq!(LowHand::evaluate_omaha(cards("As Ts Kh Kd"), board), None);
+        // Two of the same low rank in the pocket counts once.
+        assert_eq!(LowHand::evaluate_omaha(cards("As Ad Kh Kc"), board), None);
+        // A board with fewer than three low cards can't make a low.
+        assert_eq!(
+            LowHand::evaluate_omaha(cards("As 3s Kh Kc"), cards("2c 9d Th Ks Qd")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_omaha_low_matches_brute_force() {
+        let pocket = cards("As 2d 3c 3h");
+        let pool = cards("Ac Ah 2c 2h 4c 4h 5c 5h 6c 6h 7c 7h 8c 8h 9c 9h Kc");
+
+        for board in Combinations::new(pool, 5) {
+            let expected = Combinations::new(pocket, 2)
+                .flat_map(|hole| Combinations::new(board, 3).map(move |b| hole | b))
+                .filter_map(LowHand::of_five)
+                .min();
+            assert_eq!(LowHand::evaluate_omaha(pocket, board), expected);
+        }
+    }
+
+    // Each player's outcome of a single showdown.
+    fn outcomes_of_showdown(
+        highs: &[HandEvaluation],
+        lows: &[Option<LowHand>],
+    ) -> Vec<HiLoOutcome> {
+        HiLoShare::of_showdown(highs, lows)
+            .map(|share| {
+                let mut outcome = HiLoOutcome::new(highs.len());
+                outcome.add_share(share, 1);
+                outcome
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_low_hand_display() {
+        assert_eq!(low([8, 6, 4, 2, 1]).unwrap().to_string(), "8-6-4-2-A");
+    }
+
+    #[test]
+    fn test_scoop_without_a_low() {
+        let outcomes = outcomes_of_showdown(
+            &[high_card(Rank::Ace), high_card(Rank::King)],
+            &[None, None],
+        );
+
+        assert_eq!(outcomes[0].scoops, 1);
+        assert_eq!(outcomes[0].pot_draws_with, [1, 0]);
+        assert_eq!(outcomes[1].losses, 1);
+    }
+
+    #[test]
+    fn test_split_high_and_low() {
+        let outcomes = outcomes_of_showdown(
+            &[high_card(Rank::Ace), high_card(Rank::King)],
+            &[None, low([8, 7, 6, 5, 3])],
+        );
+
+        assert_eq!(outcomes[0].high_only, 1);
+        assert_eq!(outcomes[0].high_draws_with, [1, 0]);
+        assert_eq!(outcomes[1].low_only, 1);
+        assert_eq!(outcomes[1].low_draws_with, [1, 0]);
+        assert_eq!(outcomes[1].lows_made, 1);
+    }
+
+    #[test]
+    fn test_quartered() {
+        // The first player scoops the high and splits the low; the others split the low.
+        let outcomes = outcomes_of_showdown(
+            &[
+                high_card(Rank::Ace),
+                high_card(Rank::King),
+                high_card(Rank::Queen),
+            ],
+            &[low([7, 5, 4, 3, 2]), low([7, 5, 4, 3, 2]), None],
+        );
+
+        assert_eq!(outcomes[0].high_and_low, 1);
+        assert_eq!(outcomes[1].quartered, 1);
+        assert_eq!(outcomes[2].losses, 1);
+
+        let total = outcomes.into_iter().reduce(|a, b| a + b).unwrap();
+        assert_eq!(total.pot_share_percentage(), 100.0 / 3.0);
+    }
+
+    #[test]
+    fn test_pot_share() {
+        let outcome = HiLoOutcome {
+            high_draws_with: vec![2, 0],
+            low_draws_with: vec![0, 2],
+            pot_draws_with: vec![1, 1],
+            scoops: 1,
+            high_only: 1,
+            low_only: 0,
+            quartered: 0,
+            high_and_low: 1,
+            losses: 1,
+            lows_made: 2,
+        };
+
+        // 2 * 1/2 + 2 * 1/4 + 1 + 1/2 = 3 pots out of 4 showdowns.
+        assert_eq!(outcome.pot_share_percentage(), 75.0);
+    }
+}
@@ -1,5 +1,3 @@
-use crate::parallelism::algorithms::{into_parallel_reduce, parallel_map};
-
 pub fn n_choose_r(n: usize, r: usize) -> usize {
     if n == 0 {
         return 1;
@@ -30,31 +28,22 @@ impl SatisfactionFraction {
     pub fn percentage(&self) -> f64 {
         (self.satisfying * 100) as f64 / self.total as f64
     }
-}
 
-pub fn satisfaction_ratio<T: Send + Sync, P: Fn(&T) -> bool + Send + Sync>(
-    slice: &[T],
-    predicate: P,
-) -> SatisfactionFraction {
-    if slice.is_empty() {
-        return SatisfactionFraction {
-            satisfying: 0,
-            total: 0,
-        };
+    // The standard error of the proportion, treating each of the `total` items as an
+    // independent sample.
+    pub fn standard_error(&self) -> f64 {
+        let p = self.proportion();
+        (p * (1.0 - p) / self.total as f64).sqrt()
     }
 
-    let ratios = parallel_map(slice, |x| SatisfactionFraction {
-        satisfying: if predicate(x) { 1 } else { 0 },
-        total: 1,
-    });
-
-    into_parallel_reduce(ratios, |a, b| SatisfactionFraction {
-        satisfying: a.satisfying + b.satisfying,
-        total: a.total + b.total,
-    })
-    .unwrap()
+    // Half the width of the 95% confidence interval around the percentage.
+    pub fn margin_of_error_percentage(&self) -> f64 {
+        Z_SCORE_95 * self.standard_error() * 100.0
+    }
 }
 
+pub const Z_SCORE_95: f64 = 1.959964;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -71,54 +60,13 @@ mod tests {
     }
 
     #[test]
-    fn test_satisfaction_ratio_small() {
-        let nums = &[1, 1, 2, 3, 5, 8, 13];
-
-        assert_eq!(
-            satisfaction_ratio(nums, |x| x % 2 == 0),
-            SatisfactionFraction {
-                satisfying: 2,
-                total: 7
-            }
-        );
-    }
-
-    #[test]
-    fn test_satisfaction_ratio_large() {
-        let nums = (1..=10000).into_iter().collect::<Vec<i32>>();
-
-        assert_eq!(
-            satisfaction_ratio(nums.as_slice(), |x| *x <= 100),
-            SatisfactionFraction {
-                satisfying: 100,
-                total: 10000
-            }
-        );
-    }
-
-    #[test]
-    fn test_satisfaction_ratio_zero() {
-        let nums = (1..=10000).into_iter().collect::<Vec<i32>>();
-
-        assert_eq!(
-            satisfaction_ratio(nums.as_slice(), |x| *x > 10000),
-            SatisfactionFraction {
-                satisfying: 0,
-                total: 10000
-            }
-        );
-    }
-
-    #[test]
-    fn test_satisfaction_ratio_empty_set() {
-        let nums: &[i32] = &[];
+    fn test_standard_error() {
+        let fraction = SatisfactionFraction {
+            satisfying: 2500,
+            total: 10000,
+        };
 
-        assert_eq!(
-            satisfaction_ratio(nums, |_| true),
-            SatisfactionFraction {
-                satisfying: 0,
-                total: 0
-            }
-        );
+        assert!((fraction.standard_error() - 0.00433).abs() < 0.00001);
+        assert!((fraction.margin_of_error_percentage() - 0.8487).abs() < 0.001);
     }
 }
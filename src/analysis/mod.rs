@@ -1,9 +1,18 @@
+pub mod board_texture;
 pub mod evaluate_hand;
+pub mod game;
 pub mod hand_distribution;
+pub mod hand_evaluator;
+pub mod hi_lo;
 pub mod math;
+pub mod monte_carlo;
 pub mod odds;
 pub mod outcomes;
+pub mod outs;
+pub mod preflop_matrix;
 pub mod search_space;
+pub mod stud;
+pub mod table_evaluator;
 
 mod rank_counter;
 mod suit_grouping;
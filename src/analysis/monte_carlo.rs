@@ -0,0 +1,392 @@
+use crate::analysis::game::Game;
+use crate::analysis::math::n_choose_r;
+use crate::analysis::odds::{MAX_PLAYERS, ShowdownTally};
+use crate::analysis::outcomes::Outcome;
+use crate::cards::card::Card;
+use crate::cards::cardset::CardSet;
+use crate::cards::hand_range::HandRange;
+use crate::datastructures::stack_vec::StackVec;
+use crate::parallelism::algorithms::{into_parallel_reduce, parallel_map};
+use crate::util::random::Random;
+
+// The number of runouts sampled by one unit of work. Work is split into batches of this size
+// regardless of the number of threads, so a seed gives the same result on any machine.
+const BATCH_SIZE: usize = 10_000;
+
+// The number of batches run between checks of the standard error.
+const BATCHES_PER_ROUND: usize = 16;
+
+// Stops sampling to a standard error target even if the target hasn't been reached.
+pub const MAX_TRIALS: usize = 100_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StoppingRule {
+    Trials(usize),
+    // Keep sampling until the standard error of every player's win, draw and loss proportions
+    // is at most this value.
+    StandardError(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonteCarloConfig {
+    pub stopping_rule: StoppingRule,
+    pub seed: u64,
+}
+
+// A range prepared for weighted sampling. A range of every pocket that can be dealt is dealt
+// from the deck instead, since rejecting pockets that share a card almost never stops when many
+// players' pockets are unknown.
+enum SamplingRange {
+    Weighted {
+        pockets: Vec<CardSet>,
+        cumulative_weights: Vec<usize>,
+    },
+    // Every pocket of `size` cards, all with the same weight.
+    Random {
+        size: usize,
+    },
+}
+
+impl SamplingRange {
+    // `pool` is the cards that can still be dealt, so not `known`.
+    fn new(range: &HandRange, known: CardSet, pool: CardSet) -> Self {
+        let range = range.without(known);
+        let combos = range.combos();
+        if let Some(first) = combos.first() {
+            let size = first.pocket.len();
+            if combos.len() == n_choose_r(pool.len(), size)
+                && combos
+                    .iter()
+                    .all(|c| c.weight == first.weight && (c.pocket - pool).is_empty())
+            {
+                return SamplingRange::Random { size };
+            }
+        }
+
+        let mut total = 0;
+        let mut pockets = Vec::new();
+        let mut cumulative_weights = Vec::new();
+
+        for combo in combos {
+            total += combo.weight;
+            pockets.push(combo.pocket);
+            cumulative_weights.push(total);
+        }
+
+        SamplingRange::Weighted {
+            pockets,
+            cumulative_weights,
+        }
+    }
+}
+
+// Picks a pocket for every player, starting over whenever two weighted pockets share a card so
+// that every valid matchup is picked in proportion to the product of its weights. Random pockets
+// are then dealt from what's left of `deck`. Since every pocket has as many cards, that leaves
+// as many ways to deal them whatever the weighted pockets are, so the matchups are still picked
+// in proportion to their weights.
+fn sample_pockets(
+    ranges: &[SamplingRange],
+    deck: &mut [Card],
+    rng: &mut Random,
+) -> StackVec<CardSet, MAX_PLAYERS> {
+    'attempt: loop {
+        let mut used = CardSet::new();
+        let mut pockets = ranges
+            .iter()
+            .map(|_| CardSet::new())
+            .collect::<StackVec<CardSet, MAX_PLAYERS>>();
+
+        for (i, range) in ranges.iter().enumerate() {
+            if let SamplingRange::Weighted {
+                pockets: range_pockets,
+                cumulative_weights,
+            } = range
+            {
+                let total = *cumulative_weights.last().unwrap();
+                let target = rng.below(total);
+                let pocket = range_pockets[cumulative_weights.partition_point(|w| *w <= target)];
+                if !pocket.disjoint_with(used) {
+                    continue 'attempt;
+                }
+                used |= pocket;
+                pockets[i] = pocket;
+            }
+        }
+
+        for (i, range) in ranges.iter().enumerate() {
+            if let SamplingRange::Random { size } = range {
+                pockets[i] = sample_runout(deck, used, *size, rng);
+                used |= pockets[i];
+            }
+        }
+
+        return pockets;
+    }
+}
+
+// Draws `needed` cards from `deck` that aren't in `used` with a partial Fisher-Yates shuffle.
+pub fn sample_runout(deck: &mut [Card], used: CardSet, needed: usize, rng: &mut Random) -> CardSet {
+    let mut runout = CardSet::new();
+    let mut i = 0;
+
+    while runout.len() < needed {
+        let j = i + rng.below(deck.len() - i);
+        deck.swap(i, j);
+        if !used.has(deck[i]) {
+            runout += deck[i];
+            i += 1;
+        }
+    }
+
+    runout
+}
+
+fn sample_batch(
+    game: Game,
+    ranges: &[SamplingRange],
+    board: CardSet,
+    dead: CardSet,
+    seed: u64,
+    batch: usize,
+    trials: usize,
+) -> ShowdownTally {
+    let mut rng = Random::seeded_stream(seed, batch as u64);
+    let mut deck = (game.deck().cards() - board - dead)
+        .iter_desc()
+        .collect::<Vec<Card>>();
+    let needed = 5 - board.len();
+
+    let mut tally = ShowdownTally::new(ranges.len());
+
+    for _ in 0..trials {
+        let pockets = sample_pockets(ranges, deck.as_mut_slice(), &mut rng);
+        let used = pockets.iter().fold(board, |a, p| a | *p);
+        let full_board = board | sample_runout(deck.as_mut_slice(), used, needed, &mut rng);
+
+        tally.record(game, pockets.as_slice(), full_board, 1);
+    }
+
+    tally
+}
+
+fn max_standard_error(outcomes: &[Outcome]) -> f64 {
+    outcomes
+        .iter()
+        .flat_map(|o| [o.win_ratio(), o.draw_ratio(), o.loss_ratio()])
+        .map(|r| r.standard_error())
+        .fold(0.0, f64::max)
+}
+
+// Estimates the outcomes and hand distributions of a showdown by dealing random pockets from
+// each range and random runouts. Ranges of every pocket, such as `????`, are dealt like the
+// runout, so any number of players' pockets can be unknown. Every range must have at least one
+// pocket that can be dealt alongside the others, or this never returns.
+pub fn sample_odds(
+    game: Game,
+    ranges: &[HandRange],
+    board: CardSet,
+    dead: CardSet,
+    config: MonteCarloConfig,
+) -> ShowdownTally {
+    let pool = game.deck().cards() - board - dead;
+    let sampling_ranges = ranges
+        .iter()
+        .map(|r| SamplingRange::new(r, board | dead, pool))
+        .collect::<Vec<SamplingRange>>();
+
+    sample_in_batches(config, |batch, trials| {
+        sample_batch(
+            game,
+            &sampling_ranges,
+            board,
+            dead,
+            config.seed,
+            batch,
+            trials,
+        )
+    })
+}
+
+// Runs `sample_batch(batch, trials)` for batch 0, 1, ... until `config`'s stopping rule is met,
+// and adds up the results. Each batch must only depend on its index and the seed.
+pub fn sample_in_batches(
+    config: MonteCarloConfig,
+    sample_batch: impl Fn(usize, usize) -> ShowdownTally + Send + Sync,
+) -> ShowdownTally {
+    let max_trials = match config.stopping_rule {
+        StoppingRule::Trials(n) => n,
+        StoppingRule::StandardError(_) => MAX_TRIALS,
+    };
+
+    let mut result = None;
+    let mut trials_done = 0;
+    let mut next_batch = 0;
+
+    while trials_done < max_trials || result.is_none() {
+        let mut batches = Vec::new();
+        while batches.len() < BATCHES_PER_ROUND && trials_done < max_trials {
+            let trials = BATCH_SIZE.min(max_trials - trials_done);
+            batches.push((next_batch, trials));
+            next_batch += 1;
+            trials_done += trials;
+        }
+        // Without any trials, a single empty batch still gives the players' empty totals.
+        if batches.is_empty() {
+            batches.push((next_batch, 0));
+        }
+
+        let round = parallel_map(batches.as_slice(), |(batch, trials)| {
+            sample_batch(*batch, *trials)
+        });
+        let round = into_parallel_reduce(round, |a, b| a + b).unwrap();
+        let total = match result.take() {
+            Some(total) => total + round,
+            None => round,
+        };
+
+        if let StoppingRule::StandardError(target) = config.stopping_rule
+            && max_standard_error(&total.outcomes) <= target
+        {
+            return total;
+        }
+        result = Some(total);
+    }
+
+    result.unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::odds::RangeOddsCalculation;
+    use crate::cards::hand_range::random_range;
+
+    #[test]
+    fn test_sample_runout_avoids_used_cards() {
+        let mut rng = Random::seeded(7);
+        let mut deck = CardSet::universe().iter_desc().collect::<Vec<Card>>();
+        let used = CardSet::from(&[Card::ACE_SPADE, Card::ACE_HEART, Card::KING_CLUB]);
+
+        for _ in 0..1000 {
+            let runout = sample_runout(deck.as_mut_slice(), used, 5, &mut rng);
+            assert_eq!(runout.len(), 5);
+            assert!(runout.disjoint_with(used));
+        }
+    }
+
+    #[test]
+    fn test_sample_odds_is_reproducible() {
+        let ranges = [
+            HandRange::from(CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE])),
+            HandRange::from(CardSet::from(&[Card::QUEEN_CLUB, Card::QUEEN_DIAMOND])),
+        ];
+        let config = MonteCarloConfig {
+            stopping_rule: StoppingRule::Trials(2_000),
+            seed: 42,
+        };
+
+        let a = sample_odds(
+            Game::Holdem,
+            &ranges,
+            CardSet::new(),
+            CardSet::new(),
+            config,
+        );
+        let b = sample_odds(
+            Game::Holdem,
+            &ranges,
+            CardSet::new(),
+            CardSet::new(),
+            config,
+        );
+
+        assert_eq!(a, b);
+        assert_eq!(a.outcomes[0].total_hand_count(), 2_000);
+        assert_eq!(a.hand_distributions[1].total_num_hands(), 2_000);
+    }
+
+    #[test]
+    fn test_sample_odds_on_the_river_is_exact() {
+        let ranges = [
+            HandRange::from(CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE])),
+            HandRange::from(CardSet::from(&[Card::QUEEN_CLUB, Card::QUEEN_DIAMOND])),
+        ];
+        let board = CardSet::from(&[
+            Card::QUEEN_SPADE,
+            Card::SEVEN_HEART,
+            Card::TWO_DIAMOND,
+            Card::NINE_CLUB,
+            Card::KING_CLUB,
+        ]);
+        let config = MonteCarloConfig {
+            stopping_rule: StoppingRule::Trials(1_000),
+            seed: 1,
+        };
+
+        let outcomes = sample_odds(Game::Holdem, &ranges, board, CardSet::new(), config).outcomes;
+
+        assert_eq!(outcomes[0].losses, outcomes[0].total_hand_count());
+        assert_eq!(outcomes[1].draws_with[0], outcomes[1].total_hand_count());
+    }
+
+    #[test]
+    fn test_sample_odds_deals_random_pockets() {
+        let aks = HandRange::from(CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE]));
+        let board = CardSet::from(&[
+            Card::QUEEN_SPADE,
+            Card::SEVEN_HEART,
+            Card::TWO_DIAMOND,
+            Card::NINE_CLUB,
+        ]);
+        let config = MonteCarloConfig {
+            stopping_rule: StoppingRule::Trials(1_000),
+            seed: 5,
+        };
+
+        // Picking 22 random pockets that don't share a card would practically never succeed.
+        let mut ranges = [(); 23].map(|_| random_range(2));
+        ranges[0] = aks.clone();
+        let tally = sample_odds(Game::Holdem, &ranges, board, CardSet::new(), config);
+        assert_eq!(tally.outcomes[0].total_hand_count(), 1_000);
+
+        // Dealt pockets are as likely as picked ones.
+        let ranges = [aks, random_range(2)];
+        let exact =
+            RangeOddsCalculation::calculate(Game::Holdem, &ranges, board, CardSet::new()).unwrap();
+        let config = MonteCarloConfig {
+            stopping_rule: StoppingRule::Trials(20_000),
+            seed: 5,
+        };
+        let win = sample_odds(Game::Holdem, &ranges, board, CardSet::new(), config).outcomes[0]
+            .win_ratio();
+        let expected = exact[0].outcome.win_ratio().percentage();
+        assert!((win.percentage() - expected).abs() < 4.0 * win.standard_error() * 100.0);
+    }
+
+    #[test]
+    #[ignore = "This test is computationally intensive. Run it with `cargo test -- --include-ignored`"]
+    fn test_sample_odds_converges() {
+        let ranges = [
+            HandRange::from(CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE])),
+            HandRange::from(CardSet::from(&[Card::QUEEN_CLUB, Card::QUEEN_DIAMOND])),
+        ];
+        let config = MonteCarloConfig {
+            stopping_rule: StoppingRule::StandardError(0.001),
+            seed: 3,
+        };
+
+        let outcomes = sample_odds(
+            Game::Holdem,
+            &ranges,
+            CardSet::new(),
+            CardSet::new(),
+            config,
+        )
+        .outcomes;
+        let win = outcomes[0].win_ratio();
+
+        // From https://www.pokernews.com/poker-tools/poker-odds-calculator.htm
+        assert!((win.percentage() - 46.02).abs() < 4.0 * win.standard_error() * 100.0);
+    }
+}
@@ -1,43 +1,165 @@
+use std::ops::Add;
+
+use crate::analysis::evaluate_hand::{HandEvaluation, HandRanking};
+use crate::analysis::game::Game;
 use crate::analysis::hand_distribution::HandDistribution;
-use crate::analysis::outcomes::{ArrayOutcome, Outcome};
-use crate::analysis::search_space::{combinations, undealt_cards};
+use crate::analysis::hi_lo::{HiLoOutcome, HiLoShare};
+use crate::analysis::monte_carlo::{MonteCarloConfig, sample_odds};
+use crate::analysis::outcomes::{Outcome, record_showdown};
+use crate::analysis::search_space::{
+    check_ranges, count_weighted_runouts, fold_canonical_combinations, for_each_matchup,
+    undealt_cards,
+};
 use crate::cards::cardset::CardSet;
-use crate::parallelism::algorithms::{into_parallel_map, parallel_map};
-use crate::util::array::{array_map, indexes, into_array_map};
+use crate::cards::hand_range::HandRange;
+use crate::datastructures::stack_vec::StackVec;
+use crate::error::PopError;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct ArrayOddsCalculation<const N_PLAYERS: usize> {
-    pub pocket: CardSet,
-    pub outcome: ArrayOutcome<N_PLAYERS>,
-    pub hand_distribution: HandDistribution,
+// The most players a showdown can have. Their pockets and a board use up all but one card of
+// the deck.
+pub const MAX_PLAYERS: usize = 23;
+
+// Running totals of the outcomes and hands of a series of showdowns between the same players.
+// Each showdown's hands are kept on the stack, so there can be at most `MAX_PLAYERS`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct ShowdownTally {
+    pub outcomes: Vec<Outcome>,
+    pub hand_distributions: Vec<HandDistribution>,
+    pub hi_lo: Vec<HiLoOutcome>,
 }
 
-impl<const N_PLAYERS: usize> ArrayOddsCalculation<N_PLAYERS> {
-    pub fn calculate(
-        pockets: &[CardSet; N_PLAYERS],
+impl ShowdownTally {
+    pub fn new(n_players: usize) -> Self {
+        Self {
+            outcomes: vec![Outcome::new(n_players); n_players],
+            hand_distributions: vec![HandDistribution::new(); n_players],
+            hi_lo: vec![HiLoOutcome::new(n_players); n_players],
+        }
+    }
+
+    // Tallies every runout of a showdown between `pockets`, counting each one `weight` times.
+    // Runouts are evaluated as they are enumerated, so only the running totals are kept in
+    // memory. Runouts that are the same up to a suit permutation fixing every pocket and the
+    // board are evaluated once and counted as many times as there are such runouts.
+    pub fn enumerate(
+        game: Game,
+        pockets: &[CardSet],
         board: CardSet,
-    ) -> [ArrayOddsCalculation<N_PLAYERS>; N_PLAYERS] {
-        let undealt = undealt_cards(pockets, board);
+        dead: CardSet,
+        weight: usize,
+    ) -> Result<Self, PopError> {
+        check_player_count(pockets.len())?;
+        let undealt = undealt_cards(pockets, board, dead, game)?;
+        if undealt.len() < 5 - board.len() {
+            return Err(PopError::NotEnoughCards {
+                needed: 5 - board.len(),
+                left: undealt.len(),
+            });
+        }
+        let mut fixed = pockets.to_vec();
+        fixed.push(board);
+        fixed.push(dead);
 
-        let runouts = combinations(undealt, 5 - board.len());
-        let boards = into_parallel_map(runouts, |x| x | board);
+        Ok(fold_canonical_combinations(
+            undealt,
+            5 - board.len(),
+            fixed.as_slice(),
+            || Self::new(pockets.len()),
+            |mut tally, runout, runout_weight| {
+                tally.record(game, pockets, runout | board, runout_weight * weight);
+                tally
+            },
+            |a, b| a + b,
+        ))
+    }
 
-        let hand_distributions = array_map(pockets, |pocket| {
-            let hands = parallel_map(boards.as_slice(), |runout| *runout | *pocket);
-            HandDistribution::evaluate(hands.as_slice())
-        });
+    // Adds `weight` showdowns between `pockets` on the complete board `full_board`.
+    pub fn record(&mut self, game: Game, pockets: &[CardSet], full_board: CardSet, weight: usize) {
+        let hand_evals = pockets
+            .iter()
+            .map(|pocket| game.evaluate(*pocket, full_board).unwrap())
+            .collect::<StackVec<HandEvaluation, MAX_PLAYERS>>();
+        match game.hand_ranking() {
+            HandRanking::Standard => self.record_outcomes(hand_evals.as_slice(), weight),
+            ranking => {
+                let keys = hand_evals
+                    .iter()
+                    .map(|e| ranking.key(*e))
+                    .collect::<StackVec<(u8, HandEvaluation), MAX_PLAYERS>>();
+                self.record_outcomes(keys.as_slice(), weight)
+            }
+        }
+        self.record_hand_distributions(hand_evals.as_slice(), weight);
+
+        if game.is_hi_lo() {
+            let mut lows = [None; MAX_PLAYERS];
+            for (low, pocket) in lows.iter_mut().zip(pockets) {
+                *low = game.evaluate_low(*pocket, full_board);
+            }
+            let shares = HiLoShare::of_showdown(hand_evals.as_slice(), &lows[..pockets.len()]);
+            for (outcome, share) in self.hi_lo.iter_mut().zip(shares) {
+                outcome.add_share(share, weight);
+            }
+        }
+    }
 
-        if hand_distributions.iter().any(|h| !h.is_complete()) {
-            panic!("Internal error computing the hand distributions.");
+    // Adds `weight` showdowns won by the greatest of `strengths`.
+    pub fn record_outcomes<S: Ord>(&mut self, strengths: &[S], weight: usize) {
+        record_showdown(&mut self.outcomes, strengths, weight);
+    }
+
+    pub fn record_hand_distributions(&mut self, hand_evals: &[HandEvaluation], weight: usize) {
+        for (distribution, eval) in self.hand_distributions.iter_mut().zip(hand_evals) {
+            *distribution = *distribution + HandDistribution::from(*eval) * weight;
         }
+    }
 
-        let outcomes = ArrayOutcome::evaluate(pockets, boards.as_slice());
+    // Each player's outcome, hand distribution and split-pot outcome, in order.
+    pub fn into_players(self) -> impl Iterator<Item = (Outcome, HandDistribution, HiLoOutcome)> {
+        self.outcomes
+            .into_iter()
+            .zip(self.hand_distributions)
+            .zip(self.hi_lo)
+            .map(|((outcome, hand_distribution), hi_lo)| (outcome, hand_distribution, hi_lo))
+    }
+}
+
+// Both tallies must be of showdowns with as many players.
+impl Add<ShowdownTally> for ShowdownTally {
+    type Output = ShowdownTally;
 
-        into_array_map(indexes::<N_PLAYERS>(), |i| Self {
-            pocket: pockets[i],
-            outcome: outcomes[i],
-            hand_distribution: hand_distributions[i],
-        })
+    fn add(self, rhs: ShowdownTally) -> Self::Output {
+        Self {
+            outcomes: self
+                .outcomes
+                .into_iter()
+                .zip(rhs.outcomes)
+                .map(|(a, b)| a + b)
+                .collect(),
+            hand_distributions: self
+                .hand_distributions
+                .into_iter()
+                .zip(rhs.hand_distributions)
+                .map(|(a, b)| a + b)
+                .collect(),
+            hi_lo: self
+                .hi_lo
+                .into_iter()
+                .zip(rhs.hi_lo)
+                .map(|(a, b)| a + b)
+                .collect(),
+        }
+    }
+}
+
+// Fails unless a showdown with `n_players` players can be calculated.
+pub fn check_player_count(n_players: usize) -> Result<(), PopError> {
+    if n_players < 2 {
+        Err(PopError::TooFewPlayers { min: 2 })
+    } else if n_players > MAX_PLAYERS {
+        Err(PopError::TooManyPlayers { max: MAX_PLAYERS })
+    } else {
+        Ok(())
     }
 }
 
@@ -46,34 +168,136 @@ pub struct OddsCalculation {
     pub pocket: CardSet,
     pub outcome: Outcome,
     pub hand_distribution: HandDistribution,
+    pub hi_lo: HiLoOutcome,
 }
 
 impl OddsCalculation {
-    pub fn calculate<const N_PLAYERS: usize>(
-        pockets: &[CardSet; N_PLAYERS],
+    pub fn calculate(
+        game: Game,
+        pockets: &[CardSet],
         board: CardSet,
+        dead: CardSet,
+    ) -> Result<Vec<Self>, PopError> {
+        let tally = ShowdownTally::enumerate(game, pockets, board, dead, 1)?;
+        Ok(pockets
+            .iter()
+            .zip(tally.into_players())
+            .map(|(pocket, (outcome, hand_distribution, hi_lo))| Self {
+                pocket: *pocket,
+                outcome,
+                hand_distribution,
+                hi_lo,
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CalculationMethod {
+    Exhaustive,
+    MonteCarlo { seed: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeOddsCalculation {
+    pub range: HandRange,
+    pub outcome: Outcome,
+    pub hand_distribution: HandDistribution,
+    // The split-pot outcome, for games with a low half.
+    pub hi_lo: Option<HiLoOutcome>,
+    pub method: CalculationMethod,
+}
+
+impl RangeOddsCalculation {
+    fn from_tally(
+        game: Game,
+        ranges: &[HandRange],
+        tally: ShowdownTally,
+        method: CalculationMethod,
     ) -> Vec<Self> {
-        ArrayOddsCalculation::calculate(pockets, board)
-            .into_iter()
-            .map(Self::from)
+        ranges
+            .iter()
+            .zip(tally.into_players())
+            .map(|(range, (outcome, hand_distribution, hi_lo))| Self {
+                range: range.clone(),
+                outcome,
+                hand_distribution,
+                hi_lo: game.is_hi_lo().then_some(hi_lo),
+                method,
+            })
             .collect()
     }
-}
 
-impl<const N_PLAYERS: usize> From<ArrayOddsCalculation<N_PLAYERS>> for OddsCalculation {
-    fn from(value: ArrayOddsCalculation<N_PLAYERS>) -> Self {
-        Self {
-            pocket: value.pocket,
-            outcome: Outcome::from(value.outcome),
-            hand_distribution: value.hand_distribution,
+    // Sums the odds of every matchup the ranges can produce, weighting each by the product of
+    // its pockets' weights. Combos that conflict with the board, the dead cards or each other are
+    // skipped.
+    pub fn calculate(
+        game: Game,
+        ranges: &[HandRange],
+        board: CardSet,
+        dead: CardSet,
+    ) -> Result<Vec<Self>, PopError> {
+        check_player_count(ranges.len())?;
+        check_ranges(game, ranges, board, dead)?;
+        if count_weighted_runouts(game, ranges, board, dead) > usize::MAX as u128 {
+            return Err(PopError::WeightOverflow);
+        }
+        let mut tally = ShowdownTally::new(ranges.len());
+
+        // Only a matchup that leaves too few cards for the board can fail after the checks above.
+        // The weight check means every matchup's weight fits in a `usize`.
+        let mut error = None;
+        for_each_matchup(ranges, board | dead, |pockets, weight| {
+            let weight = weight as usize;
+            match ShowdownTally::enumerate(game, pockets, board, dead, weight) {
+                Ok(matchup) => tally = std::mem::take(&mut tally) + matchup,
+                Err(e) => {
+                    error.get_or_insert(e);
+                }
+            }
+        });
+        if let Some(e) = error {
+            return Err(e);
         }
+
+        Ok(Self::from_tally(
+            game,
+            ranges,
+            tally,
+            CalculationMethod::Exhaustive,
+        ))
+    }
+
+    // Estimates the odds by sampling random matchups and runouts instead of enumerating them.
+    // The outcome counts are the number of sampled runouts.
+    pub fn sample(
+        game: Game,
+        ranges: &[HandRange],
+        board: CardSet,
+        dead: CardSet,
+        config: MonteCarloConfig,
+    ) -> Result<Vec<Self>, PopError> {
+        check_player_count(ranges.len())?;
+        check_ranges(game, ranges, board, dead)?;
+        let tally = sample_odds(game, ranges, board, dead, config);
+
+        Ok(Self::from_tally(
+            game,
+            ranges,
+            tally,
+            CalculationMethod::MonteCarlo { seed: config.seed },
+        ))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cards::card::Card;
+    use crate::analysis::monte_carlo::StoppingRule;
+    use crate::analysis::search_space::Combinations;
+    use crate::cards::card::{Card, Rank};
+    use crate::cards::cardset::testing::cards;
+    use crate::cards::hand_range::{WeightedPocket, pair_combos};
 
     fn assert_roughly_eq(a: f64, b: f64) {
         assert_eq!(format!("{:.2}", a), format!("{:.2}", b));
@@ -85,7 +309,9 @@ mod tests {
         let aks = CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE]);
         let qq = CardSet::from(&[Card::QUEEN_CLUB, Card::QUEEN_DIAMOND]);
 
-        let odds = ArrayOddsCalculation::calculate(&[aks, qq], CardSet::new());
+        let odds =
+            OddsCalculation::calculate(Game::Holdem, &[aks, qq], CardSet::new(), CardSet::new())
+                .unwrap();
         let aks_odds = &odds[0];
         let qq_odds = &odds[1];
 
@@ -128,7 +354,13 @@ mod tests {
         let jj = CardSet::from(&[Card::JACK_CLUB, Card::JACK_DIAMOND]);
         let s98 = CardSet::from(&[Card::NINE_HEART, Card::EIGHT_HEART]);
 
-        let odds = ArrayOddsCalculation::calculate(&[aks, jj, s98], CardSet::new());
+        let odds = OddsCalculation::calculate(
+            Game::Holdem,
+            &[aks, jj, s98],
+            CardSet::new(),
+            CardSet::new(),
+        )
+        .unwrap();
         let aks_odds = &odds[0];
         let jj_odds = &odds[1];
         let s98_odds = &odds[2];
@@ -186,10 +418,13 @@ mod tests {
         let kqs = CardSet::from(&[Card::KING_SPADE, Card::QUEEN_SPADE]);
         let tt = CardSet::from(&[Card::TEN_CLUB, Card::TEN_DIAMOND]);
 
-        let odds = ArrayOddsCalculation::calculate(
+        let odds = OddsCalculation::calculate(
+            Game::Holdem,
             &[kqs, tt],
             CardSet::from(&[Card::JACK_SPADE, Card::TEN_SPADE, Card::SIX_DIAMOND]),
-        );
+            CardSet::new(),
+        )
+        .unwrap();
         let kqs_odds = &odds[0];
         let tt_odds = &odds[1];
 
@@ -231,7 +466,9 @@ mod tests {
         let aks = CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE]);
         let qq = CardSet::from(&[Card::QUEEN_CLUB, Card::QUEEN_DIAMOND]);
 
-        let odds = OddsCalculation::calculate(&[aks, qq], CardSet::new());
+        let odds =
+            OddsCalculation::calculate(Game::Holdem, &[aks, qq], CardSet::new(), CardSet::new())
+                .unwrap();
         assert_eq!(odds.len(), 2);
 
         let aks_odds = &odds[0];
@@ -268,4 +505,350 @@ mod tests {
         assert_roughly_eq(qq_odds.hand_distribution.pair_percentage(), 35.2);
         assert_roughly_eq(qq_odds.hand_distribution.high_card_percentage(), 0.0);
     }
+
+    #[test]
+    fn test_range_odds_on_the_river() {
+        let aks = CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE]);
+        let board = CardSet::from(&[
+            Card::QUEEN_SPADE,
+            Card::SEVEN_HEART,
+            Card::TWO_DIAMOND,
+            Card::NINE_CLUB,
+            Card::KING_CLUB,
+        ]);
+
+        // QQ has 3 combos left (flopped set, wins) and JJ has 6 (loses to top pair).
+        let qq_or_jj = HandRange::new(
+            "QQ,JJ".to_string(),
+            pair_combos(Rank::Queen)
+                .into_iter()
+                .chain(pair_combos(Rank::Jack))
+                .map(|pocket| WeightedPocket { pocket, weight: 1 })
+                .collect(),
+        );
+
+        let odds = RangeOddsCalculation::calculate(
+            Game::Holdem,
+            &[HandRange::from(aks), qq_or_jj],
+            board,
+            CardSet::new(),
+        )
+        .unwrap();
+
+        assert_eq!(odds[0].outcome.draws_with, vec![6, 0]);
+        assert_eq!(odds[0].outcome.losses, 3);
+        assert_eq!(odds[1].outcome.draws_with, vec![3, 0]);
+        assert_eq!(odds[1].outcome.losses, 6);
+        assert_eq!(odds[1].hand_distribution.three_of_a_kinds, 3);
+        assert_eq!(odds[1].hand_distribution.pairs, 6);
+    }
+
+    #[test]
+    fn test_weighted_range_odds_on_the_river() {
+        let aks = CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE]);
+        let board = CardSet::from(&[
+            Card::QUEEN_SPADE,
+            Card::SEVEN_HEART,
+            Card::TWO_DIAMOND,
+            Card::NINE_CLUB,
+            Card::KING_CLUB,
+        ]);
+
+        let weighted = HandRange::new(
+            "QQ:0.5,JJ".to_string(),
+            pair_combos(Rank::Queen)
+                .into_iter()
+                .map(|pocket| WeightedPocket { pocket, weight: 50 })
+                .chain(
+                    pair_combos(Rank::Jack)
+                        .into_iter()
+                        .map(|pocket| WeightedPocket {
+                            pocket,
+                            weight: 100,
+                        }),
+                )
+                .collect(),
+        );
+
+        let odds = RangeOddsCalculation::calculate(
+            Game::Holdem,
+            &[HandRange::from(aks), weighted],
+            board,
+            CardSet::new(),
+        )
+        .unwrap();
+
+        assert_eq!(odds[0].outcome.draws_with, vec![12, 0]);
+        assert_eq!(odds[0].outcome.losses, 3);
+        assert_eq!(odds[0].outcome.win_ratio().percentage(), 80.0);
+    }
+
+    // The best Omaha hand found by trying every 2 hole cards with every 3 board cards.
+    fn reference_omaha_eval(pocket: CardSet, board: CardSet) -> HandEvaluation {
+        let mut best = None;
+        for (i, h1) in pocket.iter_desc().enumerate() {
+            for h2 in pocket.iter_desc().skip(i + 1) {
+                for (j, b1) in board.iter_desc().enumerate() {
+                    for (k, b2) in board.iter_desc().enumerate().skip(j + 1) {
+                        for b3 in board.iter_desc().skip(k + 1) {
+                            let hand = CardSet::from(&[h1, h2, b1, b2, b3]);
+                            best = best.max(HandEvaluation::evaluate_postflop(hand));
+                        }
+                    }
+                }
+            }
+        }
+        best.unwrap()
+    }
+
+    fn reference_omaha_outcomes(pockets: &[CardSet], board: CardSet) -> Vec<Outcome> {
+        let undealt = undealt_cards(pockets, board, CardSet::new(), Game::Omaha).unwrap();
+        let mut tally = ShowdownTally::new(pockets.len());
+        for runout in Combinations::new(undealt, 5 - board.len()) {
+            let evals = pockets
+                .iter()
+                .map(|p| reference_omaha_eval(*p, board | runout))
+                .collect::<Vec<HandEvaluation>>();
+            tally.record_outcomes(&evals, 1);
+        }
+        tally.outcomes
+    }
+
+    #[test]
+    fn test_omaha_odds_on_the_river() {
+        // Hold'em would give the first player a flush, but in Omaha they hold only one spade and
+        // lose to a straight made with exactly two hole cards.
+        let pockets = [cards("As Kh Qh 3d"), cards("Js 7d 4h 4c")];
+        let board = cards("Ts 9s 8s 2s 2c");
+
+        let omaha =
+            OddsCalculation::calculate(Game::Omaha, &pockets, board, CardSet::new()).unwrap();
+        assert_eq!(omaha[0].outcome.losses, 1);
+        assert_eq!(omaha[1].outcome.draws_with, [1, 0]);
+        assert_eq!(omaha[1].hand_distribution.straights, 1);
+        assert_eq!(omaha[0].hand_distribution.pairs, 1);
+    }
+
+    #[test]
+    fn test_dead_cards_are_never_dealt() {
+        let pockets = [cards("Ah Kh"), cards("Qs Qd")];
+        let board = cards("2c 3d 4h");
+        let dead = cards("Qc Qh");
+
+        let odds = OddsCalculation::calculate(Game::Holdem, &pockets, board, dead).unwrap();
+        // 52 - 4 - 3 - 2 cards are left for the turn and river.
+        assert_eq!(odds[0].outcome.total_hand_count(), 43 * 42 / 2);
+        assert_eq!(odds[1].hand_distribution.three_of_a_kinds, 0);
+
+        let ranges = pockets.map(HandRange::from);
+        let range_odds =
+            RangeOddsCalculation::calculate(Game::Holdem, &ranges, board, dead).unwrap();
+        assert_eq!(range_odds[1].outcome, odds[1].outcome);
+    }
+
+    #[test]
+    fn test_omaha_hi_lo_split_on_the_river() {
+        // The first player has the nut low and the second a straight for the high.
+        let pockets = [cards("As 2s Kh Kd"), cards("3c 4c 7h 9s")];
+        let board = cards("5d 6h 8c Kc Jd");
+
+        let odds =
+            OddsCalculation::calculate(Game::OmahaHiLo, &pockets, board, CardSet::new()).unwrap();
+        assert_eq!(odds[0].outcome.losses, 1);
+        assert_eq!(odds[0].hi_lo.low_only, 1);
+        assert_eq!(odds[0].hi_lo.low_draws_with, [1, 0]);
+        assert_eq!(odds[1].hi_lo.high_only, 1);
+        assert_eq!(odds[1].hi_lo.high_draws_with, [1, 0]);
+        assert!(odds.iter().all(|o| o.hi_lo.lows_made == 1));
+        assert_eq!(odds[0].hi_lo.pot_share_percentage(), 50.0);
+
+        let high_only =
+            OddsCalculation::calculate(Game::Omaha, &pockets, board, CardSet::new()).unwrap();
+        assert_eq!(high_only[0].hi_lo, HiLoOutcome::new(2));
+    }
+
+    #[test]
+    fn test_omaha_hi_lo_pot_shares_add_up() {
+        let pockets = [cards("Ah 2h Kc Qd"), cards("3s 4s Jd Jc")];
+        let board = cards("5c 8d Th");
+
+        let odds = RangeOddsCalculation::calculate(
+            Game::OmahaHiLo,
+            &pockets.map(HandRange::from),
+            board,
+            CardSet::new(),
+        )
+        .unwrap();
+        let hi_lo = odds
+            .iter()
+            .map(|o| o.hi_lo.clone().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_roughly_eq(hi_lo.iter().map(|o| o.pot_share_percentage()).sum(), 100.0);
+        assert!(hi_lo.iter().all(|o| o.total_hand_count() == 41 * 40 / 2));
+    }
+
+    #[test]
+    fn test_short_deck_flush_beats_full_house() {
+        let pockets = [cards("Ah Kh"), cards("Qc Qd")];
+        let board = cards("Qh 9h 6h Ts Tc");
+        let short_deck = Game::ShortDeck {
+            trips_beat_straights: false,
+        };
+
+        let holdem =
+            OddsCalculation::calculate(Game::Holdem, &pockets, board, CardSet::new()).unwrap();
+        assert_eq!(holdem[1].outcome.draws_with, [1, 0]);
+
+        let odds = OddsCalculation::calculate(short_deck, &pockets, board, CardSet::new()).unwrap();
+        assert_eq!(odds[0].outcome.draws_with, [1, 0]);
+        assert_eq!(odds[0].hand_distribution.flushes, 1);
+        assert_eq!(odds[1].hand_distribution.full_houses, 1);
+    }
+
+    #[test]
+    fn test_short_deck_odds_on_the_flop() {
+        let pockets = [cards("As 9d"), cards("Kc Kd")];
+        let board = cards("6h 7c 8s");
+        let short_deck = Game::ShortDeck {
+            trips_beat_straights: false,
+        };
+
+        let odds = OddsCalculation::calculate(short_deck, &pockets, board, CardSet::new()).unwrap();
+
+        // 29 cards are left in the short deck, so there are 29 choose 2 turns and rivers.
+        assert_eq!(odds[0].outcome.total_hand_count(), 406);
+        // A-6-7-8-9 is already a straight.
+        assert_eq!(odds[0].hand_distribution.high_cards, 0);
+        assert_eq!(odds[0].hand_distribution.pairs, 0);
+    }
+
+    #[test]
+    fn test_omaha_odds_on_the_turn() {
+        let pockets = [cards("Ah Ad Kh Qc"), cards("Js Ts 9c 8c")];
+        let board = cards("Qh Jh 2c 3s");
+
+        let odds =
+            OddsCalculation::calculate(Game::Omaha, &pockets, board, CardSet::new()).unwrap();
+        let expected = reference_omaha_outcomes(&pockets, board);
+
+        assert_eq!(
+            odds.iter().map(|o| o.outcome.clone()).collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(odds[0].outcome.total_hand_count(), 40);
+        assert!(
+            odds.iter()
+                .all(|o| o.hand_distribution.total_num_hands() == 40)
+        );
+    }
+
+    #[test]
+    fn test_plo5_three_way_odds_on_the_flop() {
+        let pockets = [
+            cards("Ah As Kd Qd 7c"),
+            cards("Jc Tc 9h 8h 2d"),
+            cards("6s 6d 5h 4h Kc"),
+        ];
+        let board = cards("Th 9d 3h");
+
+        let odds =
+            OddsCalculation::calculate(Game::Omaha, &pockets, board, CardSet::new()).unwrap();
+        let expected = reference_omaha_outcomes(&pockets, board);
+
+        assert_eq!(
+            odds.iter().map(|o| o.outcome.clone()).collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(odds[0].outcome.total_hand_count(), 561);
+    }
+
+    #[test]
+    fn test_odds_with_every_player_the_deck_has_room_for() {
+        let deck = CardSet::universe().iter_desc().collect::<Vec<Card>>();
+        let pockets = (0..MAX_PLAYERS)
+            .map(|i| CardSet::from(&[deck[2 * i], deck[2 * i + 1]]))
+            .collect::<Vec<CardSet>>();
+
+        let odds = OddsCalculation::calculate(
+            Game::Holdem,
+            &pockets,
+            CardSet::from(&deck[46..50]),
+            CardSet::new(),
+        )
+        .unwrap();
+
+        // Only two cards are left for the river.
+        assert!(odds.iter().all(|o| o.outcome.total_hand_count() == 2));
+        assert_roughly_eq(odds.iter().map(|o| o.outcome.equity()).sum(), 1.0);
+    }
+
+    #[test]
+    fn test_player_count_is_checked() {
+        assert_eq!(
+            OddsCalculation::calculate(
+                Game::Holdem,
+                &[cards("As Ks")],
+                CardSet::new(),
+                CardSet::new()
+            ),
+            Err(PopError::TooFewPlayers { min: 2 })
+        );
+
+        let ranges = vec![HandRange::from(cards("As Ks")); MAX_PLAYERS + 1];
+        assert_eq!(
+            RangeOddsCalculation::calculate(Game::Holdem, &ranges, CardSet::new(), CardSet::new()),
+            Err(PopError::TooManyPlayers { max: MAX_PLAYERS })
+        );
+    }
+
+    #[test]
+    fn test_many_weighted_ranges_dont_overflow() {
+        // Ten ranges of two pockets weighted 37 and 100, so some matchups weigh over 10^19.
+        let pairs = [
+            ("As Ah", "Ks Kh"),
+            ("Ac Ad", "Kc Kd"),
+            ("Qs Qh", "Js Jh"),
+            ("Qc Qd", "Jc Jd"),
+            ("Ts Th", "9s 9h"),
+            ("Tc Td", "9c 9d"),
+            ("8s 8h", "7s 7h"),
+            ("8c 8d", "7c 7d"),
+            ("6s 6h", "5s 5h"),
+            ("6c 6d", "5c 5d"),
+        ];
+        let ranges = pairs
+            .iter()
+            .map(|(light, heavy)| {
+                HandRange::new(
+                    format!("{}:0.37, {}", light, heavy),
+                    vec![
+                        WeightedPocket {
+                            pocket: cards(light),
+                            weight: 37,
+                        },
+                        WeightedPocket {
+                            pocket: cards(heavy),
+                            weight: 100,
+                        },
+                    ],
+                )
+            })
+            .collect::<Vec<HandRange>>();
+        let board = cards("2c 3d 4h 2s 3s");
+
+        assert_eq!(
+            RangeOddsCalculation::calculate(Game::Holdem, &ranges, board, CardSet::new()),
+            Err(PopError::WeightOverflow)
+        );
+
+        let config = MonteCarloConfig {
+            stopping_rule: StoppingRule::Trials(20_000),
+            seed: 5,
+        };
+        let odds =
+            RangeOddsCalculation::sample(Game::Holdem, &ranges, board, CardSet::new(), config)
+                .unwrap();
+        assert_roughly_eq(odds.iter().map(|o| o.outcome.equity()).sum(), 1.0);
+    }
 }
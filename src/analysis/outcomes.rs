@@ -1,83 +1,54 @@
-use std::cmp::Reverse;
-use std::num::NonZero;
+use std::ops::Add;
 
 use crate::analysis::evaluate_hand::HandEvaluation;
+use crate::analysis::game::Game;
 use crate::analysis::math::SatisfactionFraction;
-use crate::analysis::search_space::assert_input_is_well_formed;
+use crate::analysis::odds::check_player_count;
+use crate::analysis::search_space::undealt_cards;
 use crate::cards::cardset::CardSet;
+use crate::error::PopError;
 use crate::parallelism::algorithms::{into_parallel_reduce, parallel_map};
-use crate::util::array::{array_map, indexes, into_array_zip};
+use crate::util::array::add_elementwise;
 
-#[derive(Debug, Clone, PartialEq, Eq, Copy, Hash)]
-pub struct ArrayOutcome<const N_PLAYERS: usize> {
-    pub draws_with: [usize; N_PLAYERS],
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Outcome {
+    pub draws_with: Vec<usize>,
     pub losses: usize,
 }
 
-impl<const N_PLAYERS: usize> ArrayOutcome<N_PLAYERS> {
-    pub fn evaluate(
-        players: &[CardSet; N_PLAYERS],
-        boards: &[CardSet],
-    ) -> [ArrayOutcome<N_PLAYERS>; N_PLAYERS] {
-        const { assert!(N_PLAYERS >= 2 && N_PLAYERS <= 23) }
+impl Outcome {
+    pub fn new(n_players: usize) -> Self {
+        Self {
+            draws_with: vec![0; n_players],
+            losses: 0,
+        }
+    }
 
-        parallel_map(boards, |board| {
-            assert_input_is_well_formed(players, *board);
-        });
+    // Each player's outcome over a Hold'em showdown on each of `boards`.
+    pub fn evaluate(players: &[CardSet], boards: &[CardSet]) -> Result<Vec<Self>, PopError> {
+        check_player_count(players.len())?;
+        for board in boards {
+            undealt_cards(players, *board, CardSet::new(), Game::Holdem)?;
+        }
 
         let outcomes = parallel_map(boards, |board| {
-            let hand_evals = array_map(players, |pocket| {
-                let hand = *board | *pocket;
-                HandEvaluation::evaluate_postflop(hand).unwrap()
-            });
-
-            let mut outcomes = [Self {
-                losses: 0,
-                draws_with: [0; N_PLAYERS],
-            }; N_PLAYERS];
-
-            let mut indexes = indexes::<N_PLAYERS>();
-            indexes.sort_unstable_by_key(|i| Reverse(hand_evals[*i]));
-
-            let mut draw_len = NonZero::new(1);
-
-            for i in 1..indexes.len() {
-                if let Some(len) = draw_len {
-                    if hand_evals[indexes[i]] == hand_evals[indexes[i - 1]] {
-                        draw_len = Some(NonZero::new(len.get() + 1).unwrap());
-                    } else {
-                        for j in 0..len.get() {
-                            outcomes[indexes[j]].draws_with[len.get() - 1] = 1;
-                        }
-                        draw_len = None;
-                        outcomes[indexes[i]].losses = 1;
-                    }
-                } else {
-                    outcomes[indexes[i]].losses = 1;
-                }
-            }
-            if let Some(_) = draw_len {
-                for outcome in &mut outcomes {
-                    outcome.draws_with[N_PLAYERS - 1] = 1;
-                }
-            }
-
+            let hand_evals = players
+                .iter()
+                .map(|pocket| HandEvaluation::evaluate_postflop(*board | *pocket).unwrap())
+                .collect::<Vec<HandEvaluation>>();
+            let mut outcomes = vec![Self::new(players.len()); players.len()];
+            record_showdown(&mut outcomes, &hand_evals, 1);
             outcomes
         });
 
-        into_parallel_reduce(outcomes, |a, c| {
-            into_array_zip(a, c, |mut x, y| {
-                x.draws_with = into_array_zip(x.draws_with, y.draws_with, |t, u| t + u);
-                x.losses += y.losses;
-
-                x
-            })
+        Ok(into_parallel_reduce(outcomes, |a, b| {
+            a.into_iter().zip(b).map(|(x, y)| x + y).collect()
         })
-        .unwrap()
+        .unwrap_or_else(|| vec![Self::new(players.len()); players.len()]))
     }
 
     pub fn total_hand_count(&self) -> usize {
-        self.draws_with.into_iter().fold(0, |a, b| a + b) + self.losses
+        self.draws_with.iter().fold(0, |a, b| a + b) + self.losses
     }
 
     pub fn win_ratio(&self) -> SatisfactionFraction {
@@ -89,7 +60,7 @@ impl<const N_PLAYERS: usize> ArrayOutcome<N_PLAYERS> {
 
     pub fn draw_ratio(&self) -> SatisfactionFraction {
         SatisfactionFraction {
-            satisfying: self.draws_with.into_iter().skip(1).fold(0, |a, b| a + b),
+            satisfying: self.draws_with.iter().skip(1).fold(0, |a, b| a + b),
             total: self.total_hand_count(),
         }
     }
@@ -100,54 +71,39 @@ impl<const N_PLAYERS: usize> ArrayOutcome<N_PLAYERS> {
             total: self.total_hand_count(),
         }
     }
-}
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct Outcome {
-    pub draws_with: Vec<usize>,
-    pub losses: usize,
-}
-
-impl Outcome {
-    pub fn evaluate<const N_PLAYERS: usize>(
-        players: &[CardSet; N_PLAYERS],
-        boards: &[CardSet],
-    ) -> Vec<Self> {
-        let outcomes = ArrayOutcome::evaluate(players, boards);
-        outcomes.into_iter().map(Outcome::from).collect()
-    }
-
-    pub fn total_hand_count(&self) -> usize {
-        self.draws_with.iter().fold(0, |a, b| a + b) + self.losses
+    // The share of the pot won on average, where a pot split between n players counts 1/n.
+    pub fn equity(&self) -> f64 {
+        let shares = self
+            .draws_with
+            .iter()
+            .enumerate()
+            .map(|(i, count)| *count as f64 / (i + 1) as f64)
+            .sum::<f64>();
+        shares / self.total_hand_count() as f64
     }
+}
 
-    pub fn win_ratio(&self) -> SatisfactionFraction {
-        SatisfactionFraction {
-            satisfying: self.draws_with[0],
-            total: self.total_hand_count(),
-        }
-    }
+// Both outcomes must be of showdowns with as many players.
+impl Add<Outcome> for Outcome {
+    type Output = Outcome;
 
-    pub fn draw_ratio(&self) -> SatisfactionFraction {
-        SatisfactionFraction {
-            satisfying: self.draws_with.iter().skip(1).fold(0, |a, b| a + b),
-            total: self.total_hand_count(),
-        }
-    }
-
-    pub fn loss_ratio(&self) -> SatisfactionFraction {
-        SatisfactionFraction {
-            satisfying: self.losses,
-            total: self.total_hand_count(),
-        }
+    fn add(mut self, rhs: Outcome) -> Self::Output {
+        add_elementwise(&mut self.draws_with, &rhs.draws_with);
+        self.losses += rhs.losses;
+        self
     }
 }
 
-impl<const N_PLAYERS: usize> From<ArrayOutcome<N_PLAYERS>> for Outcome {
-    fn from(value: ArrayOutcome<N_PLAYERS>) -> Self {
-        Self {
-            draws_with: value.draws_with.to_vec(),
-            losses: value.losses,
+// Adds `weight` showdowns won by the greatest of `strengths` to `outcomes`, one per player.
+pub fn record_showdown<S: Ord>(outcomes: &mut [Outcome], strengths: &[S], weight: usize) {
+    let best = strengths.iter().max().unwrap();
+    let winners = strengths.iter().filter(|s| *s == best).count();
+    for (outcome, strength) in outcomes.iter_mut().zip(strengths) {
+        if strength == best {
+            outcome.draws_with[winners - 1] += weight;
+        } else {
+            outcome.losses += weight;
         }
     }
 }
@@ -158,64 +114,6 @@ mod tests {
 
     use super::*;
 
-    #[test]
-    fn test_outcomes_heads_up() {
-        let players = &[
-            CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE]),
-            CardSet::from(&[Card::JACK_CLUB, Card::JACK_HEART]),
-        ];
-
-        let boards = &[
-            // JJ wins
-            CardSet::from(&[
-                Card::EIGHT_HEART,
-                Card::TWO_CLUB,
-                Card::NINE_SPADE,
-                Card::JACK_DIAMOND,
-                Card::QUEEN_HEART,
-            ]),
-            // AKs wins
-            CardSet::from(&[
-                Card::EIGHT_HEART,
-                Card::TWO_CLUB,
-                Card::NINE_SPADE,
-                Card::ACE_DIAMOND,
-                Card::QUEEN_HEART,
-            ]),
-            // AKs wins
-            CardSet::from(&[
-                Card::EIGHT_HEART,
-                Card::TWO_CLUB,
-                Card::NINE_SPADE,
-                Card::KING_DIAMOND,
-                Card::QUEEN_HEART,
-            ]),
-            // both tie
-            CardSet::from(&[
-                Card::ACE_DIAMOND,
-                Card::KING_DIAMOND,
-                Card::QUEEN_DIAMOND,
-                Card::JACK_DIAMOND,
-                Card::TEN_DIAMOND,
-            ]),
-        ];
-
-        let outcomes = ArrayOutcome::evaluate(players, boards);
-
-        let expected = [
-            ArrayOutcome {
-                draws_with: [2, 1],
-                losses: 1,
-            },
-            ArrayOutcome {
-                draws_with: [1, 1],
-                losses: 2,
-            },
-        ];
-
-        assert_eq!(expected, outcomes);
-    }
-
     #[test]
     fn test_outcomes_three_way() {
         let players = &[
@@ -259,19 +157,19 @@ mod tests {
             ]),
         ];
 
-        let outcomes = ArrayOutcome::evaluate(players, boards);
+        let outcomes = Outcome::evaluate(players, boards).unwrap();
 
-        let expected = [
-            ArrayOutcome {
-                draws_with: [0, 1, 1],
+        let expected = vec![
+            Outcome {
+                draws_with: vec![0, 1, 1],
                 losses: 2,
             },
-            ArrayOutcome {
-                draws_with: [1, 1, 1],
+            Outcome {
+                draws_with: vec![1, 1, 1],
                 losses: 1,
             },
-            ArrayOutcome {
-                draws_with: [1, 0, 1],
+            Outcome {
+                draws_with: vec![1, 0, 1],
                 losses: 2,
             },
         ];
@@ -280,24 +178,37 @@ mod tests {
     }
 
     #[test]
-    fn test_generic_outcome_from_outcome() {
-        let outcome = ArrayOutcome {
-            draws_with: [1, 2, 3],
-            losses: 69,
+    fn test_outcome_add() {
+        let a = Outcome {
+            draws_with: vec![1, 2],
+            losses: 3,
         };
-
-        let expected = Outcome {
-            draws_with: vec![1, 2, 3],
-            losses: 69,
+        let b = Outcome {
+            draws_with: vec![4, 0],
+            losses: 1,
         };
 
-        let actual = Outcome::from(outcome);
+        assert_eq!(
+            a + b,
+            Outcome {
+                draws_with: vec![5, 2],
+                losses: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn test_record_showdown_splits_draws() {
+        let mut outcomes = vec![Outcome::new(3); 3];
+        record_showdown(&mut outcomes, &[7, 9, 9], 2);
 
-        assert_eq!(expected, actual);
+        assert_eq!(outcomes[0].losses, 2);
+        assert_eq!(outcomes[1].draws_with, [0, 2, 0]);
+        assert_eq!(outcomes[2].draws_with, [0, 2, 0]);
     }
 
     #[test]
-    fn test_generic_outcome_heads_up() {
+    fn test_outcomes_heads_up() {
         let players = &[
             CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE]),
             CardSet::from(&[Card::JACK_CLUB, Card::JACK_HEART]),
@@ -338,7 +249,7 @@ mod tests {
             ]),
         ];
 
-        let outcomes = Outcome::evaluate(players, boards);
+        let outcomes = Outcome::evaluate(players, boards).unwrap();
 
         let expected = vec![
             Outcome {
@@ -353,4 +264,14 @@ mod tests {
 
         assert_eq!(expected, outcomes);
     }
+
+    #[test]
+    fn test_equity_splits_draws() {
+        let outcome = Outcome {
+            draws_with: vec![2, 1, 3],
+            losses: 4,
+        };
+        // 2 wins, half of one pot and a third of three pots, out of 10.
+        assert_eq!(outcome.equity(), 0.35);
+    }
 }
@@ -0,0 +1,216 @@
+use crate::analysis::evaluate_hand::HandEvaluation;
+use crate::analysis::game::Game;
+use crate::analysis::odds::OddsCalculation;
+use crate::analysis::search_space::undealt_cards;
+use crate::cards::card::Card;
+use crate::cards::cardset::CardSet;
+use crate::error::PopError;
+
+// The hands and equities of every player once `card` is dealt.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NextCard {
+    pub card: Card,
+    pub hands: Vec<HandEvaluation>,
+    pub equities: Vec<f64>,
+}
+
+impl NextCard {
+    // The player with the highest equity, or None if several share it.
+    pub fn favourite(&self) -> Option<usize> {
+        let best = self.equities.iter().copied().fold(f64::MIN, f64::max);
+        let mut leaders = (0..self.equities.len()).filter(|i| self.equities[*i] == best);
+        match (leaders.next(), leaders.next()) {
+            (Some(leader), None) => Some(leader),
+            _ => None,
+        }
+    }
+}
+
+// The cards that improve a player to a better kind of hand, all of the same kind.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutGroup {
+    // The hand made with the first of `cards`. The others make the same kind of hand.
+    pub hand: HandEvaluation,
+    pub cards: Vec<Card>,
+    // For each opponent and kind of hand they make with some of `cards` that beats the player's:
+    // the opponent, their hand with the first such card, and how many of `cards` do it.
+    pub beaten_by: Vec<(usize, HandEvaluation, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutsCalculation {
+    pub game: Game,
+    pub pockets: Vec<CardSet>,
+    pub hands: Vec<HandEvaluation>,
+    pub equities: Vec<f64>,
+    pub next_cards: Vec<NextCard>,
+}
+
+// The share of the pot each player wins on average, counting both halves in split-pot games.
+fn equities(game: Game, odds: &[OddsCalculation]) -> Vec<f64> {
+    odds.iter()
+        .map(|o| match game.is_hi_lo() {
+            true => o.hi_lo.pot_share_percentage() / 100.0,
+            false => o.outcome.equity(),
+        })
+        .collect()
+}
+
+impl OutsCalculation {
+    // Enumerates every card that can come next on a flop or turn `board`, keeping the results of
+    // each card apart.
+    pub fn calculate(
+        game: Game,
+        pockets: &[CardSet],
+        board: CardSet,
+        dead: CardSet,
+    ) -> Result<Self, PopError> {
+        if board.len() != 3 && board.len() != 4 {
+            return Err(PopError::InvalidBoardSize {
+                board,
+                expected: "a flop or a turn",
+            });
+        }
+        let undealt = undealt_cards(pockets, board, dead, game)?;
+
+        let evaluate = |board: CardSet| {
+            pockets
+                .iter()
+                .map(|pocket| game.evaluate(*pocket, board).unwrap())
+                .collect::<Vec<HandEvaluation>>()
+        };
+
+        let next_cards = undealt
+            .iter_desc()
+            .map(|card| {
+                let next_board = board + card;
+                let odds = OddsCalculation::calculate(game, pockets, next_board, dead)?;
+                Ok(NextCard {
+                    card,
+                    hands: evaluate(next_board),
+                    equities: equities(game, &odds),
+                })
+            })
+            .collect::<Result<Vec<NextCard>, PopError>>()?;
+
+        let odds = OddsCalculation::calculate(game, pockets, board, dead)?;
+        Ok(Self {
+            game,
+            pockets: pockets.to_vec(),
+            hands: evaluate(board),
+            equities: equities(game, &odds),
+            next_cards,
+        })
+    }
+
+    // The cards that give `player` a better kind of hand than they have now, grouped by that
+    // kind from the strongest to the weakest.
+    pub fn outs(&self, player: usize) -> Vec<OutGroup> {
+        let ranking = self.game.hand_ranking();
+        let current = ranking.category(&self.hands[player]);
+
+        let mut groups = Vec::<OutGroup>::new();
+        for next in &self.next_cards {
+            let hand = next.hands[player];
+            let category = ranking.category(&hand);
+            if category <= current {
+                continue;
+            }
+
+            let index = match groups
+                .iter()
+                .position(|g| ranking.category(&g.hand) == category)
+            {
+                Some(index) => index,
+                None => {
+                    groups.push(OutGroup {
+                        hand,
+                        cards: Vec::new(),
+                        beaten_by: Vec::new(),
+                    });
+                    groups.len() - 1
+                }
+            };
+            let group = &mut groups[index];
+            group.cards.push(next.card);
+
+            for (opponent, opponent_hand) in next.hands.iter().enumerate() {
+                if opponent == player || ranking.key(*opponent_hand) <= ranking.key(hand) {
+                    continue;
+                }
+                let opponent_category = ranking.category(opponent_hand);
+                match group
+                    .beaten_by
+                    .iter_mut()
+                    .find(|(o, h, _)| *o == opponent && ranking.category(h) == opponent_category)
+                {
+                    Some((_, _, count)) => *count += 1,
+                    None => group.beaten_by.push((opponent, *opponent_hand, 1)),
+                }
+            }
+        }
+
+        groups.sort_by_key(|g| std::cmp::Reverse(ranking.category(&g.hand)));
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::evaluate_hand::HandCategory;
+    use crate::cards::cardset::testing::cards;
+
+    #[test]
+    fn test_outs_on_the_turn() {
+        // A flush draw against a set.
+        let pockets = [cards("Ah Kh"), cards("Qs Qd")];
+        let board = cards("Qh 7h 2c 3s");
+        let outs =
+            OutsCalculation::calculate(Game::Holdem, &pockets, board, CardSet::new()).unwrap();
+
+        assert_eq!(outs.next_cards.len(), 52 - 4 - 4);
+        assert_eq!(
+            outs.next_cards
+                .iter()
+                .filter(|n| n.favourite() == Some(0))
+                .count(),
+            7
+        );
+
+        let groups = outs.outs(0);
+        let flush = groups
+            .iter()
+            .find(|g| g.hand.category() == HandCategory::Flush)
+            .unwrap();
+        // 9 hearts, where 2h and 3h pair the board and give the queens a full house.
+        assert_eq!(flush.cards.len(), 9);
+        assert_eq!(flush.beaten_by.len(), 1);
+        assert_eq!(flush.beaten_by[0].0, 1);
+        assert_eq!(flush.beaten_by[0].2, 2);
+
+        // Every river ends the hand, so the equities are 0, 1/2 or 1.
+        for next in &outs.next_cards {
+            assert!(next.equities.iter().all(|e| [0.0, 0.5, 1.0].contains(e)));
+            assert_eq!(next.equities.iter().sum::<f64>(), 1.0);
+        }
+    }
+
+    #[test]
+    fn test_outs_equities_average_to_the_current_equity() {
+        let pockets = [cards("As Ks"), cards("Jc Jd"), cards("9h 8h")];
+        let board = cards("Ts 7d 2s");
+        let outs =
+            OutsCalculation::calculate(Game::Holdem, &pockets, board, CardSet::new()).unwrap();
+
+        for player in 0..3 {
+            let average = outs
+                .next_cards
+                .iter()
+                .map(|n| n.equities[player])
+                .sum::<f64>()
+                / outs.next_cards.len() as f64;
+            assert!((average - outs.equities[player]).abs() < 1e-9);
+        }
+    }
+}
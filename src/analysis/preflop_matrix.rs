@@ -0,0 +1,323 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use crate::analysis::game::Game;
+use crate::analysis::odds::OddsCalculation;
+use crate::analysis::search_space::SuitPermutation;
+use crate::cards::card::{ALL_RANKS, NUM_RANKS, Rank};
+use crate::cards::cardset::CardSet;
+use crate::cards::hand_range::{Suitedness, pair_combos, unpaired_combos};
+
+pub const NUM_HAND_CLASSES: usize = NUM_RANKS * NUM_RANKS;
+
+// A Hold'em starting hand up to its suits, e.g. AA, AKs or AKo.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+pub enum HandClass {
+    PocketPair { rank: Rank },
+    Suited { higher_rank: Rank, lower_rank: Rank },
+    Offsuit { higher_rank: Rank, lower_rank: Rank },
+}
+
+impl HandClass {
+    // The position in the usual 13x13 grid, read row by row: pairs on the diagonal, suited hands
+    // above it and offsuit hands below it, with aces first.
+    pub fn index(&self) -> usize {
+        let position = |rank: Rank| NUM_RANKS - 1 - rank as usize;
+        match *self {
+            HandClass::PocketPair { rank } => position(rank) * (NUM_RANKS + 1),
+            HandClass::Suited {
+                higher_rank,
+                lower_rank,
+            } => position(higher_rank) * NUM_RANKS + position(lower_rank),
+            HandClass::Offsuit {
+                higher_rank,
+                lower_rank,
+            } => position(lower_rank) * NUM_RANKS + position(higher_rank),
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        assert!(index < NUM_HAND_CLASSES, "No hand class at {}", index);
+        let rank = |position: usize| ALL_RANKS[NUM_RANKS - 1 - position];
+        let (row, column) = (index / NUM_RANKS, index % NUM_RANKS);
+        if row == column {
+            HandClass::PocketPair { rank: rank(row) }
+        } else if row < column {
+            HandClass::Suited {
+                higher_rank: rank(row),
+                lower_rank: rank(column),
+            }
+        } else {
+            HandClass::Offsuit {
+                higher_rank: rank(column),
+                lower_rank: rank(row),
+            }
+        }
+    }
+
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..NUM_HAND_CLASSES).map(Self::from_index)
+    }
+
+    // The 6 combos of a pair, 4 of a suited hand or 12 of an offsuit hand.
+    pub fn combos(&self) -> Vec<CardSet> {
+        match *self {
+            HandClass::PocketPair { rank } => pair_combos(rank),
+            HandClass::Suited {
+                higher_rank,
+                lower_rank,
+            } => unpaired_combos(higher_rank, lower_rank, Suitedness::Suited),
+            HandClass::Offsuit {
+                higher_rank,
+                lower_rank,
+            } => unpaired_combos(higher_rank, lower_rank, Suitedness::Offsuit),
+        }
+    }
+}
+
+impl Display for HandClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HandClass::PocketPair { rank } => write!(f, "{}{}", rank, rank),
+            HandClass::Suited {
+                higher_rank,
+                lower_rank,
+            } => write!(f, "{}{}s", higher_rank, lower_rank),
+            HandClass::Offsuit {
+                higher_rank,
+                lower_rank,
+            } => write!(f, "{}{}o", higher_rank, lower_rank),
+        }
+    }
+}
+
+// The heads-up matchups between the combos of `hero` and `villain` that don't share a card, up to
+// a relabeling of the suits, each with the number of matchups it stands for.
+pub fn canonical_matchups(hero: HandClass, villain: HandClass) -> Vec<([CardSet; 2], usize)> {
+    let permutations = SuitPermutation::all();
+    let mut matchups = BTreeMap::<(u64, u64), usize>::new();
+    for a in hero.combos() {
+        for b in villain.combos().into_iter().filter(|b| b.disjoint_with(a)) {
+            let canonical = permutations
+                .iter()
+                .map(|p| (p.apply(a).bitset(), p.apply(b).bitset()))
+                .min()
+                .unwrap();
+            *matchups.entry(canonical).or_default() += 1;
+        }
+    }
+
+    matchups
+        .into_iter()
+        .map(|((a, b), count)| ([CardSet::from_bitset(a), CardSet::from_bitset(b)], count))
+        .collect()
+}
+
+// The all-in preflop equity of `hero` against `villain`, over every matchup of their combos and
+// every board. Each matchup up to suits is only enumerated once.
+pub fn class_equity(hero: HandClass, villain: HandClass) -> f64 {
+    let matchups = canonical_matchups(hero, villain);
+    let total = matchups.iter().map(|(_, count)| count).sum::<usize>();
+    matchups
+        .iter()
+        .map(|(pockets, count)| {
+            // The pockets of a matchup never share a card, so this can't fail.
+            let odds =
+                OddsCalculation::calculate(Game::Holdem, pockets, CardSet::new(), CardSet::new())
+                    .unwrap();
+            odds[0].outcome.equity() * *count as f64
+        })
+        .sum::<f64>()
+        / total as f64
+}
+
+// The equity of every hand class against every other, where cells that haven't been calculated
+// yet are None. Setting a cell also sets its mirror image, since the equities add up to 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EquityMatrix {
+    equities: Vec<Option<f64>>,
+}
+
+impl EquityMatrix {
+    // A class against itself always has half the equity, so only the diagonal is known.
+    pub fn empty() -> Self {
+        let mut equities = vec![None; NUM_HAND_CLASSES * NUM_HAND_CLASSES];
+        for i in 0..NUM_HAND_CLASSES {
+            equities[i * NUM_HAND_CLASSES + i] = Some(0.5);
+        }
+        Self { equities }
+    }
+
+    pub fn get(&self, hero: HandClass, villain: HandClass) -> Option<f64> {
+        self.equities[hero.index() * NUM_HAND_CLASSES + villain.index()]
+    }
+
+    pub fn set(&mut self, hero: HandClass, villain: HandClass, equity: f64) {
+        self.equities[hero.index() * NUM_HAND_CLASSES + villain.index()] = Some(equity);
+        self.equities[villain.index() * NUM_HAND_CLASSES + hero.index()] = Some(1.0 - equity);
+    }
+
+    // The pairs of classes left to calculate, each pair once.
+    pub fn missing(&self) -> Vec<(HandClass, HandClass)> {
+        (0..NUM_HAND_CLASSES)
+            .flat_map(|i| (i + 1..NUM_HAND_CLASSES).map(move |j| (i, j)))
+            .filter(|(i, j)| self.equities[i * NUM_HAND_CLASSES + j].is_none())
+            .map(|(i, j)| (HandClass::from_index(i), HandClass::from_index(j)))
+            .collect()
+    }
+
+    // A header row of the classes, then a row per class with its equity against each column, or
+    // an empty value if it hasn't been calculated.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("equity");
+        for class in HandClass::all() {
+            csv.push_str(&format!(",{}", class));
+        }
+        csv.push('\n');
+
+        for hero in HandClass::all() {
+            csv.push_str(&hero.to_string());
+            for villain in HandClass::all() {
+                match self.get(hero, villain) {
+                    Some(equity) => csv.push_str(&format!(",{:.6}", equity)),
+                    None => csv.push(','),
+                }
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    pub fn from_csv(csv: &str) -> Result<Self, String> {
+        let mut lines = csv.lines();
+        let expected_header = Self::empty().to_csv().lines().next().unwrap().to_string();
+        if lines.next() != Some(expected_header.as_str()) {
+            return Err("the header doesn't list the 169 hand classes in order".to_string());
+        }
+
+        let mut matrix = Self::empty();
+        for hero in HandClass::all() {
+            let line = lines
+                .next()
+                .ok_or_else(|| format!("the row for {} is missing", hero))?;
+            let mut values = line.split(',');
+            if values.next() != Some(hero.to_string().as_str()) {
+                return Err(format!("expected the row for {}, but got '{}'", hero, line));
+            }
+            for villain in HandClass::all() {
+                let value = values
+                    .next()
+                    .ok_or_else(|| format!("the row for {} is too short", hero))?;
+                if value.is_empty() {
+                    continue;
+                }
+                let equity = value
+                    .parse::<f64>()
+                    .map_err(|_| format!("'{}' in the row for {} isn't a number", value, hero))?;
+                matrix.equities[hero.index() * NUM_HAND_CLASSES + villain.index()] = Some(equity);
+            }
+            if values.next().is_some() {
+                return Err(format!("the row for {} is too long", hero));
+            }
+        }
+        Ok(matrix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class_of(pocket: CardSet) -> Option<HandClass> {
+        if pocket.len() != 2 {
+            return None;
+        }
+        let mut cards = pocket.iter_desc();
+        let (high, low) = (cards.next().unwrap(), cards.next().unwrap());
+        Some(if high.rank == low.rank {
+            HandClass::PocketPair { rank: high.rank }
+        } else if high.suit == low.suit {
+            HandClass::Suited {
+                higher_rank: high.rank,
+                lower_rank: low.rank,
+            }
+        } else {
+            HandClass::Offsuit {
+                higher_rank: high.rank,
+                lower_rank: low.rank,
+            }
+        })
+    }
+
+    #[test]
+    fn test_hand_class_indexes() {
+        let names = HandClass::all()
+            .map(|c| c.to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(&names[..3], ["AA", "AKs", "AQs"]);
+        assert_eq!(names[NUM_RANKS], "AKo");
+        assert_eq!(names[NUM_HAND_CLASSES - 1], "22");
+
+        for (i, class) in HandClass::all().enumerate() {
+            assert_eq!(class.index(), i);
+        }
+        let combos = HandClass::all().map(|c| c.combos().len()).sum::<usize>();
+        assert_eq!(combos, 1326);
+        for class in HandClass::all() {
+            assert!(class.combos().iter().all(|p| class_of(*p) == Some(class)));
+        }
+    }
+
+    #[test]
+    fn test_canonical_matchups() {
+        let aa = HandClass::PocketPair { rank: Rank::Ace };
+        let kk = HandClass::PocketPair { rank: Rank::King };
+        let matchups = canonical_matchups(aa, kk);
+        // The kings share both, one or neither of the aces' suits.
+        assert_eq!(matchups.len(), 3);
+        assert_eq!(matchups.iter().map(|(_, n)| n).sum::<usize>(), 36);
+
+        let ako = HandClass::Offsuit {
+            higher_rank: Rank::Ace,
+            lower_rank: Rank::King,
+        };
+        // Combos that share a card are left out.
+        assert_eq!(
+            canonical_matchups(aa, ako)
+                .iter()
+                .map(|(_, n)| n)
+                .sum::<usize>(),
+            6 * 6
+        );
+    }
+
+    #[test]
+    #[ignore = "This test is computationally intensive. Run it with `cargo test --release -- --include-ignored`"]
+    fn test_class_equity() {
+        let aa = HandClass::PocketPair { rank: Rank::Ace };
+        let kk = HandClass::PocketPair { rank: Rank::King };
+        assert!((class_equity(aa, kk) - 0.8195).abs() < 0.0005);
+    }
+
+    #[test]
+    fn test_equity_matrix_csv() {
+        let aa = HandClass::PocketPair { rank: Rank::Ace };
+        let t9s = HandClass::Suited {
+            higher_rank: Rank::Ten,
+            lower_rank: Rank::Nine,
+        };
+        let mut matrix = EquityMatrix::empty();
+        matrix.set(aa, t9s, 0.75);
+        assert_eq!(matrix.get(t9s, aa), Some(0.25));
+        assert_eq!(matrix.get(aa, aa), Some(0.5));
+        assert_eq!(
+            matrix.missing().len(),
+            NUM_HAND_CLASSES * (NUM_HAND_CLASSES - 1) / 2 - 1
+        );
+
+        let csv = matrix.to_csv();
+        assert_eq!(EquityMatrix::from_csv(&csv), Ok(matrix));
+        assert!(EquityMatrix::from_csv(&csv.replace("0.750000", "x")).is_err());
+        assert!(EquityMatrix::from_csv(&csv[..csv.len() / 2]).is_err());
+    }
+}
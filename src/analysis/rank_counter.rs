@@ -23,13 +23,6 @@ impl RankCounter {
     pub fn get(&self, rank: Rank) -> u8 {
         self.counts[rank as usize]
     }
-
-    // Sets all rank counts back to 0.
-    pub fn reset(&mut self) {
-        for count in &mut self.counts {
-            *count = 0;
-        }
-    }
 }
 
 #[cfg(test)]
@@ -49,24 +42,4 @@ mod tests {
         assert_eq!(counter.get(Rank::Ace), 1);
         assert_eq!(counter.get(Rank::King), 0);
     }
-
-    #[test]
-    fn test_reset() {
-        let mut counter = RankCounter::new();
-
-        counter.inc(Rank::Two);
-        counter.inc(Rank::Two);
-        counter.inc(Rank::Ace);
-
-        counter.reset();
-
-        counter.inc(Rank::Two);
-        counter.inc(Rank::Three);
-        counter.inc(Rank::Ace);
-        counter.inc(Rank::Ace);
-
-        assert_eq!(counter.get(Rank::Two), 1);
-        assert_eq!(counter.get(Rank::Three), 1);
-        assert_eq!(counter.get(Rank::Ace), 2);
-    }
 }
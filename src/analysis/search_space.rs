@@ -1,327 +1,405 @@
 use crate::{
-    analysis::math::n_choose_r,
-    cards::{card::Card, cardset::CardSet},
-    parallelism::{os::get_parallelism_from_os, send_sync_raw_ptr::SendSyncRawPtr},
+    analysis::{game::Game, math::n_choose_r},
+    cards::{
+        card::{Card, NUM_SUITS},
+        cardset::CardSet,
+        hand_range::HandRange,
+    },
+    error::PopError,
+    parallelism::os::get_parallelism_from_os,
 };
-use crossbeam_channel::Sender;
 use std::{
     sync::atomic::{AtomicUsize, Ordering},
     thread::{self},
 };
 
-unsafe fn parallel_combinations_of_slice_of_len_1(
-    slice: &[Card],
-    current: CardSet,
-    output: SendSyncRawPtr<CardSet>,
-    amount_done: &AtomicUsize,
-) {
-    let mut ptr = output.ptr;
-    let mut set = current;
-
-    for x1 in 0..slice.len() {
-        set.add(slice[x1]);
-        unsafe {
-            *ptr = set;
-            ptr = ptr.add(1);
+// Lazily yields every `size`-card subset of a pool, one at a time.
+pub struct Combinations {
+    cards: Vec<Card>,
+    indexes: Vec<usize>,
+    done: bool,
+}
+
+impl Combinations {
+    pub fn new(pool: CardSet, size: usize) -> Self {
+        Self {
+            cards: pool.iter_desc().collect(),
+            indexes: (0..size).collect(),
+            done: pool.len() < size,
         }
-        set -= slice[x1];
     }
-
-    amount_done.fetch_add(n_choose_r(slice.len(), 1), Ordering::Release);
 }
 
-unsafe fn parallel_combinations_of_slice_of_len_2(
-    slice: &[Card],
-    current: CardSet,
-    output: SendSyncRawPtr<CardSet>,
-    amount_done: &AtomicUsize,
-) {
-    let mut ptr = output.ptr;
-    let mut set = current;
-
-    for x1 in 0..slice.len() {
-        set.add(slice[x1]);
-        for x2 in x1 + 1..slice.len() {
-            set.add(slice[x2]);
-            unsafe {
-                *ptr = set;
-                ptr = ptr.add(1);
-            }
-            set -= slice[x2];
+impl Iterator for Combinations {
+    type Item = CardSet;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
         }
-        set -= slice[x1];
-    }
 
-    amount_done.fetch_add(n_choose_r(slice.len(), 2), Ordering::Release);
-}
+        let set = self.indexes.iter().map(|i| self.cards[*i]).collect();
 
-unsafe fn parallel_combinations_of_slice_of_len_3(
-    slice: &[Card],
-    current: CardSet,
-    output: SendSyncRawPtr<CardSet>,
-    amount_done: &AtomicUsize,
-) {
-    let mut ptr = output.ptr;
-    let mut set = current;
-
-    for x1 in 0..slice.len() {
-        set.add(slice[x1]);
-        for x2 in x1 + 1..slice.len() {
-            set.add(slice[x2]);
-            for x3 in x2 + 1..slice.len() {
-                set.add(slice[x3]);
-                unsafe {
-                    *ptr = set;
-                    ptr = ptr.add(1);
+        // Advances the rightmost index that still has room, then packs the ones after it.
+        let n = self.cards.len();
+        let size = self.indexes.len();
+        match (0..size).rev().find(|i| self.indexes[*i] < n - size + i) {
+            Some(i) => {
+                self.indexes[i] += 1;
+                for j in i + 1..size {
+                    self.indexes[j] = self.indexes[j - 1] + 1;
                 }
-                set -= slice[x3];
             }
-            set -= slice[x2];
+            None => self.done = true,
         }
-        set -= slice[x1];
-    }
 
-    amount_done.fetch_add(n_choose_r(slice.len(), 3), Ordering::Release);
+        Some(set)
+    }
 }
 
-unsafe fn parallel_combinations_of_slice_of_len_4(
-    slice: &[Card],
-    current: CardSet,
-    output: SendSyncRawPtr<CardSet>,
-    amount_done: &AtomicUsize,
-) {
-    let mut ptr = output.ptr;
-    let mut set = current;
-
-    for x1 in 0..slice.len() {
-        set.add(slice[x1]);
-        for x2 in x1 + 1..slice.len() {
-            set.add(slice[x2]);
-            for x3 in x2 + 1..slice.len() {
-                set.add(slice[x3]);
-                for x4 in x3 + 1..slice.len() {
-                    set.add(slice[x4]);
-                    unsafe {
-                        *ptr = set;
-                        ptr = ptr.add(1);
-                    }
-                    set -= slice[x4];
-                }
-                set -= slice[x3];
-            }
-            set -= slice[x2];
-        }
-        set -= slice[x1];
+// Folds every `size`-card subset of `pool` without storing them.
+//
+// The subsets are split into units of work by their two highest cards. Each thread folds the
+// units it takes into its own accumulator, so memory use doesn't depend on the number of subsets.
+pub fn fold_combinations<
+    T: Send,
+    I: Fn() -> T + Send + Sync,
+    F: Fn(T, CardSet) -> T + Send + Sync,
+    R: Fn(T, T) -> T,
+>(
+    pool: CardSet,
+    size: usize,
+    identity: I,
+    folder: F,
+    reducer: R,
+) -> T {
+    if size < 2 || pool.len() < size {
+        return Combinations::new(pool, size).fold(identity(), folder);
     }
 
-    amount_done.fetch_add(n_choose_r(slice.len(), 4), Ordering::Release);
-}
+    let cards = pool.iter_desc().collect::<Vec<Card>>();
+    let mut prefixes = Vec::new();
+    for i in 0..cards.len() {
+        for j in i + 1..cards.len() - (size - 2) {
+            prefixes.push((CardSet::from(&[cards[i], cards[j]]), j + 1));
+        }
+    }
 
-unsafe fn parallel_combinations_of_slice_of_len_5(
-    slice: &[Card],
-    current: CardSet,
-    output: SendSyncRawPtr<CardSet>,
-    amount_done: &AtomicUsize,
-) {
-    let mut ptr = output.ptr;
-    let mut set = current;
-
-    for x1 in 0..slice.len() {
-        set.add(slice[x1]);
-        for x2 in x1 + 1..slice.len() {
-            set.add(slice[x2]);
-            for x3 in x2 + 1..slice.len() {
-                set.add(slice[x3]);
-                for x4 in x3 + 1..slice.len() {
-                    set.add(slice[x4]);
-                    for x5 in x4 + 1..slice.len() {
-                        set.add(slice[x5]);
-                        unsafe {
-                            *ptr = set;
-                            ptr = ptr.add(1);
+    let next_prefix = AtomicUsize::new(0);
+    let n_threads = get_parallelism_from_os().get().min(prefixes.len());
+
+    let accumulators = thread::scope(|s| {
+        let threads = (0..n_threads)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut acc = identity();
+                    loop {
+                        let index = next_prefix.fetch_add(1, Ordering::Relaxed);
+                        let (prefix, rest_start) = match prefixes.get(index) {
+                            Some(p) => *p,
+                            None => break,
+                        };
+                        let rest = CardSet::from(&cards[rest_start..]);
+                        for combination in Combinations::new(rest, size - 2) {
+                            acc = folder(acc, prefix | combination);
                         }
-                        set -= slice[x5];
                     }
-                    set -= slice[x4];
+                    acc
+                })
+            })
+            .collect::<Vec<_>>();
+
+        threads
+            .into_iter()
+            .map(|t| t.join().unwrap())
+            .collect::<Vec<T>>()
+    });
+
+    accumulators.into_iter().reduce(reducer).unwrap()
+}
+
+// The cards of the first suit. Every suit's cards are this shifted left by the suit's index.
+const FIRST_SUIT_MASK: u64 = 0x0001_1111_1111_1111;
+
+// A relabeling of the suits, where suit `i` becomes suit `self.0[i]`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct SuitPermutation([usize; NUM_SUITS]);
+
+impl SuitPermutation {
+    pub fn all() -> Vec<Self> {
+        let mut permutations = Vec::new();
+        for a in 0..NUM_SUITS {
+            for b in (0..NUM_SUITS).filter(|b| *b != a) {
+                for c in (0..NUM_SUITS).filter(|c| *c != a && *c != b) {
+                    let d = NUM_SUITS * (NUM_SUITS - 1) / 2 - a - b - c;
+                    permutations.push(Self([a, b, c, d]));
                 }
-                set -= slice[x3];
             }
-            set -= slice[x2];
         }
-        set -= slice[x1];
+        permutations
     }
 
-    amount_done.fetch_add(n_choose_r(slice.len(), 5), Ordering::Release);
+    pub fn apply(&self, set: CardSet) -> CardSet {
+        let bits = set.bitset();
+        let mut permuted = 0;
+        for (from, to) in self.0.iter().enumerate() {
+            let suit = bits & (FIRST_SUIT_MASK << from);
+            permuted |= (suit >> from) << to;
+        }
+        CardSet::from_bitset(permuted)
+    }
 }
 
-unsafe fn parallel_combinations_of_slice<'a>(
-    sender: &Sender<Option<ParallelCombinationsWorkItem<'a>>>,
-    amount_done: &AtomicUsize,
-    work_item: ParallelCombinationsWorkItem<'a>,
-) {
-    let slice = work_item.slice;
-    let current = work_item.current;
-    let needed = work_item.needed;
-    let output = work_item.output;
-
-    match work_item.needed {
-        0 => {
-            unsafe { *output.ptr = current };
-            amount_done.fetch_add(1, Ordering::Release);
-            return;
-        }
-        1 => {
-            unsafe { parallel_combinations_of_slice_of_len_1(slice, current, output, amount_done) };
-            return;
-        }
-        2 => {
-            unsafe { parallel_combinations_of_slice_of_len_2(slice, current, output, amount_done) };
-            return;
-        }
-        3 => {
-            unsafe { parallel_combinations_of_slice_of_len_3(slice, current, output, amount_done) };
-            return;
-        }
-        4 => {
-            unsafe { parallel_combinations_of_slice_of_len_4(slice, current, output, amount_done) };
-            return;
+// The suit permutations that map each of the `fixed` sets onto itself, such as the pockets and
+// the board of a showdown. These never change the outcome of a runout, so runouts that are
+// permutations of each other only need to be evaluated once.
+pub fn suit_symmetries(fixed: &[CardSet]) -> Vec<SuitPermutation> {
+    SuitPermutation::all()
+        .into_iter()
+        .filter(|p| fixed.iter().all(|set| p.apply(*set) == *set))
+        .collect()
+}
+
+// The number of runouts that `runout` stands for if it is the smallest of its permutations
+// under `symmetries`, or 0 if another permutation stands for it instead.
+pub fn canonical_weight(runout: CardSet, symmetries: &[SuitPermutation]) -> usize {
+    let mut fixed_by = 0;
+    for permutation in symmetries {
+        let permuted = permutation.apply(runout);
+        if permuted.bitset() < runout.bitset() {
+            return 0;
         }
-        5 => {
-            unsafe { parallel_combinations_of_slice_of_len_5(slice, current, output, amount_done) };
-            return;
+        if permuted == runout {
+            fixed_by += 1;
         }
-        _ => {}
-    };
+    }
+    symmetries.len() / fixed_by
+}
 
-    let mut current_ptr = output.ptr;
+// Like `fold_combinations`, but only folds one subset out of each group that the `fixed` sets'
+// suit symmetries make equivalent, along with the size of that group.
+pub fn fold_canonical_combinations<
+    T: Send,
+    I: Fn() -> T + Send + Sync,
+    F: Fn(T, CardSet, usize) -> T + Send + Sync,
+    R: Fn(T, T) -> T,
+>(
+    pool: CardSet,
+    size: usize,
+    fixed: &[CardSet],
+    identity: I,
+    folder: F,
+    reducer: R,
+) -> T {
+    let symmetries = suit_symmetries(fixed);
+    if symmetries.len() == 1 {
+        return fold_combinations(
+            pool,
+            size,
+            identity,
+            |acc, set| folder(acc, set, 1),
+            reducer,
+        );
+    }
 
-    for i in 0..slice.len() - needed + 1 {
-        let amount = n_choose_r(slice.len() - i - 1, needed - 1);
-        debug_assert!(amount > 0);
+    fold_combinations(
+        pool,
+        size,
+        identity,
+        |acc, set| match canonical_weight(set, symmetries.as_slice()) {
+            0 => acc,
+            weight => folder(acc, set, weight),
+        },
+        reducer,
+    )
+}
 
-        let current_mut_ptr = SendSyncRawPtr { ptr: current_ptr };
+// The cards of `game`'s deck that aren't in a pocket, on the board or dead.
+pub fn undealt_cards(
+    pockets: &[CardSet],
+    board: CardSet,
+    dead: CardSet,
+    game: Game,
+) -> Result<CardSet, PopError> {
+    for pocket in pockets {
+        if !game.pocket_sizes().contains(&pocket.len()) {
+            return Err(PopError::InvalidPocketSize {
+                pocket: *pocket,
+                game,
+            });
+        }
+    }
 
-        let mut new_current = current;
-        new_current.add(slice[i]);
+    if board.len() > 5 {
+        return Err(PopError::BoardTooLarge(board));
+    }
 
-        sender
-            .send(Some(ParallelCombinationsWorkItem {
-                slice: &slice[i + 1..],
-                current: new_current,
-                needed: needed - 1,
-                output: current_mut_ptr,
-            }))
-            .unwrap();
+    let deck = game.deck().cards();
+    for set in pockets.iter().chain([&board]) {
+        if let Some(card) = (*set - deck).iter_desc().next() {
+            return Err(PopError::CardNotInDeck { card, game });
+        }
+    }
 
-        current_ptr = unsafe { current_ptr.add(amount) };
+    let mut set = board;
+    for pocket in pockets.iter().chain([&dead]) {
+        if let Some(card) = (set & *pocket).iter_desc().next() {
+            return Err(PopError::DuplicateCard(card));
+        }
+        set |= *pocket;
     }
-}
 
-#[derive(Clone, Copy)]
-struct ParallelCombinationsWorkItem<'a> {
-    pub slice: &'a [Card],
-    pub current: CardSet,
-    pub needed: usize,
-    pub output: SendSyncRawPtr<CardSet>,
+    Ok(deck - set)
 }
 
-pub fn combinations(pool: CardSet, size: usize) -> Vec<CardSet> {
-    if pool.len() < size {
-        return vec![];
+// Checks that every pocket of `ranges` can be dealt in `game`, and that some matchup of them can
+// be dealt with `board` and `dead`.
+pub fn check_ranges(
+    game: Game,
+    ranges: &[HandRange],
+    board: CardSet,
+    dead: CardSet,
+) -> Result<(), PopError> {
+    undealt_cards(&[], board, dead, game)?;
+    for combo in ranges.iter().flat_map(|r| r.combos()) {
+        undealt_cards(&[combo.pocket], CardSet::new(), CardSet::new(), game)?;
     }
+    if !has_matchup(ranges, board | dead) {
+        return Err(PopError::NoMatchup);
+    }
+    Ok(())
+}
 
-    let amount = n_choose_r(pool.len(), size);
-    let mut ret = Vec::new();
-    ret.reserve_exact(amount);
-
-    let cards = pool.iter_desc().collect::<Vec<Card>>();
-
-    let (sender, receiver) = crossbeam_channel::unbounded::<Option<ParallelCombinationsWorkItem>>();
-    sender
-        .send(Some(ParallelCombinationsWorkItem {
-            slice: cards.as_slice(),
-            current: CardSet::new(),
-            needed: size,
-            output: SendSyncRawPtr {
-                ptr: ret.as_mut_ptr(),
-            },
-        }))
-        .unwrap();
-
-    let amount_done = AtomicUsize::new(0);
-
-    let n_threads = get_parallelism_from_os().get();
+// Calls `visitor` with every way of picking one pocket from each range without reusing a card,
+// along with the product of the picked pockets' weights. The product saturates instead of
+// overflowing. Stops early if `visitor` returns false.
+fn visit_matchups(
+    ranges: &[HandRange],
+    used: CardSet,
+    current: &mut Vec<CardSet>,
+    weight: u128,
+    visitor: &mut dyn FnMut(&[CardSet], u128) -> bool,
+) -> bool {
+    let range = match ranges.first() {
+        Some(r) => r,
+        None => return visitor(current.as_slice(), weight),
+    };
 
-    thread::scope(|s| unsafe {
-        for _ in 0..n_threads {
-            s.spawn(|| {
-                while let Some(work_item) = receiver.recv().unwrap() {
-                    parallel_combinations_of_slice(&sender, &amount_done, work_item);
-                    if amount_done.load(Ordering::Acquire) == amount {
-                        for _ in 0..n_threads {
-                            sender.send(None).unwrap();
-                        }
-                    }
-                }
-            });
+    for combo in range.combos() {
+        if !combo.pocket.disjoint_with(used) {
+            continue;
         }
-    });
 
-    unsafe { ret.set_len(amount) };
+        current.push(combo.pocket);
+        let keep_going = visit_matchups(
+            &ranges[1..],
+            used | combo.pocket,
+            current,
+            weight.saturating_mul(combo.weight as u128),
+            visitor,
+        );
+        current.pop();
+
+        if !keep_going {
+            return false;
+        }
+    }
 
-    ret
+    true
 }
 
-pub fn assert_input_is_well_formed(pockets: &[CardSet], board: CardSet) {
-    let _ = undealt_cards(pockets, board);
+// Calls `f` with every matchup the ranges can produce and its weight, without storing them.
+pub fn for_each_matchup(ranges: &[HandRange], known: CardSet, mut f: impl FnMut(&[CardSet], u128)) {
+    visit_matchups(ranges, known, &mut Vec::new(), 1, &mut |pockets, weight| {
+        f(pockets, weight);
+        true
+    });
 }
 
-pub fn undealt_cards(pockets: &[CardSet], board: CardSet) -> CardSet {
-    for pocket in pockets {
-        if pocket.len() != 2 {
-            panic!("All pockets must have 2 cards, but {} doesn't", pocket);
-        }
-    }
-
-    if board.len() > 5 {
-        panic!("The board cannot have more than 5 cards, but has {}", board);
-    }
-
-    let mut set = board;
-    for pocket in pockets {
-        let intersection = set & *pocket;
-        if intersection.len() != 0 {
-            panic!(
-                "Cannot have duplicate cards, but {} appears multiple times",
-                intersection.iter_desc().next().unwrap()
-            )
-        }
-        set |= *pocket;
-    }
+pub fn has_matchup(ranges: &[HandRange], known: CardSet) -> bool {
+    let mut found = false;
+    visit_matchups(ranges, known, &mut Vec::new(), 1, &mut |_, _| {
+        found = true;
+        false
+    });
+    found
+}
 
-    CardSet::universe() - set
+// Counts the matchups the ranges can produce, stopping once `limit` is reached.
+pub fn count_matchups(ranges: &[HandRange], known: CardSet, limit: usize) -> usize {
+    let mut count = 0;
+    visit_matchups(ranges, known, &mut Vec::new(), 1, &mut |_, _| {
+        count += 1;
+        count < limit
+    });
+    count
 }
 
-pub fn all_seven_card_hands() -> Vec<CardSet> {
-    combinations(CardSet::universe(), 7)
+// The number of boards an exhaustive calculation would evaluate, or `limit` if it's larger.
+pub fn count_runouts(
+    game: Game,
+    ranges: &[HandRange],
+    board: CardSet,
+    dead: CardSet,
+    limit: usize,
+) -> usize {
+    let dealt = ranges
+        .iter()
+        .map(|r| r.combos().first().map_or(0, |c| c.pocket.len()))
+        .sum::<usize>();
+    let known = board | (dead & game.deck().cards());
+    let undealt = game.deck().cards().len() - known.len() - dealt;
+    let per_matchup = n_choose_r(undealt, 5 - board.len());
+    let matchups = count_matchups(ranges, known, limit.div_ceil(per_matchup.max(1)));
+    per_matchup.saturating_mul(matchups).min(limit)
 }
 
-pub fn all_boards<const N_PLAYERS: usize>(pockets: &[CardSet; N_PLAYERS]) {
-    if pockets.iter().any(|p| p.len() != 2) {
-        panic!("All pockets must have 2 cards each");
-    }
+// The number of runouts an exhaustive calculation would evaluate, with each matchup's runouts
+// counted as many times as its weight. Every count the calculation adds up is at most this, so
+// it can't overflow if this fits in a `usize`. Saturates instead of overflowing.
+pub fn count_weighted_runouts(
+    game: Game,
+    ranges: &[HandRange],
+    board: CardSet,
+    dead: CardSet,
+) -> u128 {
+    let known = board | (dead & game.deck().cards());
+    let mut total = 0u128;
+    visit_matchups(ranges, known, &mut Vec::new(), 1, &mut |pockets, weight| {
+        let dealt = pockets.iter().map(|p| p.len()).sum::<usize>();
+        let undealt = game.deck().cards().len() - known.len() - dealt;
+        let runouts = n_choose_r(undealt, 5 - board.len()) as u128;
+        total = total.saturating_add(weight.saturating_mul(runouts));
+        total != u128::MAX
+    });
+    total
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cards::{
+        card::Rank,
+        hand_range::{WeightedPocket, pair_combos},
+    };
+    use std::collections::HashSet;
+
+    fn pair_range(rank: Rank) -> HandRange {
+        HandRange::new(
+            rank.to_string(),
+            pair_combos(rank)
+                .into_iter()
+                .map(|pocket| WeightedPocket { pocket, weight: 1 })
+                .collect(),
+        )
+    }
 
     #[test]
     fn test_combinations_empty() {
         let set = CardSet::from(&[Card::ACE_SPADE, Card::KING_HEART, Card::QUEEN_DIAMOND]);
         let expected: Vec<CardSet> = vec![CardSet::new()];
 
-        let actual = combinations(set, 0);
+        let actual = Combinations::new(set, 0).collect::<Vec<CardSet>>();
 
         assert_eq!(actual, expected);
     }
@@ -335,7 +413,7 @@ mod tests {
             CardSet::from(&[Card::QUEEN_DIAMOND]),
         ];
 
-        let actual = combinations(set, 1);
+        let actual = Combinations::new(set, 1).collect::<Vec<CardSet>>();
 
         assert_eq!(actual, expected);
     }
@@ -349,7 +427,7 @@ mod tests {
             CardSet::from(&[Card::KING_HEART, Card::QUEEN_DIAMOND]),
         ];
 
-        let actual = combinations(set, 2);
+        let actual = Combinations::new(set, 2).collect::<Vec<CardSet>>();
 
         assert_eq!(actual, expected);
     }
@@ -376,7 +454,188 @@ mod tests {
             CardSet::from(&[Card::QUEEN_DIAMOND, Card::JACK_CLUB, Card::TEN_SPADE]),
         ];
 
-        let actual = combinations(set, 3);
+        let actual = Combinations::new(set, 3).collect::<Vec<CardSet>>();
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_suit_permutations() {
+        let permutations = SuitPermutation::all();
+        let hand = CardSet::from(&[Card::ACE_SPADE, Card::ACE_HEART, Card::TWO_CLUB]);
+
+        assert_eq!(permutations.iter().collect::<HashSet<_>>().len(), 24);
+        assert!(permutations.iter().all(|p| p.apply(hand).len() == 3));
+        assert_eq!(
+            SuitPermutation([3, 1, 2, 0]).apply(hand),
+            CardSet::from(&[Card::ACE_CLUB, Card::ACE_HEART, Card::TWO_SPADE])
+        );
+    }
+
+    #[test]
+    fn test_suit_symmetries() {
+        let aks = CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE]);
+        let qq = CardSet::from(&[Card::QUEEN_CLUB, Card::QUEEN_DIAMOND]);
+        let ako = CardSet::from(&[Card::ACE_SPADE, Card::KING_HEART]);
+
+        assert_eq!(suit_symmetries(&[]).len(), 24);
+        assert_eq!(suit_symmetries(&[aks]).len(), 6);
+        assert_eq!(suit_symmetries(&[aks, qq]).len(), 2);
+        assert_eq!(suit_symmetries(&[ako]).len(), 2);
+    }
+
+    #[test]
+    fn test_canonical_weights_cover_every_combination() {
+        let fixed = [
+            CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE]),
+            CardSet::from(&[Card::QUEEN_CLUB, Card::QUEEN_DIAMOND]),
+        ];
+        let pool = CardSet::universe() - fixed[0] - fixed[1];
+
+        let (canonical, total) = fold_canonical_combinations(
+            pool,
+            3,
+            &fixed,
+            || (0, 0),
+            |(canonical, total), _, weight| (canonical + 1, total + weight),
+            |a, b| (a.0 + b.0, a.1 + b.1),
+        );
+
+        assert_eq!(total, n_choose_r(48, 3));
+        assert!(canonical < total);
+    }
+
+    #[test]
+    fn test_fold_combinations() {
+        let pool = CardSet::universe() - CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE]);
+
+        let actual = fold_combinations(
+            pool,
+            3,
+            Vec::new,
+            |mut acc, set| {
+                acc.push(set);
+                acc
+            },
+            |mut a, b| {
+                a.extend(b);
+                a
+            },
+        );
+
+        let expected = Combinations::new(pool, 3).collect::<Vec<CardSet>>();
+        assert_eq!(actual.len(), expected.len());
+        assert_eq!(
+            actual.into_iter().collect::<HashSet<CardSet>>(),
+            expected.into_iter().collect::<HashSet<CardSet>>()
+        );
+    }
+
+    fn matchups(ranges: &[HandRange], known: CardSet) -> Vec<(Vec<CardSet>, u128)> {
+        let mut ret = Vec::new();
+        for_each_matchup(ranges, known, |pockets, weight| {
+            ret.push((pockets.to_vec(), weight))
+        });
+        ret
+    }
+
+    #[test]
+    fn test_matchups_remove_conflicts() {
+        let aa = pair_range(Rank::Ace);
+        let aks = HandRange::from(CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE]));
+
+        let found = matchups(&[aks, aa], CardSet::new());
+
+        // Only the three ace pairs without the ace of spades remain.
+        assert_eq!(found.len(), 3);
+        assert!(
+            found
+                .iter()
+                .all(|(pockets, weight)| { *weight == 1 && pockets[0].disjoint_with(pockets[1]) })
+        );
+    }
+
+    #[test]
+    fn test_matchups_respect_known_cards() {
+        let aa = pair_range(Rank::Ace);
+        let kk = pair_range(Rank::King);
+
+        let found = matchups(&[aa, kk], CardSet::from(&[Card::ACE_CLUB, Card::KING_CLUB]));
+
+        assert_eq!(found.len(), 9);
+    }
+
+    #[test]
+    fn test_count_runouts() {
+        let aks = HandRange::from(CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE]));
+        let qq = pair_range(Rank::Queen);
+        let board = CardSet::from(&[Card::QUEEN_CLUB, Card::TWO_CLUB, Card::THREE_CLUB]);
+
+        // 3 combos of QQ are left, and each has 45 choose 2 turns and rivers.
+        assert_eq!(
+            count_runouts(
+                Game::Holdem,
+                &[aks.clone(), qq.clone()],
+                board,
+                CardSet::new(),
+                usize::MAX
+            ),
+            3 * 990
+        );
+        assert_eq!(
+            count_runouts(Game::Holdem, &[aks, qq], board, CardSet::new(), 1000),
+            1000
+        );
+    }
+
+    #[test]
+    fn test_has_matchup() {
+        let aks = HandRange::from(CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE]));
+        let aqs = HandRange::from(CardSet::from(&[Card::ACE_SPADE, Card::QUEEN_SPADE]));
+        let qq = pair_range(Rank::Queen);
+
+        assert!(!has_matchup(&[aks.clone(), aqs.clone()], CardSet::new()));
+        assert!(has_matchup(&[aks, qq.clone()], CardSet::new()));
+        assert!(!has_matchup(
+            &[aqs, qq],
+            CardSet::from(&[Card::QUEEN_CLUB, Card::QUEEN_HEART, Card::QUEEN_DIAMOND])
+        ));
+    }
+
+    #[test]
+    fn test_undealt_cards_errors() {
+        let aks = CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE]);
+        let aqs = CardSet::from(&[Card::ACE_SPADE, Card::QUEEN_SPADE]);
+        assert_eq!(
+            undealt_cards(&[aks, aqs], CardSet::new(), CardSet::new(), Game::Holdem),
+            Err(PopError::DuplicateCard(Card::ACE_SPADE))
+        );
+        assert_eq!(
+            undealt_cards(&[aks], CardSet::new(), CardSet::new(), Game::Omaha),
+            Err(PopError::InvalidPocketSize {
+                pocket: aks,
+                game: Game::Omaha
+            })
+        );
+        let board = CardSet::from(&[
+            Card::TWO_CLUB,
+            Card::THREE_CLUB,
+            Card::FOUR_CLUB,
+            Card::FIVE_CLUB,
+            Card::SIX_CLUB,
+            Card::SEVEN_CLUB,
+        ]);
+        assert_eq!(
+            undealt_cards(&[aks], board, CardSet::new(), Game::Holdem),
+            Err(PopError::BoardTooLarge(board))
+        );
+        assert_eq!(
+            check_ranges(
+                Game::Holdem,
+                &[HandRange::from(aks), HandRange::from(aqs)],
+                CardSet::new(),
+                CardSet::new()
+            ),
+            Err(PopError::NoMatchup)
+        );
+    }
 }
@@ -0,0 +1,524 @@
+use std::cmp::Reverse;
+use std::fmt::Display;
+
+use crate::analysis::hand_distribution::HandDistribution;
+use crate::analysis::hand_evaluator::HandEvaluator;
+use crate::analysis::hi_lo::low_value;
+use crate::analysis::math::n_choose_r;
+use crate::analysis::monte_carlo::{MonteCarloConfig, sample_in_batches, sample_runout};
+use crate::analysis::odds::{CalculationMethod, MAX_PLAYERS, ShowdownTally, check_player_count};
+use crate::analysis::outcomes::Outcome;
+use crate::analysis::search_space::{Combinations, fold_combinations};
+use crate::analysis::table_evaluator::TableEvaluator;
+use crate::cards::card::Card;
+use crate::cards::cardset::CardSet;
+use crate::datastructures::stack_vec::StackVec;
+use crate::error::PopError;
+use crate::util::random::Random;
+
+// The number of cards every stud player ends up with.
+pub const STUD_HAND_SIZE: usize = 7;
+
+// The most cards a stud player shows.
+pub const MAX_UP_CARDS: usize = 4;
+
+// Games where every player is dealt their own seven cards, some face up, and there's no board.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum StudGame {
+    // The best five-card high hand wins.
+    High,
+    // The best five-card ace-to-five low wins. Straights and flushes don't count against a low,
+    // and there's no qualifier.
+    Razz,
+}
+
+pub const ALL_STUD_GAMES: [StudGame; 2] = [StudGame::High, StudGame::Razz];
+
+impl StudGame {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StudGame::High => "stud",
+            StudGame::Razz => "razz",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        ALL_STUD_GAMES
+            .into_iter()
+            .find(|g| g.name().eq_ignore_ascii_case(name))
+    }
+}
+
+impl Display for StudGame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            StudGame::High => "Seven Card Stud",
+            StudGame::Razz => "Razz",
+        })
+    }
+}
+
+// An ace-to-five low, where aces count as 1 and straights and flushes are ignored.
+//
+// Hands compare like high hands do with every value reversed, so a *smaller* `RazzHand` is a
+// better hand: any five different ranks beat any pair, and 7-5-4-3-2 beats 8-4-3-2-A.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+pub struct RazzHand {
+    // 0 for five different ranks, then one pair, two pair, trips, full house and quads.
+    pub category: u8,
+    // The values grouped like a high hand: bigger groups first, then higher values first.
+    pub values: [u8; 5],
+}
+
+impl RazzHand {
+    pub fn of_five(cards: CardSet) -> Option<Self> {
+        if cards.len() != 5 {
+            return None;
+        }
+
+        let mut counts = [0u8; 14];
+        for card in cards.iter_desc() {
+            counts[low_value(card.rank) as usize] += 1;
+        }
+
+        let mut groups = (1..=13u8)
+            .filter(|v| counts[*v as usize] > 0)
+            .map(|v| (counts[v as usize], v))
+            .collect::<Vec<(u8, u8)>>();
+        groups.sort_unstable_by_key(|g| Reverse(*g));
+
+        let category = match groups.iter().map(|g| g.0).collect::<Vec<u8>>().as_slice() {
+            [1, 1, 1, 1, 1] => 0,
+            [2, 1, 1, 1] => 1,
+            [2, 2, 1] => 2,
+            [3, 1, 1] => 3,
+            [3, 2] => 4,
+            _ => 5,
+        };
+
+        let mut values = [0; 5];
+        let mut i = 0;
+        for (count, value) in groups {
+            for _ in 0..count {
+                values[i] = value;
+                i += 1;
+            }
+        }
+
+        Some(Self { category, values })
+    }
+
+    // The best low out of 5 to 7 cards.
+    pub fn evaluate(cards: CardSet) -> Option<Self> {
+        if cards.len() < 5 || cards.len() > STUD_HAND_SIZE {
+            return None;
+        }
+
+        // With five different ranks, the best low is simply the five lowest.
+        let mut mask = cards
+            .iter_desc()
+            .fold(0u16, |m, c| m | 1 << low_value(c.rank));
+        if mask.count_ones() >= 5 {
+            let mut values = [0; 5];
+            for value in values.iter_mut().rev() {
+                *value = mask.trailing_zeros() as u8;
+                mask &= mask - 1;
+            }
+            return Some(Self {
+                category: 0,
+                values,
+            });
+        }
+
+        Combinations::new(cards, 5).filter_map(Self::of_five).min()
+    }
+}
+
+// What is known about one player's cards.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct StudHand {
+    pub down: CardSet,
+    pub up: CardSet,
+    // Down cards that have been dealt but aren't known, e.g. an opponent's hole cards.
+    pub unknown_down: usize,
+}
+
+impl StudHand {
+    pub fn known(&self) -> CardSet {
+        self.down | self.up
+    }
+
+    // The number of cards dealt to this player so far.
+    pub fn dealt(&self) -> usize {
+        self.down.len() + self.up.len() + self.unknown_down
+    }
+
+    // The number of cards this player has yet to be dealt, one per street.
+    pub fn streets_left(&self) -> usize {
+        STUD_HAND_SIZE.saturating_sub(self.dealt())
+    }
+}
+
+impl Display for StudHand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "({}{}) {}",
+            self.down,
+            "??".repeat(self.unknown_down),
+            self.up
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StudOddsCalculation {
+    pub hand: StudHand,
+    pub outcome: Outcome,
+    // Only counted for stud high.
+    pub hand_distribution: Option<HandDistribution>,
+    pub method: CalculationMethod,
+}
+
+impl StudOddsCalculation {
+    fn from_tally(
+        game: StudGame,
+        hands: &[StudHand],
+        tally: ShowdownTally,
+        method: CalculationMethod,
+    ) -> Vec<Self> {
+        hands
+            .iter()
+            .zip(tally.into_players())
+            .map(|(hand, (outcome, hand_distribution, _))| Self {
+                hand: *hand,
+                outcome,
+                hand_distribution: match game {
+                    StudGame::High => Some(hand_distribution),
+                    StudGame::Razz => None,
+                },
+                method,
+            })
+            .collect()
+    }
+
+    // Enumerates every way of dealing each player the rest of their cards.
+    pub fn calculate(
+        game: StudGame,
+        hands: &[StudHand],
+        dead: CardSet,
+    ) -> Result<Vec<Self>, PopError> {
+        check_player_count(hands.len())?;
+        let pool = undealt_stud_cards(hands, dead)?;
+        let known = hands.iter().map(|h| h.known()).collect::<Vec<CardSet>>();
+        let to_deal = hands.iter().map(cards_to_deal).collect::<Vec<usize>>();
+
+        let tally = fold_combinations(
+            pool,
+            to_deal[0],
+            || ShowdownTally::new(hands.len()),
+            |mut tally, first| {
+                let mut cards = known.clone();
+                cards[0] |= first;
+                deal_rest(game, pool - first, &to_deal, &mut cards, 1, &mut tally);
+                tally
+            },
+            |a, b| a + b,
+        );
+
+        Ok(Self::from_tally(
+            game,
+            hands,
+            tally,
+            CalculationMethod::Exhaustive,
+        ))
+    }
+
+    // Estimates the odds by dealing the rest of every player's cards at random.
+    pub fn sample(
+        game: StudGame,
+        hands: &[StudHand],
+        dead: CardSet,
+        config: MonteCarloConfig,
+    ) -> Result<Vec<Self>, PopError> {
+        check_player_count(hands.len())?;
+        let pool = undealt_stud_cards(hands, dead)?;
+        let known = hands.iter().map(|h| h.known()).collect::<Vec<CardSet>>();
+        let to_deal = hands.iter().map(cards_to_deal).collect::<Vec<usize>>();
+
+        let tally = sample_in_batches(config, |batch, trials| {
+            let mut rng = Random::seeded_stream(config.seed, batch as u64);
+            let mut deck = pool.iter_desc().collect::<Vec<Card>>();
+            let mut tally = ShowdownTally::new(hands.len());
+            let mut cards = known.clone();
+
+            for _ in 0..trials {
+                cards.copy_from_slice(&known);
+                let mut used = CardSet::new();
+                for (cards, to_deal) in cards.iter_mut().zip(&to_deal) {
+                    let dealt = sample_runout(deck.as_mut_slice(), used, *to_deal, &mut rng);
+                    used |= dealt;
+                    *cards |= dealt;
+                }
+                record_stud_showdown(game, &cards, 1, &mut tally);
+            }
+            tally
+        });
+
+        Ok(Self::from_tally(
+            game,
+            hands,
+            tally,
+            CalculationMethod::MonteCarlo { seed: config.seed },
+        ))
+    }
+}
+
+// The number of cards `hand` has yet to be dealt, counting its unknown down cards.
+pub fn cards_to_deal(hand: &StudHand) -> usize {
+    STUD_HAND_SIZE - hand.known().len()
+}
+
+// The cards that can still be dealt. Fails if a card is known twice or there aren't enough cards
+// left to complete every hand.
+pub fn undealt_stud_cards(hands: &[StudHand], dead: CardSet) -> Result<CardSet, PopError> {
+    let mut seen = dead;
+    let mut needed = 0;
+    for hand in hands {
+        if hand.dealt() > STUD_HAND_SIZE || hand.up.len() > MAX_UP_CARDS {
+            return Err(PopError::InvalidStudHand(*hand));
+        }
+        if let Some(card) = (seen & hand.known()).iter_desc().next() {
+            return Err(PopError::DuplicateCard(card));
+        }
+        seen |= hand.known();
+        needed += cards_to_deal(hand);
+    }
+
+    let pool = CardSet::universe() - seen;
+    if pool.len() < needed {
+        return Err(PopError::NotEnoughCards {
+            needed,
+            left: pool.len(),
+        });
+    }
+    Ok(pool)
+}
+
+// Fails unless every hand has `streets_left` streets to come.
+pub fn check_streets_left(hands: &[StudHand], streets_left: usize) -> Result<(), PopError> {
+    match hands.iter().find(|h| h.streets_left() != streets_left) {
+        Some(hand) => Err(PopError::StreetMismatch {
+            hand: *hand,
+            streets_left,
+        }),
+        None => Ok(()),
+    }
+}
+
+// The number of deals an exhaustive calculation would evaluate.
+pub fn count_stud_deals(hands: &[StudHand], dead: CardSet) -> Result<usize, PopError> {
+    let mut left = undealt_stud_cards(hands, dead)?.len();
+    let mut deals = 1usize;
+    for hand in hands {
+        deals = deals.saturating_mul(n_choose_r(left, cards_to_deal(hand)));
+        left -= cards_to_deal(hand);
+    }
+    Ok(deals)
+}
+
+fn deal_rest(
+    game: StudGame,
+    pool: CardSet,
+    to_deal: &[usize],
+    cards: &mut [CardSet],
+    player: usize,
+    tally: &mut ShowdownTally,
+) {
+    if player == cards.len() {
+        record_stud_showdown(game, cards, 1, tally);
+        return;
+    }
+
+    let before = cards[player];
+    for dealt in Combinations::new(pool, to_deal[player]) {
+        cards[player] = before | dealt;
+        deal_rest(game, pool - dealt, to_deal, cards, player + 1, tally);
+    }
+    cards[player] = before;
+}
+
+fn record_stud_showdown(
+    game: StudGame,
+    cards: &[CardSet],
+    weight: usize,
+    tally: &mut ShowdownTally,
+) {
+    match game {
+        StudGame::High => {
+            let hand_evals = cards
+                .iter()
+                .map(|c| TableEvaluator::evaluate(*c).unwrap())
+                .collect::<StackVec<_, MAX_PLAYERS>>();
+            tally.record_outcomes(hand_evals.as_slice(), weight);
+            tally.record_hand_distributions(hand_evals.as_slice(), weight);
+        }
+        StudGame::Razz => {
+            let lows = cards
+                .iter()
+                .map(|c| Reverse(RazzHand::evaluate(*c).unwrap()))
+                .collect::<StackVec<_, MAX_PLAYERS>>();
+            tally.record_outcomes(lows.as_slice(), weight);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::evaluate_hand::HandEvaluation;
+    use crate::cards::cardset::testing::cards;
+
+    fn hand(down: &str, up: &str) -> StudHand {
+        StudHand {
+            down: cards(down),
+            up: cards(up),
+            unknown_down: 0,
+        }
+    }
+
+    #[test]
+    fn test_razz_hand_order() {
+        let wheel = RazzHand::evaluate(cards("As 2d 3c 4h 5s Kd Kh")).unwrap();
+        let seven = RazzHand::evaluate(cards("7s 5d 4c 3h 2s Qd Jh")).unwrap();
+        let eight = RazzHand::evaluate(cards("8s 4d 3c 2h As Ad 2c")).unwrap();
+        let pair = RazzHand::evaluate(cards("As Ad 2c 3h 4s 4d 2h")).unwrap();
+
+        assert_eq!(wheel.values, [5, 4, 3, 2, 1]);
+        assert!(wheel < seven);
+        assert!(seven < eight);
+        assert!(eight < pair);
+        assert_eq!(pair.category, 1);
+        assert_eq!(pair.values, [1, 1, 4, 3, 2]);
+    }
+
+    #[test]
+    fn test_razz_evaluate_matches_every_five_cards() {
+        for hand in Combinations::new(cards("As Ad 2c 3h 4s 7d 7h 8c 9s Kd"), 7) {
+            assert_eq!(
+                RazzHand::evaluate(hand),
+                Combinations::new(hand, 5)
+                    .filter_map(RazzHand::of_five)
+                    .min()
+            );
+        }
+    }
+
+    #[test]
+    fn test_razz_ignores_straights_and_flushes() {
+        let straight_flush = RazzHand::of_five(cards("6h 5h 4h 3h 2h")).unwrap();
+
+        assert_eq!(straight_flush.category, 0);
+        assert!(straight_flush < RazzHand::of_five(cards("7s 5d 4c 3h 2s")).unwrap());
+    }
+
+    #[test]
+    fn test_stud_on_seventh_street() {
+        let hands = [
+            hand("Ah Ad 7c", "Kc Qd 2s 9h"),
+            hand("Ks Kd 3c", "8h 8d 4s Jc"),
+        ];
+
+        let high = StudOddsCalculation::calculate(StudGame::High, &hands, CardSet::new()).unwrap();
+        assert_eq!(high[1].outcome.draws_with, vec![1, 0]);
+        assert_eq!(high[1].hand_distribution.unwrap().two_pairs, 1);
+
+        let razz = StudOddsCalculation::calculate(StudGame::Razz, &hands, CardSet::new()).unwrap();
+        // The first player's Q-9-7-2-A beats K-J-8-4-3 in Razz.
+        assert_eq!(razz[0].outcome.draws_with, vec![1, 0]);
+        assert_eq!(razz[0].hand_distribution, None);
+    }
+
+    #[test]
+    fn test_stud_on_sixth_street() {
+        let hands = [hand("Ah Ad", "Kc Qd 2s 9h"), hand("Ks Kd", "8h 8d 4s Jc")];
+        let dead = cards("Ac");
+
+        let odds = StudOddsCalculation::calculate(StudGame::High, &hands, dead).unwrap();
+
+        // 39 cards are left, so there are 39 * 38 ways to deal the last card to each player.
+        assert_eq!(odds[0].outcome.total_hand_count(), 39 * 38);
+        assert_eq!(count_stud_deals(&hands, dead).unwrap(), 39 * 38);
+
+        let brute_force = Combinations::new(undealt_stud_cards(&hands, dead).unwrap(), 2)
+            .flat_map(|two| {
+                let [a, b] = two.iter_desc().collect::<Vec<Card>>().try_into().unwrap();
+                [(a, b), (b, a)]
+            })
+            .filter(|(a, b)| {
+                HandEvaluation::evaluate_postflop(hands[0].known() + *a)
+                    > HandEvaluation::evaluate_postflop(hands[1].known() + *b)
+            })
+            .count();
+        assert_eq!(odds[0].outcome.draws_with[0], brute_force);
+    }
+
+    #[test]
+    fn test_stud_with_unknown_down_cards() {
+        let hands = [
+            hand("Ah Ad", "Kc Qd 2s 9h"),
+            StudHand {
+                down: CardSet::new(),
+                up: cards("8h 8d 4s Jc"),
+                unknown_down: 2,
+            },
+        ];
+
+        assert_eq!(cards_to_deal(&hands[1]), 3);
+        assert_eq!(
+            count_stud_deals(&hands, CardSet::new()).unwrap(),
+            42 * n_choose_r(41, 3)
+        );
+    }
+
+    #[test]
+    fn test_check_streets_left() {
+        let hands = [
+            hand("Ah Ad", "Kc Qd 2s 9h"),
+            StudHand {
+                down: CardSet::new(),
+                up: cards("8h 8d 4s Jc"),
+                unknown_down: 2,
+            },
+        ];
+
+        assert_eq!(hands[1].streets_left(), 1);
+        assert_eq!(check_streets_left(&hands, 1), Ok(()));
+        assert_eq!(
+            check_streets_left(&hands, 2),
+            Err(PopError::StreetMismatch {
+                hand: hands[0],
+                streets_left: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_sample_stud_is_reproducible() {
+        let hands = [hand("Ah Ad", "Kc"), hand("7s 6s", "5s")];
+        let config = MonteCarloConfig {
+            stopping_rule: crate::analysis::monte_carlo::StoppingRule::Trials(2_000),
+            seed: 9,
+        };
+
+        let a =
+            StudOddsCalculation::sample(StudGame::Razz, &hands, CardSet::new(), config).unwrap();
+        let b =
+            StudOddsCalculation::sample(StudGame::Razz, &hands, CardSet::new(), config).unwrap();
+
+        assert_eq!(a, b);
+        assert_eq!(a[0].outcome.total_hand_count(), 2_000);
+        // 7-6-5 is a big favourite over a pair of aces in Razz.
+        assert!(a[1].outcome.win_ratio().percentage() > 60.0);
+    }
+}
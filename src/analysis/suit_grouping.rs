@@ -29,13 +29,6 @@ impl SuitGrouping {
     pub fn get(&self, suit: Suit) -> &[Rank] {
         return self.groups[suit as usize].as_slice();
     }
-
-    // Empties each suit grouping.
-    pub fn reset(&mut self) {
-        for group in &mut self.groups {
-            group.reset();
-        }
-    }
 }
 
 #[cfg(test)]
@@ -86,23 +79,4 @@ mod tests {
             ]
         );
     }
-
-    #[test]
-    fn test_reset() {
-        let mut grouping = SuitGrouping::new();
-
-        grouping.insert(Card::KING_CLUB);
-        grouping.insert(Card::TWO_SPADE);
-        grouping.insert(Card::ACE_SPADE);
-
-        grouping.reset();
-
-        grouping.insert(Card::KING_CLUB);
-        grouping.insert(Card::THREE_HEART);
-        grouping.insert(Card::THREE_CLUB);
-
-        assert_eq!(grouping.get(Suit::Club), &[Rank::King, Rank::Three]);
-        assert_eq!(grouping.get(Suit::Heart), &[Rank::Three]);
-        assert_eq!(grouping.get(Suit::Spade), &[]);
-    }
 }
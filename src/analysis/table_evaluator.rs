@@ -0,0 +1,443 @@
+use crate::analysis::evaluate_hand::HandEvaluation;
+use crate::analysis::hand_evaluator::HandEvaluator;
+use crate::cards::card::{ALL_RANKS, NUM_RANKS, NUM_SUITS, Rank};
+use crate::cards::cardset::CardSet;
+
+// Bit `r` of a rank mask is set if the rank with index `r` is present.
+const NUM_RANK_MASKS: usize = 1 << NUM_RANKS;
+const ALL_RANKS_MASK: u16 = (1 << NUM_RANKS) - 1;
+const WHEEL_MASK: u16 = (1 << Rank::Ace as u16) | 0b1111;
+
+const HIGH_CARD: u32 = 0;
+const PAIR: u32 = 1;
+const TWO_PAIR: u32 = 2;
+const THREE_OF_A_KIND: u32 = 3;
+const STRAIGHT: u32 = 4;
+const FLUSH: u32 = 5;
+const FULL_HOUSE: u32 = 6;
+const FOUR_OF_A_KIND: u32 = 7;
+const STRAIGHT_FLUSH: u32 = 8;
+
+// For every rank mask, one more than the index of the highest rank of a straight in it, or 0 if
+// it doesn't contain a straight.
+static STRAIGHT_HIGHS: [u8; NUM_RANK_MASKS] = build_straight_highs();
+
+// For every rank mask, its (up to) five highest rank indexes packed 4 bits each, highest first,
+// starting at bit 16.
+static TOP_RANKS: [u32; NUM_RANK_MASKS] = build_top_ranks();
+
+const fn build_straight_highs() -> [u8; NUM_RANK_MASKS] {
+    let mut table = [0; NUM_RANK_MASKS];
+    let mut mask = 0;
+    while mask < NUM_RANK_MASKS {
+        let mut high = Rank::Ace as usize;
+        while high >= Rank::Six as usize {
+            let window = 0b11111 << (high - 4);
+            if mask & window == window {
+                table[mask] = high as u8 + 1;
+                break;
+            }
+            high -= 1;
+        }
+        if table[mask] == 0 && mask as u16 & WHEEL_MASK == WHEEL_MASK {
+            table[mask] = Rank::Five as u8 + 1;
+        }
+        mask += 1;
+    }
+    table
+}
+
+const fn build_top_ranks() -> [u32; NUM_RANK_MASKS] {
+    let mut table = [0; NUM_RANK_MASKS];
+    let mut mask = 0;
+    while mask < NUM_RANK_MASKS {
+        let mut packed = 0;
+        let mut count = 0;
+        let mut rank = NUM_RANKS;
+        while rank > 0 && count < 5 {
+            rank -= 1;
+            if mask & (1 << rank) != 0 {
+                packed = (packed << 4) | rank as u32;
+                count += 1;
+            }
+        }
+        table[mask] = packed << (4 * (5 - count));
+        mask += 1;
+    }
+    table
+}
+
+fn highest(mask: u16) -> u32 {
+    15 - mask.leading_zeros()
+}
+
+fn without(mask: u16, rank: u32) -> u16 {
+    mask & !(1 << rank)
+}
+
+// A hand's category in bits 20-23 followed by the ranks that break ties within the category,
+// in the same order as the fields of the matching `HandEvaluation` variant.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+pub struct HandStrength(u32);
+
+impl HandStrength {
+    fn new(category: u32, ranks: u32) -> Self {
+        Self((category << 20) | ranks)
+    }
+
+    fn category(&self) -> u32 {
+        self.0 >> 20
+    }
+
+    // The `i`th tiebreaking rank, starting from 0.
+    fn rank(&self, i: usize) -> Rank {
+        ALL_RANKS[((self.0 >> (16 - 4 * i)) & 0xF) as usize]
+    }
+
+    fn ranks<const N: usize>(&self, start: usize) -> [Rank; N] {
+        std::array::from_fn(|i| self.rank(start + i))
+    }
+}
+
+impl From<HandStrength> for HandEvaluation {
+    fn from(value: HandStrength) -> Self {
+        match value.category() {
+            HIGH_CARD => HandEvaluation::HighCard {
+                rank: value.rank(0),
+                kickers_sorted_desc: value.ranks(1),
+            },
+            PAIR => HandEvaluation::Pair {
+                rank: value.rank(0),
+                kickers_sorted_desc: value.ranks(1),
+            },
+            TWO_PAIR => HandEvaluation::TwoPair {
+                higher_rank: value.rank(0),
+                lower_rank: value.rank(1),
+                kicker: value.rank(2),
+            },
+            THREE_OF_A_KIND => HandEvaluation::ThreeOfAKind {
+                rank: value.rank(0),
+                kickers_sorted_desc: value.ranks(1),
+            },
+            STRAIGHT => HandEvaluation::Straight {
+                highest_rank: value.rank(0),
+            },
+            FLUSH => HandEvaluation::Flush {
+                ranks_sorted_desc: value.ranks(0),
+            },
+            FULL_HOUSE => HandEvaluation::FullHouse {
+                triple: value.rank(0),
+                pair: value.rank(1),
+            },
+            FOUR_OF_A_KIND => HandEvaluation::FourOfAKind {
+                rank: value.rank(0),
+                kicker: value.rank(1),
+            },
+            STRAIGHT_FLUSH => HandEvaluation::StraightFlush {
+                highest_rank: value.rank(0),
+            },
+            c => panic!("Invalid hand category {}", c),
+        }
+    }
+}
+
+impl From<HandEvaluation> for HandStrength {
+    fn from(value: HandEvaluation) -> Self {
+        fn pack(ranks: &[Rank]) -> u32 {
+            ranks
+                .iter()
+                .enumerate()
+                .fold(0, |a, (i, r)| a | (*r as u32) << (16 - 4 * i))
+        }
+
+        match value {
+            HandEvaluation::HighCard {
+                rank,
+                kickers_sorted_desc: k,
+            } => Self::new(HIGH_CARD, pack(&[rank, k[0], k[1], k[2], k[3]])),
+            HandEvaluation::Pair {
+                rank,
+                kickers_sorted_desc: k,
+            } => Self::new(PAIR, pack(&[rank, k[0], k[1], k[2]])),
+            HandEvaluation::TwoPair {
+                higher_rank,
+                lower_rank,
+                kicker,
+            } => Self::new(TWO_PAIR, pack(&[higher_rank, lower_rank, kicker])),
+            HandEvaluation::ThreeOfAKind {
+                rank,
+                kickers_sorted_desc: k,
+            } => Self::new(THREE_OF_A_KIND, pack(&[rank, k[0], k[1]])),
+            HandEvaluation::Straight { highest_rank } => Self::new(STRAIGHT, pack(&[highest_rank])),
+            HandEvaluation::Flush { ranks_sorted_desc } => {
+                Self::new(FLUSH, pack(&ranks_sorted_desc))
+            }
+            HandEvaluation::FullHouse { triple, pair } => {
+                Self::new(FULL_HOUSE, pack(&[triple, pair]))
+            }
+            HandEvaluation::FourOfAKind { rank, kicker } => {
+                Self::new(FOUR_OF_A_KIND, pack(&[rank, kicker]))
+            }
+            HandEvaluation::StraightFlush { highest_rank } => {
+                Self::new(STRAIGHT_FLUSH, pack(&[highest_rank]))
+            }
+        }
+    }
+}
+
+// Splits the hand into a rank mask per suit and per number of cards of that rank, then looks up
+// straights and kickers in tables indexed by rank mask.
+pub struct TableEvaluator;
+
+impl HandEvaluator for TableEvaluator {
+    type Strength = HandStrength;
+
+    fn strength(hand: CardSet) -> Option<Self::Strength> {
+        if hand.len() < 5 || hand.len() > 7 {
+            return None;
+        }
+
+        // Card `rank * 4 + suit` is bit `rank * 4 + suit`, so each rank is one nibble.
+        let bits = hand.bitset();
+        let mut by_suit = [0u16; NUM_SUITS];
+        let mut by_count = [0u16; 5];
+        for rank in 0..NUM_RANKS {
+            let nibble = ((bits >> (rank * 4)) & 0xF) as u16;
+            for (suit, mask) in by_suit.iter_mut().enumerate() {
+                *mask |= ((nibble >> suit) & 1) << rank;
+            }
+            by_count[nibble.count_ones() as usize] |= 1 << rank;
+        }
+
+        let present = ALL_RANKS_MASK & !by_count[0];
+        let singles = by_count[1];
+        let pairs = by_count[2];
+        let trips = by_count[3];
+        let quads = by_count[4];
+        let flush = by_suit.into_iter().find(|m| m.count_ones() >= 5);
+
+        if let Some(suited) = flush {
+            let high = STRAIGHT_HIGHS[suited as usize] as u32;
+            if high != 0 {
+                return Some(HandStrength::new(STRAIGHT_FLUSH, (high - 1) << 16));
+            }
+        }
+
+        if quads != 0 {
+            let rank = highest(quads);
+            let kicker = highest(without(present, rank));
+            return Some(HandStrength::new(
+                FOUR_OF_A_KIND,
+                (rank << 16) | (kicker << 12),
+            ));
+        }
+
+        if trips != 0 {
+            let rank = highest(trips);
+            let rest = without(trips, rank) | pairs;
+            if rest != 0 {
+                return Some(HandStrength::new(
+                    FULL_HOUSE,
+                    (rank << 16) | (highest(rest) << 12),
+                ));
+            }
+        }
+
+        if let Some(suited) = flush {
+            return Some(HandStrength::new(FLUSH, TOP_RANKS[suited as usize]));
+        }
+
+        let high = STRAIGHT_HIGHS[present as usize] as u32;
+        if high != 0 {
+            return Some(HandStrength::new(STRAIGHT, (high - 1) << 16));
+        }
+
+        if trips != 0 {
+            let kickers = (TOP_RANKS[singles as usize] >> 4) & 0xFF00;
+            return Some(HandStrength::new(
+                THREE_OF_A_KIND,
+                (highest(trips) << 16) | kickers,
+            ));
+        }
+
+        if pairs.count_ones() >= 2 {
+            let higher = highest(pairs);
+            let lower = highest(without(pairs, higher));
+            let kicker = highest(without(without(pairs, higher), lower) | singles);
+            return Some(HandStrength::new(
+                TWO_PAIR,
+                (higher << 16) | (lower << 12) | (kicker << 8),
+            ));
+        }
+
+        if pairs != 0 {
+            let kickers = (TOP_RANKS[singles as usize] >> 4) & 0xFFF0;
+            return Some(HandStrength::new(PAIR, (highest(pairs) << 16) | kickers));
+        }
+
+        Some(HandStrength::new(HIGH_CARD, TOP_RANKS[singles as usize]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::hand_evaluator::MatchingEvaluator;
+    use crate::analysis::search_space::fold_combinations;
+    use crate::cards::card::{ALL_CARDS, Card};
+    use crate::util::random::Random;
+
+    fn random_hand(rng: &mut Random, size: usize) -> CardSet {
+        let mut hand = CardSet::new();
+        while hand.len() < size {
+            hand += ALL_CARDS[rng.below(ALL_CARDS.len())];
+        }
+        hand
+    }
+
+    fn assert_evaluators_agree(hand: CardSet) {
+        assert_eq!(
+            TableEvaluator::evaluate(hand),
+            MatchingEvaluator::evaluate(hand),
+            "The evaluators disagree on {}",
+            hand
+        );
+    }
+
+    #[test]
+    fn test_table_evaluator_edge_cases() {
+        let hands: [&[Card]; 6] = [
+            // Wheel
+            &[
+                Card::ACE_SPADE,
+                Card::TWO_HEART,
+                Card::THREE_CLUB,
+                Card::FOUR_DIAMOND,
+                Card::FIVE_SPADE,
+                Card::KING_HEART,
+                Card::NINE_CLUB,
+            ],
+            // Steel wheel
+            &[
+                Card::ACE_SPADE,
+                Card::TWO_SPADE,
+                Card::THREE_SPADE,
+                Card::FOUR_SPADE,
+                Card::FIVE_SPADE,
+                Card::SIX_HEART,
+            ],
+            // Two sets of trips
+            &[
+                Card::TEN_HEART,
+                Card::TEN_SPADE,
+                Card::TEN_CLUB,
+                Card::SEVEN_HEART,
+                Card::SEVEN_CLUB,
+                Card::SEVEN_SPADE,
+                Card::KING_HEART,
+            ],
+            // Three pairs, where the third pair beats the kicker
+            &[
+                Card::ACE_HEART,
+                Card::ACE_SPADE,
+                Card::KING_CLUB,
+                Card::KING_HEART,
+                Card::QUEEN_CLUB,
+                Card::QUEEN_SPADE,
+                Card::TWO_HEART,
+            ],
+            // Quads with a pair
+            &[
+                Card::NINE_HEART,
+                Card::NINE_SPADE,
+                Card::NINE_CLUB,
+                Card::NINE_DIAMOND,
+                Card::SEVEN_CLUB,
+                Card::SEVEN_SPADE,
+                Card::TWO_HEART,
+            ],
+            // A flush and a straight, but not a straight flush
+            &[
+                Card::SIX_HEART,
+                Card::SEVEN_HEART,
+                Card::EIGHT_HEART,
+                Card::NINE_SPADE,
+                Card::TEN_HEART,
+                Card::KING_HEART,
+            ],
+        ];
+
+        for hand in hands {
+            assert_evaluators_agree(CardSet::from(hand));
+        }
+    }
+
+    #[test]
+    fn test_table_evaluator_agrees_on_random_hands() {
+        let mut rng = Random::seeded(4);
+        for size in 5..=7 {
+            for _ in 0..20_000 {
+                assert_evaluators_agree(random_hand(&mut rng, size));
+            }
+        }
+    }
+
+    #[test]
+    fn test_table_evaluator_rejects_bad_sizes() {
+        let four = CardSet::from(&[
+            Card::ACE_SPADE,
+            Card::KING_SPADE,
+            Card::QUEEN_SPADE,
+            Card::JACK_SPADE,
+        ]);
+
+        assert_eq!(TableEvaluator::strength(four), None);
+        assert_eq!(TableEvaluator::strength(CardSet::universe()), None);
+    }
+
+    #[test]
+    fn test_hand_strength_round_trips() {
+        let mut rng = Random::seeded(5);
+        for _ in 0..10_000 {
+            let evaluation = MatchingEvaluator::evaluate(random_hand(&mut rng, 7)).unwrap();
+            assert_eq!(
+                HandEvaluation::from(HandStrength::from(evaluation)),
+                evaluation
+            );
+        }
+    }
+
+    #[test]
+    fn test_hand_strength_orders_like_hand_evaluation() {
+        let mut rng = Random::seeded(6);
+        for _ in 0..10_000 {
+            let a = random_hand(&mut rng, 7);
+            let b = random_hand(&mut rng, 7);
+            assert_eq!(
+                TableEvaluator::strength(a).cmp(&TableEvaluator::strength(b)),
+                MatchingEvaluator::strength(a).cmp(&MatchingEvaluator::strength(b)),
+                "{} and {} are ordered differently",
+                a,
+                b
+            );
+        }
+    }
+
+    #[test]
+    #[ignore = "This test is computationally intensive. Run it with `cargo test --release -- --include-ignored`"]
+    fn test_table_evaluator_agrees_on_every_seven_card_hand() {
+        let (checked, mismatches) = fold_combinations(
+            CardSet::universe(),
+            7,
+            || (0usize, 0usize),
+            |(checked, mismatches), hand| {
+                let agree = TableEvaluator::evaluate(hand) == MatchingEvaluator::evaluate(hand);
+                (checked + 1, mismatches + if agree { 0 } else { 1 })
+            },
+            |a, b| (a.0 + b.0, a.1 + b.1),
+        );
+
+        assert_eq!(checked, 133_784_560);
+        assert_eq!(mismatches, 0);
+    }
+}
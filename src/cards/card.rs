@@ -20,6 +20,18 @@ impl Display for Suit {
     }
 }
 
+impl Suit {
+    // The letter the suit is typed as, e.g. 's' for spades.
+    pub fn letter(&self) -> char {
+        match self {
+            Suit::Club => 'c',
+            Suit::Diamond => 'd',
+            Suit::Heart => 'h',
+            Suit::Spade => 's',
+        }
+    }
+}
+
 pub const NUM_SUITS: usize = 4;
 
 pub const ALL_SUITS: [Suit; NUM_SUITS] = [Suit::Club, Suit::Diamond, Suit::Heart, Suit::Spade];
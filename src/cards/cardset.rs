@@ -8,7 +8,7 @@ use crate::{
     ui::output::format_separated_values,
 };
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
 pub struct CardSet {
     bitset: u64,
 }
@@ -50,6 +50,18 @@ impl CardSet {
     pub fn len(&self) -> usize {
         self.bitset.count_ones() as usize
     }
+
+    // The underlying bits, where card `i` (as given by `card_index`) is bit `i`.
+    pub fn bitset(&self) -> u64 {
+        self.bitset
+    }
+
+    // The inverse of `bitset`. Bits that don't correspond to a card are ignored.
+    pub fn from_bitset(bitset: u64) -> Self {
+        Self {
+            bitset: bitset & Self::universe().bitset,
+        }
+    }
 }
 
 impl Add<Card> for CardSet {
@@ -183,6 +195,20 @@ impl Iterator for CardSetIterator {
     }
 }
 
+// Helpers for the tests of any module that deals with cards.
+#[cfg(test)]
+pub mod testing {
+    use super::CardSet;
+    use crate::cards::card::Card;
+
+    // The cards written in `text`, e.g. "As Kd 7c".
+    pub fn cards(text: &str) -> CardSet {
+        text.split_whitespace()
+            .map(|c| Card::parse(c).unwrap())
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
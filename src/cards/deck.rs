@@ -0,0 +1,70 @@
+use std::fmt::Display;
+
+use crate::cards::card::{ALL_RANKS, ALL_SUITS, Card, Rank};
+use crate::cards::cardset::CardSet;
+
+// The cards a game is dealt from.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Deck {
+    // All 52 cards.
+    Standard,
+    // The 36 cards from 6 to ace.
+    Short,
+}
+
+impl Deck {
+    pub fn lowest_rank(&self) -> Rank {
+        match self {
+            Deck::Standard => Rank::Two,
+            Deck::Short => Rank::Six,
+        }
+    }
+
+    // The highest rank of the straight where the ace plays low, e.g. 5 for A-2-3-4-5.
+    pub fn wheel_high_rank(&self) -> Rank {
+        ALL_RANKS[self.lowest_rank() as usize + 3]
+    }
+
+    pub fn cards(&self) -> CardSet {
+        let mut cards = CardSet::new();
+        for rank in ALL_RANKS.into_iter().filter(|r| *r >= self.lowest_rank()) {
+            for suit in ALL_SUITS {
+                cards += Card { rank, suit };
+            }
+        }
+        cards
+    }
+
+    pub fn contains(&self, card: Card) -> bool {
+        card.rank >= self.lowest_rank()
+    }
+}
+
+impl Display for Deck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Deck::Standard => "standard",
+            Deck::Short => "short",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deck_cards() {
+        assert_eq!(Deck::Standard.cards(), CardSet::universe());
+        assert_eq!(Deck::Short.cards().len(), 36);
+        assert!(!Deck::Short.cards().has(Card::FIVE_CLUB));
+        assert!(Deck::Short.cards().has(Card::SIX_CLUB));
+        assert!(!Deck::Short.contains(Card::TWO_HEART));
+    }
+
+    #[test]
+    fn test_wheel_high_rank() {
+        assert_eq!(Deck::Standard.wheel_high_rank(), Rank::Five);
+        assert_eq!(Deck::Short.wheel_high_rank(), Rank::Nine);
+    }
+}
@@ -0,0 +1,391 @@
+use std::{collections::HashSet, fmt::Display};
+
+use crate::cards::{
+    card::{ALL_CARDS, ALL_RANKS, ALL_SUITS, Card, Rank},
+    cardset::CardSet,
+};
+
+// The weight given to a combo that is always in the range.
+pub const FULL_WEIGHT: usize = 100;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Suitedness {
+    Suited,
+    Offsuit,
+    Any,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct WeightedPocket {
+    pub pocket: CardSet,
+    pub weight: usize,
+}
+
+// A set of pockets that a player could be holding, each with a relative weight.
+//
+// Weights are reduced by their greatest common divisor, so a range where every combo is
+// equally likely has a weight of 1 for every combo.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct HandRange {
+    notation: String,
+    combos: Vec<WeightedPocket>,
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+impl HandRange {
+    // If a pocket appears more than once, only its first occurrence is kept.
+    pub fn new(notation: String, combos: Vec<WeightedPocket>) -> Self {
+        let mut seen = HashSet::<CardSet>::new();
+        let mut combos = combos
+            .into_iter()
+            .filter(|c| c.weight > 0 && seen.insert(c.pocket))
+            .collect::<Vec<WeightedPocket>>();
+
+        let divisor = combos.iter().fold(0, |a, c| gcd(a, c.weight));
+        for combo in &mut combos {
+            combo.weight /= divisor;
+        }
+
+        Self { notation, combos }
+    }
+
+    pub fn combos(&self) -> &[WeightedPocket] {
+        self.combos.as_slice()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    // The single pocket in this range, if there is exactly one.
+    pub fn single_pocket(&self) -> Option<CardSet> {
+        match self.combos.as_slice() {
+            [combo] => Some(combo.pocket),
+            _ => None,
+        }
+    }
+
+    // The notation with suits written as the letters they're typed as, e.g.
+    // "AsKs" for "A♠K♠".
+    pub fn typed_notation(&self) -> String {
+        ALL_SUITS
+            .iter()
+            .fold(self.notation.clone(), |notation, suit| {
+                notation.replace(&suit.to_string(), &suit.letter().to_string())
+            })
+    }
+
+    // Removes every combo that shares a card with `known`.
+    pub fn without(&self, known: CardSet) -> Self {
+        Self {
+            notation: self.notation.clone(),
+            combos: self
+                .combos
+                .iter()
+                .filter(|c| c.pocket.disjoint_with(known))
+                .copied()
+                .collect(),
+        }
+    }
+}
+
+impl From<CardSet> for HandRange {
+    fn from(value: CardSet) -> Self {
+        Self::new(
+            value.to_string(),
+            vec![WeightedPocket {
+                pocket: value,
+                weight: 1,
+            }],
+        )
+    }
+}
+
+impl Display for HandRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.notation)
+    }
+}
+
+// All 6 combos of a pocket pair.
+pub fn pair_combos(rank: Rank) -> Vec<CardSet> {
+    let mut combos = Vec::new();
+    for (i, s1) in ALL_SUITS.iter().enumerate() {
+        for s2 in ALL_SUITS.iter().skip(i + 1) {
+            combos.push(CardSet::from(&[
+                Card { rank, suit: *s1 },
+                Card { rank, suit: *s2 },
+            ]));
+        }
+    }
+    combos
+}
+
+// The 4 suited and/or 12 offsuit combos of two different ranks.
+pub fn unpaired_combos(first: Rank, second: Rank, suitedness: Suitedness) -> Vec<CardSet> {
+    let mut combos = Vec::new();
+    if first == second {
+        return combos;
+    }
+
+    for s1 in ALL_SUITS {
+        for s2 in ALL_SUITS {
+            let wanted = match suitedness {
+                Suitedness::Suited => s1 == s2,
+                Suitedness::Offsuit => s1 != s2,
+                Suitedness::Any => true,
+            };
+            if wanted {
+                combos.push(CardSet::from(&[
+                    Card {
+                        rank: first,
+                        suit: s1,
+                    },
+                    Card {
+                        rank: second,
+                        suit: s2,
+                    },
+                ]));
+            }
+        }
+    }
+    combos
+}
+
+// A card of a partially known pocket: a known card, a rank of unknown suit (`Kx`) or a card
+// that isn't known at all (`??`).
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum CardPattern {
+    Known(Card),
+    Rank(Rank),
+    Any,
+}
+
+impl CardPattern {
+    pub fn matches(&self, card: Card) -> bool {
+        match self {
+            CardPattern::Known(c) => *c == card,
+            CardPattern::Rank(r) => card.rank == *r,
+            CardPattern::Any => true,
+        }
+    }
+}
+
+impl Display for CardPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CardPattern::Known(card) => write!(f, "{}", card),
+            CardPattern::Rank(rank) => write!(f, "{}x", rank),
+            CardPattern::Any => f.write_str("??"),
+        }
+    }
+}
+
+fn extend_placeholder_combos(
+    patterns: &[CardPattern],
+    chosen: CardSet,
+    first_any: usize,
+    combos: &mut Vec<CardSet>,
+) {
+    let Some((pattern, rest)) = patterns.split_first() else {
+        combos.push(chosen);
+        return;
+    };
+
+    for (i, card) in ALL_CARDS.iter().enumerate() {
+        if chosen.has(*card) || !pattern.matches(*card) {
+            continue;
+        }
+        // `??`s are interchangeable, so they only take cards in increasing order.
+        match pattern {
+            CardPattern::Any if i < first_any => {}
+            CardPattern::Any => extend_placeholder_combos(rest, chosen + *card, i + 1, combos),
+            _ => extend_placeholder_combos(rest, chosen + *card, first_any, combos),
+        }
+    }
+}
+
+// Every pocket whose cards can be matched up one-to-one with `patterns`.
+pub fn placeholder_combos(patterns: &[CardPattern]) -> Vec<CardSet> {
+    let mut combos = Vec::new();
+    extend_placeholder_combos(patterns, CardSet::new(), 0, &mut combos);
+
+    let mut seen = HashSet::new();
+    combos.retain(|c| seen.insert(*c));
+    combos
+}
+
+// Every pocket of `pocket_size` cards, for a player whose pocket is unknown.
+pub fn random_range(pocket_size: usize) -> HandRange {
+    let combos = placeholder_combos(&vec![CardPattern::Any; pocket_size])
+        .into_iter()
+        .map(|pocket| WeightedPocket { pocket, weight: 1 })
+        .collect();
+    HandRange::new("random".to_string(), combos)
+}
+
+// The ranks from `low` to `high` inclusive, in ascending order.
+pub fn ranks_between(low: Rank, high: Rank) -> impl Iterator<Item = Rank> {
+    ALL_RANKS
+        .into_iter()
+        .filter(move |r| *r >= low && *r <= high)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pair_combos() {
+        let combos = pair_combos(Rank::Queen);
+
+        assert_eq!(combos.len(), 6);
+        assert_eq!(combos.iter().collect::<HashSet<_>>().len(), 6);
+        assert!(
+            combos
+                .iter()
+                .all(|c| c.len() == 2 && c.iter_desc().all(|x| x.rank == Rank::Queen))
+        );
+    }
+
+    #[test]
+    fn test_placeholder_combos() {
+        let ace = CardPattern::Rank(Rank::Ace);
+        let ace_of_spades = CardPattern::Known(Card::ACE_SPADE);
+
+        assert_eq!(
+            placeholder_combos(&[CardPattern::Any; 2]).len(),
+            52 * 51 / 2
+        );
+        assert_eq!(placeholder_combos(&[CardPattern::Any; 4]).len(), 270725);
+        assert_eq!(
+            placeholder_combos(&[ace_of_spades, CardPattern::Any]).len(),
+            51
+        );
+        assert_eq!(
+            placeholder_combos(&[ace, ace])
+                .into_iter()
+                .collect::<HashSet<_>>(),
+            pair_combos(Rank::Ace).into_iter().collect::<HashSet<_>>()
+        );
+        // Pockets with at least one ace: 6 with two and 4 * 48 with one.
+        assert_eq!(
+            placeholder_combos(&[ace, CardPattern::Any]).len(),
+            6 + 4 * 48
+        );
+        assert!(placeholder_combos(&[ace; 5]).is_empty());
+        assert_eq!(
+            placeholder_combos(&[CardPattern::Known(Card::KING_HEART), ace_of_spades]),
+            vec![CardSet::from(&[Card::KING_HEART, Card::ACE_SPADE])]
+        );
+    }
+
+    #[test]
+    fn test_random_range() {
+        let range = random_range(2);
+        assert_eq!(range.to_string(), "random");
+        assert_eq!(range.len(), 1326);
+        assert!(range.combos().iter().all(|c| c.weight == 1));
+    }
+
+    #[test]
+    fn test_unpaired_combos() {
+        assert_eq!(
+            unpaired_combos(Rank::Ace, Rank::King, Suitedness::Suited).len(),
+            4
+        );
+        assert_eq!(
+            unpaired_combos(Rank::Ace, Rank::King, Suitedness::Offsuit).len(),
+            12
+        );
+        assert_eq!(
+            unpaired_combos(Rank::Ace, Rank::King, Suitedness::Any).len(),
+            16
+        );
+        assert!(unpaired_combos(Rank::Ace, Rank::Ace, Suitedness::Any).is_empty());
+    }
+
+    #[test]
+    fn test_ranks_between() {
+        assert_eq!(
+            ranks_between(Rank::Nine, Rank::Queen).collect::<Vec<Rank>>(),
+            vec![Rank::Nine, Rank::Ten, Rank::Jack, Rank::Queen]
+        );
+    }
+
+    #[test]
+    fn test_hand_range_dedups_and_reduces_weights() {
+        let aks = CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE]);
+        let qq = CardSet::from(&[Card::QUEEN_CLUB, Card::QUEEN_DIAMOND]);
+
+        let range = HandRange::new(
+            "test".to_string(),
+            vec![
+                WeightedPocket {
+                    pocket: aks,
+                    weight: 100,
+                },
+                WeightedPocket {
+                    pocket: qq,
+                    weight: 50,
+                },
+                WeightedPocket {
+                    pocket: aks,
+                    weight: 25,
+                },
+            ],
+        );
+
+        assert_eq!(
+            range.combos(),
+            &[
+                WeightedPocket {
+                    pocket: aks,
+                    weight: 2
+                },
+                WeightedPocket {
+                    pocket: qq,
+                    weight: 1
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hand_range_without() {
+        let range = HandRange::new(
+            "QQ".to_string(),
+            pair_combos(Rank::Queen)
+                .into_iter()
+                .map(|pocket| WeightedPocket { pocket, weight: 1 })
+                .collect(),
+        );
+
+        let remaining = range.without(CardSet::from(&[Card::QUEEN_SPADE, Card::TWO_CLUB]));
+
+        assert_eq!(remaining.len(), 3);
+        assert!(
+            remaining
+                .combos()
+                .iter()
+                .all(|c| !c.pocket.has(Card::QUEEN_SPADE))
+        );
+    }
+
+    #[test]
+    fn test_hand_range_from_pocket() {
+        let aks = CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE]);
+        let range = HandRange::from(aks);
+
+        assert_eq!(range.single_pocket(), Some(aks));
+        assert_eq!(range.to_string(), aks.to_string());
+        assert_eq!(range.typed_notation(), "AsKs");
+    }
+}
@@ -1,2 +1,4 @@
 pub mod card;
 pub mod cardset;
+pub mod deck;
+pub mod hand_range;
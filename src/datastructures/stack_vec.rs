@@ -3,7 +3,7 @@ use std::cmp::Ordering;
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
 use std::mem::MaybeUninit;
-use std::ops::{Index, Range};
+use std::ops::{Index, IndexMut, Range};
 
 // A vector whose memory lives entirely on the stack.
 pub struct StackVec<T, const LENGTH: usize> {
@@ -50,10 +50,6 @@ impl<T, const LENGTH: usize> StackVec<T, LENGTH> {
         self.elems[self.length] = element;
         self.length += 1;
     }
-
-    pub fn reset(&mut self) {
-        self.length = 0;
-    }
 }
 
 impl<T, const LENGTH: usize, const ARR_LENGTH: usize> From<[T; ARR_LENGTH]>
@@ -86,6 +82,17 @@ impl<T: Clone, const LENGTH: usize, const ARR_LENGTH: usize> From<&[T; ARR_LENGT
     }
 }
 
+// Elements past the first `LENGTH` are dropped, like with `push`.
+impl<T, const LENGTH: usize> FromIterator<T> for StackVec<T, LENGTH> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut ret = Self::new();
+        for elem in iter {
+            ret.push(elem);
+        }
+        ret
+    }
+}
+
 impl<T: Clone, const LENGTH: usize> Clone for StackVec<T, LENGTH> {
     fn clone(&self) -> Self {
         let mut ret = Self::new();
@@ -108,6 +115,13 @@ impl<T, const LENGTH: usize> Index<usize> for StackVec<T, LENGTH> {
     }
 }
 
+impl<T, const LENGTH: usize> IndexMut<usize> for StackVec<T, LENGTH> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        assert!(index < self.length);
+        &mut self.elems[index]
+    }
+}
+
 impl<T, const LENGTH: usize> Index<Range<usize>> for StackVec<T, LENGTH> {
     type Output = [T];
 
@@ -242,6 +256,19 @@ mod tests {
         assert_eq!(v1, v2);
     }
 
+    #[test]
+    fn test_stack_vec_from_iter() {
+        let vec = (1..=4).collect::<StackVec<i32, 3>>();
+        assert_eq!(vec.as_slice(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_stack_vec_index_mut() {
+        let mut vec = StackVec::<i32, 3>::from([1, 2]);
+        vec[1] = 5;
+        assert_eq!(vec.as_slice(), &[1, 5]);
+    }
+
     #[test]
     fn test_stack_vec_clone() {
         let mut v1 = StackVec::<i32, 3>::new();
@@ -274,19 +301,6 @@ mod tests {
         assert_eq!(vec.as_slice(), &[1, 2, 3]);
     }
 
-    #[test]
-    fn test_stack_vec_reset() {
-        let mut vec = StackVec::<i32, 3>::new();
-        vec.push(1);
-        vec.push(2);
-        vec.reset();
-        vec.push(3);
-        vec.push(4);
-        vec.push(5);
-
-        assert_eq!(vec.as_slice(), &[3, 4, 5]);
-    }
-
     #[test]
     fn test_stack_vec_eq() {
         let mut v1 = StackVec::<i32, 3>::new();
@@ -0,0 +1,118 @@
+use std::fmt::Display;
+
+use crate::analysis::game::Game;
+use crate::analysis::stud::StudHand;
+use crate::cards::card::Card;
+use crate::cards::cardset::CardSet;
+
+// Why a hand, showdown or deal can't be evaluated as given.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PopError {
+    // A card is in more than one pocket, hand, board or set of dead cards.
+    DuplicateCard(Card),
+    // A card the game's deck doesn't have, e.g. a 2 in short deck.
+    CardNotInDeck {
+        card: Card,
+        game: Game,
+    },
+    InvalidPocketSize {
+        pocket: CardSet,
+        game: Game,
+    },
+    BoardTooLarge(CardSet),
+    // The board doesn't have as many cards as the calculation needs, e.g. a flop or a turn.
+    InvalidBoardSize {
+        board: CardSet,
+        expected: &'static str,
+    },
+    // A stud hand with more than seven cards or more than four of them face up.
+    InvalidStudHand(StudHand),
+    // The number of streets left to deal doesn't match the cards the hand was dealt.
+    StreetMismatch {
+        hand: StudHand,
+        streets_left: usize,
+    },
+    TooFewPlayers {
+        min: usize,
+    },
+    TooManyPlayers {
+        max: usize,
+    },
+    // There aren't enough cards left to complete every hand.
+    NotEnoughCards {
+        needed: usize,
+        left: usize,
+    },
+    // The ranges can't be dealt at the same time without sharing a card.
+    NoMatchup,
+    // Range notation that couldn't be parsed, with the reason.
+    InvalidRange(String),
+    // The ranges' weights multiply to more runouts than can be counted exactly.
+    WeightOverflow,
+}
+
+impl Display for PopError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PopError::DuplicateCard(card) => write!(
+                f,
+                "Cannot have duplicate cards, but {} appears multiple times",
+                card
+            ),
+            PopError::CardNotInDeck { card, game } => write!(
+                f,
+                "{} is not in the {} deck {} is dealt from",
+                card,
+                game.deck(),
+                game
+            ),
+            PopError::InvalidPocketSize { pocket, game } => write!(
+                f,
+                "All {} pockets must have {:?} cards, but {} doesn't",
+                game,
+                game.pocket_sizes(),
+                pocket
+            ),
+            PopError::BoardTooLarge(board) => write!(
+                f,
+                "The board cannot have more than 5 cards, but has {}",
+                board
+            ),
+            PopError::InvalidBoardSize { board, expected } => write!(
+                f,
+                "Expected {} on the board, but the board has {} cards",
+                expected,
+                board.len()
+            ),
+            PopError::InvalidStudHand(hand) => {
+                write!(f, "{} has too many cards for a stud hand", hand)
+            }
+            PopError::StreetMismatch { hand, streets_left } => write!(
+                f,
+                "{} has {} streets to come, not {}",
+                hand,
+                hand.streets_left(),
+                streets_left
+            ),
+            PopError::TooFewPlayers { min } => write!(f, "Need at least {} players", min),
+            PopError::TooManyPlayers { max } => {
+                write!(f, "Cannot have more than {} players", max)
+            }
+            PopError::NotEnoughCards { needed, left } => write!(
+                f,
+                "Need {} cards to complete every hand, but only {} are left",
+                needed, left
+            ),
+            PopError::InvalidRange(message) => f.write_str(message),
+            PopError::NoMatchup => {
+                f.write_str("The given pockets cannot be dealt without duplicate cards")
+            }
+            PopError::WeightOverflow => f.write_str(
+                "The ranges' weights are too uneven to count every runout exactly, so the odds \
+                 must be sampled",
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PopError {}
@@ -0,0 +1,42 @@
+// Poker odds: hand evaluation, ranges and the showdown odds engine behind the `pop` binary.
+//
+// The items re-exported here are the supported API. The modules themselves are private, so
+// internal helpers can change freely.
+
+use std::process::ExitCode;
+
+mod analysis;
+mod cards;
+mod datastructures;
+mod error;
+mod operations;
+mod parallelism;
+mod ui;
+mod util;
+
+pub use crate::analysis::evaluate_hand::{
+    HandCategory, HandEvaluation, HandRanking, PreflopEvaluation,
+};
+pub use crate::analysis::game::Game;
+pub use crate::analysis::hand_distribution::HandDistribution;
+pub use crate::analysis::hand_evaluator::{HandEvaluator, MatchingEvaluator};
+pub use crate::analysis::hi_lo::{HiLoOutcome, LowHand};
+pub use crate::analysis::math::SatisfactionFraction;
+pub use crate::analysis::monte_carlo::{MonteCarloConfig, StoppingRule};
+pub use crate::analysis::odds::{CalculationMethod, OddsCalculation, RangeOddsCalculation};
+pub use crate::analysis::outcomes::Outcome;
+pub use crate::analysis::table_evaluator::{HandStrength, TableEvaluator};
+pub use crate::cards::card::{Card, Rank, Suit};
+pub use crate::cards::cardset::CardSet;
+pub use crate::cards::deck::Deck;
+pub use crate::cards::hand_range::{HandRange, WeightedPocket};
+pub use crate::error::PopError;
+
+// Runs the command line interface with `args`, the executable name first, and returns the exit
+// code for the process.
+pub fn run_cli<I: Iterator<Item = String>>(args: I) -> ExitCode {
+    match ui::input::parse_input(args) {
+        Ok(operation) => operations::run(operation),
+        Err(code) => code,
+    }
+}
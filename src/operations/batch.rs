@@ -0,0 +1,136 @@
+use std::io::BufRead;
+use std::process::ExitCode;
+
+use crate::{
+    operations::showdown::{
+        calculate_odds_from_showdown, print_showdown_text, showdown_header, showdown_json,
+        showdown_rows,
+    },
+    ui::{input::parse_showdown_line, json::Json, output::OutputFormat},
+};
+
+// Reads from stdin instead of a file.
+pub const STDIN_PATH: &str = "-";
+
+pub struct Batch {
+    // The file with a showdown per line, or `STDIN_PATH`.
+    pub path: String,
+    // Overrides any `--format` given on a line, so every result can go in the same output.
+    pub format: OutputFormat,
+}
+
+fn read_lines(path: &str) -> Result<Vec<String>, ExitCode> {
+    let lines = if path == STDIN_PATH {
+        std::io::stdin().lock().lines().collect()
+    } else {
+        std::fs::read_to_string(path).map(|text| text.lines().map(String::from).collect())
+    };
+
+    lines.map_err(|e| {
+        eprintln!("Could not read '{}': {}", path, e);
+        ExitCode::FAILURE
+    })
+}
+
+// Runs the showdown on every line that isn't blank or a `#` comment. Lines that don't parse are
+// reported and skipped, and make the whole batch fail once the other lines are done.
+pub fn run_batch(batch: &Batch) -> ExitCode {
+    let lines = match read_lines(&batch.path) {
+        Ok(lines) => lines,
+        Err(code) => return code,
+    };
+
+    if let Some(separator) = batch.format.separator() {
+        println!("{}", showdown_header(separator));
+    }
+
+    let mut results = Vec::new();
+    let mut failed_lines = 0;
+    for (i, line) in lines.iter().enumerate() {
+        let line_number = i + 1;
+        let text = line.trim();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+
+        let mut showdown = match parse_showdown_line(text) {
+            Ok(showdown) => showdown,
+            Err(_) => {
+                eprintln!("Skipped line {}: {}", line_number, text);
+                failed_lines += 1;
+                continue;
+            }
+        };
+        showdown.format = batch.format;
+        let odds = match calculate_odds_from_showdown(&showdown) {
+            Ok(odds) => odds,
+            Err(e) => {
+                eprintln!("Skipped line {}: {}", line_number, e);
+                failed_lines += 1;
+                continue;
+            }
+        };
+
+        match batch.format {
+            OutputFormat::Text => {
+                println!("Line {}: {}", line_number, text);
+                print_showdown_text(showdown.game, &odds);
+            }
+            OutputFormat::Json => {
+                let mut fields = vec![
+                    ("line", Json::count(line_number)),
+                    ("input", Json::string(text)),
+                ];
+                if let Json::Object(showdown_fields) = showdown_json(&showdown, &odds) {
+                    fields.extend(showdown_fields);
+                }
+                results.push(Json::Object(fields));
+            }
+            OutputFormat::Csv | OutputFormat::Tsv => {
+                let separator = batch.format.separator().unwrap();
+                for row in showdown_rows(line_number, &showdown, &odds, separator) {
+                    println!("{}", row);
+                }
+            }
+        }
+    }
+
+    if batch.format == OutputFormat::Json {
+        println!("{}", Json::Array(results));
+    }
+
+    if failed_lines > 0 {
+        eprintln!("{} line(s) could not be parsed", failed_lines);
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+pub fn print_batch_help(executable_name: &str) {
+    println!(
+        "{} batch: Analyze a showdown for every line of a file",
+        executable_name
+    );
+    println!(
+        "Usage: {} batch <file> [--format <text|json|csv|tsv>]",
+        executable_name
+    );
+    println!();
+    println!(
+        "Each line is a showdown written the same way as for `{} showdown`, e.g.",
+        executable_name
+    );
+    println!("\tAsKs vs QQ on Qc 2c 3c --seed 1");
+    println!("Blank lines and lines starting with # are ignored. Use - as the file to read the");
+    println!("lines from stdin.");
+    println!();
+    println!("Every result is printed in the format given to batch, which overrides a --format");
+    println!("on a line. JSON prints an array with an object per line, which also has the");
+    println!("line number and input. CSV and TSV print a single header, and use the line number");
+    println!("as the matchup number.");
+    println!();
+    println!("Lines that can't be parsed are reported on stderr and skipped, so the output stays");
+    println!("valid JSON, CSV or TSV. The batch then finishes the other lines and exits with a");
+    println!("failure.");
+}
@@ -0,0 +1,108 @@
+use crate::{
+    analysis::board_texture::{BoardTexture, RankedHand, ranked_hands},
+    cards::cardset::CardSet,
+};
+
+pub const DEFAULT_TOP_HANDS: usize = 10;
+
+// Only this many pockets are listed for each hand.
+const MAX_POCKETS_SHOWN: usize = 12;
+
+pub struct Board {
+    pub board: CardSet,
+    // How many of the strongest hands to list.
+    pub top: usize,
+}
+
+fn describe_pairing(texture: &BoardTexture) -> &'static str {
+    match (texture.most_of_a_rank, texture.paired_ranks) {
+        (1, _) => "Unpaired",
+        (2, 1) => "Paired",
+        (2, _) => "Two pair",
+        (3, 1) => "Trips",
+        (3, _) => "Full house",
+        _ => "Quads",
+    }
+}
+
+fn describe_suits(board: CardSet, texture: &BoardTexture) -> String {
+    match texture.most_of_a_suit {
+        n if n == board.len() => "Monotone".to_string(),
+        1 => "Rainbow".to_string(),
+        2 => "Two-tone".to_string(),
+        n => format!("{} cards of a suit", n),
+    }
+}
+
+fn print_ranked_hand(position: usize, ranked: &RankedHand) {
+    let mut pockets = ranked
+        .pockets
+        .iter()
+        .take(MAX_POCKETS_SHOWN)
+        .map(|p| p.to_string())
+        .collect::<Vec<String>>();
+    if ranked.pockets.len() > MAX_POCKETS_SHOWN {
+        pockets.push(format!(
+            "and {} more",
+            ranked.pockets.len() - MAX_POCKETS_SHOWN
+        ));
+    }
+    println!(
+        "{:>3}. {:<12}{}",
+        position,
+        ranked.hand.short_code(),
+        pockets.join(" ")
+    );
+}
+
+pub fn print_board(board: &Board) {
+    let street = match board.board.len() {
+        3 => "flop",
+        4 => "turn",
+        _ => "river",
+    };
+    println!("Board: {} ({})", board.board, street);
+    println!();
+
+    let texture = BoardTexture::analyze(board.board);
+    println!("{}", describe_pairing(&texture));
+    println!("{}", describe_suits(board.board, &texture));
+    println!(
+        "Connectedness:      {} of the board's ranks fit in one straight",
+        texture.connectedness
+    );
+    println!("Possible straights: {}", texture.possible_straights);
+    if board.board.len() < 5 {
+        println!("Flush draws:        {} pockets", texture.flush_draws);
+        println!("Straight draws:     {} pockets", texture.straight_draws);
+    }
+    println!();
+
+    let hands = ranked_hands(board.board);
+    println!("The nuts: {}", hands[0].hand);
+    println!();
+    println!("Top {} hands:", board.top.min(hands.len()));
+    for (i, ranked) in hands.iter().take(board.top).enumerate() {
+        print_ranked_hand(i + 1, ranked);
+    }
+}
+
+pub fn print_board_help(executable_name: &str) {
+    println!(
+        "{} board: Find the nuts and describe the texture of a board",
+        executable_name
+    );
+    println!("Usage: {} board <card>+ [--top <n>]", executable_name);
+    println!();
+    println!("Takes a Hold'em flop, turn or river and prints:");
+    println!("\tWhether it's paired, and whether it's monotone, two-tone or rainbow");
+    println!("\tHow many of its ranks fit in one straight, and how many straights can be made");
+    println!("\tHow many pockets have a flush or a straight draw, before the river");
+    println!(
+        "\tThe {} strongest hands a pocket can make, or as many as --top gives, with the",
+        DEFAULT_TOP_HANDS
+    );
+    println!("\tpockets that make them");
+    println!();
+    println!("Draws only count pockets that help to make the flush or straight.");
+}
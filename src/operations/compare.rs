@@ -0,0 +1,250 @@
+use crate::{
+    analysis::{evaluate_hand::HandEvaluation, game::Game, hi_lo::LowHand},
+    cards::{card::Card, cardset::CardSet},
+};
+
+pub struct Compare {
+    pub game: Game,
+    pub pockets: Vec<CardSet>,
+    // Always 5 cards.
+    pub board: CardSet,
+}
+
+// How one player's pocket fared at showdown.
+pub struct CompareResult {
+    pub pocket: CardSet,
+    // In the order the hand is read, e.g. the pair before the kickers.
+    pub best_five: [Card; 5],
+    pub hand: HandEvaluation,
+    // The player's qualifying low, in hi-lo games.
+    pub low: Option<LowHand>,
+    // 1 for the best high hand. Players with equal hands share a place.
+    pub place: usize,
+    // The fraction of the pot the player wins.
+    pub pot_share: f64,
+}
+
+pub fn calculate_compare(compare: &Compare) -> Vec<CompareResult> {
+    let game = compare.game;
+    let ranking = game.hand_ranking();
+    let hands = compare
+        .pockets
+        .iter()
+        .map(|p| game.evaluate(*p, compare.board).unwrap())
+        .collect::<Vec<HandEvaluation>>();
+    let lows = compare
+        .pockets
+        .iter()
+        .map(|p| game.evaluate_low(*p, compare.board))
+        .collect::<Vec<Option<LowHand>>>();
+
+    let places = hands
+        .iter()
+        .map(|h| {
+            1 + hands
+                .iter()
+                .filter(|o| ranking.key(**o) > ranking.key(*h))
+                .count()
+        })
+        .collect::<Vec<usize>>();
+    let high_winners = places.iter().filter(|p| **p == 1).count();
+    let best_low = lows.iter().flatten().min();
+    let low_winners = lows
+        .iter()
+        .filter(|l| l.is_some() && l.as_ref() == best_low)
+        .count();
+    // Without a qualifying low, the best high hand takes the whole pot.
+    let high_half = if best_low.is_some() { 0.5 } else { 1.0 };
+
+    compare
+        .pockets
+        .iter()
+        .enumerate()
+        .map(|(i, pocket)| {
+            let mut pot_share = 0.0;
+            if places[i] == 1 {
+                pot_share += high_half / high_winners as f64;
+            }
+            if lows[i].is_some() && lows[i].as_ref() == best_low {
+                pot_share += 0.5 / low_winners as f64;
+            }
+            CompareResult {
+                pocket: *pocket,
+                best_five: game.best_five(*pocket, compare.board).unwrap(),
+                hand: hands[i],
+                low: lows[i],
+                place: places[i],
+                pot_share,
+            }
+        })
+        .collect()
+}
+
+fn print_table(rows: &[Vec<String>]) {
+    let widths = (0..rows[0].len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<usize>>();
+    for row in rows {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<String>>()
+            .join("  ");
+        println!("{}", line.trim_end());
+    }
+}
+
+pub fn print_compare(compare: &Compare, results: &[CompareResult]) {
+    let hi_lo = compare.game.is_hi_lo();
+    println!("{} on {}", compare.game, compare.board);
+    println!();
+
+    let mut order = results.iter().collect::<Vec<&CompareResult>>();
+    order.sort_by_key(|r| r.place);
+
+    let mut header = vec!["Place", "Pocket", "Best five", "Played", "Hand"];
+    if hi_lo {
+        header.push("Low");
+    }
+    header.push("Pot");
+    let mut rows = vec![
+        header
+            .iter()
+            .map(|h| h.to_string())
+            .collect::<Vec<String>>(),
+    ];
+    for result in &order {
+        let mut row = vec![
+            result.place.to_string(),
+            result.pocket.to_string(),
+            result.best_five.iter().map(|c| c.to_string()).collect(),
+            match CardSet::from(&result.best_five) & result.pocket {
+                played if played.is_empty() => "-".to_string(),
+                played => played.to_string(),
+            },
+            result.hand.describe(compare.game.deck()),
+        ];
+        if hi_lo {
+            row.push(match &result.low {
+                Some(low) => low.to_string(),
+                None => "-".to_string(),
+            });
+        }
+        row.push(format!("{:.2}%", result.pot_share * 100.0));
+        rows.push(row);
+    }
+    print_table(&rows);
+    println!();
+
+    let winners = order
+        .iter()
+        .filter(|r| r.pot_share > 0.0)
+        .collect::<Vec<&&CompareResult>>();
+    match winners.as_slice() {
+        [winner] => println!(
+            "{} wins the pot with {}",
+            winner.pocket,
+            winner.hand.describe(compare.game.deck())
+        ),
+        _ => {
+            let names = winners
+                .iter()
+                .map(|r| r.pocket.to_string())
+                .collect::<Vec<String>>();
+            println!("{} split the pot", names.join(", "));
+        }
+    }
+}
+
+pub fn print_compare_help(executable_name: &str) {
+    println!(
+        "{} compare: Show who wins a showdown on a complete board, and with what",
+        executable_name
+    );
+    println!(
+        "Usage: {} compare <pocket> [vs <pocket>]+ on <card>{{5}} [--game <game>]",
+        executable_name
+    );
+    println!();
+    println!("Takes exact pockets and a full 5 card board, and prints each player's best five");
+    println!("cards and the hole cards among them, their hand, e.g. `Two Pair, Kings and Nines,");
+    println!("Ace kicker`, their place and their share of the pot. Players with equal hands share");
+    println!("a place and split the pot.");
+    println!("In Omaha Hi/Lo the best qualifying low is shown too, and wins half of the pot.");
+    println!();
+    println!("Pockets, cards and --game are written the same way as for `showdown`.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::cardset::testing::cards;
+
+    fn compare(game: Game, pockets: &[&str], board: &str) -> Vec<CompareResult> {
+        calculate_compare(&Compare {
+            game,
+            pockets: pockets.iter().map(|p| cards(p)).collect(),
+            board: cards(board),
+        })
+    }
+
+    fn places_and_shares(results: &[CompareResult]) -> Vec<(usize, f64)> {
+        results.iter().map(|r| (r.place, r.pot_share)).collect()
+    }
+
+    #[test]
+    fn test_compare_places() {
+        let results = compare(Game::Holdem, &["As Ks", "Qc Qd", "7h 7d"], "Qh 7c 2d 3s 9c");
+
+        assert_eq!(places_and_shares(&results), [(3, 0.0), (1, 1.0), (2, 0.0)]);
+        assert_eq!(results[1].best_five.len(), 5);
+        assert!(results.iter().all(|r| r.low.is_none()));
+    }
+
+    #[test]
+    fn test_compare_ties_share_a_place_and_the_pot() {
+        let results = compare(Game::Holdem, &["As Kd", "Ac Kh", "4s 4h"], "Qh Jc Td 3s 2c");
+
+        // Both broadway straights tie, so the pair of fours is third.
+        assert_eq!(places_and_shares(&results), [(1, 0.5), (1, 0.5), (3, 0.0)]);
+    }
+
+    #[test]
+    fn test_compare_hi_lo_splits() {
+        let results = compare(
+            Game::OmahaHiLo,
+            &["Ah 2h Qc Td", "Ks Kd Js Jd", "Ac 2c Jc Tc"],
+            "3c 5d 8h Kh 9s",
+        );
+
+        // The kings take the high half, and the two 8-5-3-2-A lows split the low half even
+        // though A-K-Q beats A-K-J for high.
+        assert_eq!(
+            places_and_shares(&results),
+            [(2, 0.25), (1, 0.5), (3, 0.25)]
+        );
+        assert!(results[0].low.is_some());
+        assert_eq!(results[0].low, results[2].low);
+        assert_eq!(results[1].low, None);
+        assert_eq!(results.iter().map(|r| r.pot_share).sum::<f64>(), 1.0);
+    }
+
+    #[test]
+    fn test_compare_hi_lo_without_a_qualifying_low() {
+        let results = compare(
+            Game::OmahaHiLo,
+            &["Ah 2h 3c 4d", "Ks Kd 5s 6d"],
+            "Kc Qd Jh 9s 8c",
+        );
+
+        // With three cards above 8 on the board, the high hand takes the whole pot.
+        assert!(results.iter().all(|r| r.low.is_none()));
+        assert_eq!(places_and_shares(&results), [(2, 0.0), (1, 1.0)]);
+    }
+}
@@ -0,0 +1,148 @@
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use crate::analysis::preflop_matrix::{EquityMatrix, HandClass, NUM_HAND_CLASSES, class_equity};
+
+// How often a build saves the cells it has calculated, so an interrupted build loses little.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(60);
+
+pub enum Matrix {
+    // Calculates the cells missing from the matrix at `path`, or at most `cells` of them.
+    Build {
+        path: String,
+        cells: Option<usize>,
+    },
+    Lookup {
+        path: String,
+        hero: HandClass,
+        villain: HandClass,
+    },
+}
+
+fn load_matrix(path: &str) -> Result<EquityMatrix, ExitCode> {
+    let csv = std::fs::read_to_string(path).map_err(|e| {
+        println!("Could not read '{}': {}", path, e);
+        ExitCode::FAILURE
+    })?;
+    EquityMatrix::from_csv(&csv).map_err(|e| {
+        println!("'{}' is not an equity matrix: {}", path, e);
+        ExitCode::FAILURE
+    })
+}
+
+// Writes to a temporary file first, so the matrix at `path` is never left half written.
+fn save_matrix(path: &str, matrix: &EquityMatrix) -> Result<(), ExitCode> {
+    let temporary = format!("{}.tmp", path);
+    std::fs::write(&temporary, matrix.to_csv())
+        .and_then(|_| std::fs::rename(&temporary, path))
+        .map_err(|e| {
+            println!("Could not write '{}': {}", path, e);
+            ExitCode::FAILURE
+        })
+}
+
+fn build_matrix(path: &str, cells: Option<usize>) -> Result<(), ExitCode> {
+    let mut matrix = if Path::new(path).exists() {
+        load_matrix(path)?
+    } else {
+        EquityMatrix::empty()
+    };
+
+    let total = NUM_HAND_CLASSES * (NUM_HAND_CLASSES - 1) / 2;
+    let missing = matrix.missing();
+    let todo = cells.unwrap_or(missing.len()).min(missing.len());
+    println!(
+        "{} of {} matchups done, calculating {} more",
+        total - missing.len(),
+        total,
+        todo
+    );
+
+    let mut last_checkpoint = Instant::now();
+    for (i, (hero, villain)) in missing.iter().take(todo).enumerate() {
+        matrix.set(*hero, *villain, class_equity(*hero, *villain));
+        if last_checkpoint.elapsed() >= CHECKPOINT_INTERVAL {
+            save_matrix(path, &matrix)?;
+            last_checkpoint = Instant::now();
+            println!(
+                "Saved {} of {} matchups (last {} vs {})",
+                total - missing.len() + i + 1,
+                total,
+                hero,
+                villain
+            );
+        }
+    }
+    save_matrix(path, &matrix)?;
+
+    match missing.len() - todo {
+        0 => println!("The matrix is complete"),
+        left => println!(
+            "Saved {} of {} matchups, run the build again to calculate the other {}",
+            total - left,
+            total,
+            left
+        ),
+    }
+    Ok(())
+}
+
+fn lookup(path: &str, hero: HandClass, villain: HandClass) -> Result<(), ExitCode> {
+    let matrix = load_matrix(path)?;
+    match matrix.get(hero, villain) {
+        Some(equity) => {
+            println!("{}: {:.2}%", hero, equity * 100.0);
+            println!("{}: {:.2}%", villain, (1.0 - equity) * 100.0);
+            Ok(())
+        }
+        None => {
+            println!(
+                "{} vs {} hasn't been calculated yet, run the build again to resume it",
+                hero, villain
+            );
+            Err(ExitCode::FAILURE)
+        }
+    }
+}
+
+pub fn run_matrix(matrix: &Matrix) -> ExitCode {
+    let result = match matrix {
+        Matrix::Build { path, cells } => build_matrix(path, *cells),
+        Matrix::Lookup {
+            path,
+            hero,
+            villain,
+        } => lookup(path, *hero, *villain),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(code) => code,
+    }
+}
+
+pub fn print_matrix_help(executable_name: &str) {
+    println!(
+        "{} matrix: Build and look up the preflop equities of every Hold'em starting hand",
+        executable_name
+    );
+    println!(
+        "Usage: {} matrix build <file> [--cells <n>]",
+        executable_name
+    );
+    println!(
+        "       {} matrix lookup <file> <hand> vs <hand>",
+        executable_name
+    );
+    println!();
+    println!("`build` calculates the heads-up all-in equity of each of the 169 starting hands,");
+    println!("such as AA, AKs or AKo, against each other. Every matchup of their combos and every");
+    println!("board is enumerated, so a full build takes hours. The matrix is saved to the CSV");
+    println!("file every minute and at the end. Running the build again on the same file resumes");
+    println!("it, and --cells stops it after calculating that many more matchups.");
+    println!();
+    println!("`lookup` reads the equities of two starting hands from the file.");
+    println!();
+    println!("The CSV has a header of the hands, then a row per hand with its equity against");
+    println!("each column between 0 and 1, left empty until it has been calculated.");
+}
@@ -1 +1,72 @@
+use std::process::ExitCode;
+
+use crate::operations::batch::{Batch, run_batch};
+use crate::operations::board::{Board, print_board};
+use crate::operations::compare::{Compare, calculate_compare, print_compare};
+use crate::operations::matrix::{Matrix, run_matrix};
+use crate::operations::outs::{Outs, calculate_outs, print_outs};
+use crate::operations::repl::run_repl;
+use crate::operations::showdown::{Showdown, calculate_odds_from_showdown, print_showdown_odds};
+use crate::operations::stud::{Stud, calculate_odds_from_stud, print_stud_odds};
+use crate::operations::vs_random::{VsRandom, calculate_vs_random, print_vs_random};
+
+pub mod batch;
+pub mod board;
+pub mod compare;
+pub mod matrix;
+pub mod outs;
+pub mod repl;
 pub mod showdown;
+pub mod stud;
+pub mod vs_random;
+
+// An operation parsed from the command line. Only one is made per run, so its size doesn't matter.
+#[allow(clippy::large_enum_variant)]
+pub enum Operation {
+    Showdown(Showdown),
+    Stud(Stud),
+    Batch(Batch),
+    Repl,
+    Outs(Outs),
+    Matrix(Matrix),
+    VsRandom(VsRandom),
+    Board(Board),
+    Compare(Compare),
+}
+
+// Runs `operation`, printing its results, and returns the exit code for the process.
+pub fn run(operation: Operation) -> ExitCode {
+    let result = match operation {
+        Operation::Showdown(showdown) => calculate_odds_from_showdown(&showdown)
+            .map(|odds| print_showdown_odds(&showdown, &odds)),
+        Operation::Stud(stud) => {
+            calculate_odds_from_stud(&stud).map(|odds| print_stud_odds(&stud, &odds))
+        }
+        Operation::Batch(batch) => return run_batch(&batch),
+        Operation::Matrix(matrix) => return run_matrix(&matrix),
+        Operation::Board(board) => {
+            print_board(&board);
+            Ok(())
+        }
+        Operation::Compare(compare) => {
+            let results = calculate_compare(&compare);
+            print_compare(&compare, &results);
+            Ok(())
+        }
+        Operation::VsRandom(vs_random) => {
+            calculate_vs_random(&vs_random).map(|odds| print_vs_random(&vs_random, &odds))
+        }
+        Operation::Repl => return run_repl(),
+        Operation::Outs(outs) => {
+            calculate_outs(&outs).map(|calculation| print_outs(&outs, &calculation))
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{
+    analysis::{
+        evaluate_hand::{HandCategory, HandEvaluation},
+        game::Game,
+        outs::OutsCalculation,
+    },
+    cards::cardset::CardSet,
+    error::PopError,
+};
+
+// More players than this don't fit in the table of next cards.
+pub const MAX_OUTS_PLAYERS: usize = 6;
+
+pub struct Outs {
+    pub game: Game,
+    pub pockets: Vec<CardSet>,
+    // A flop or a turn.
+    pub board: CardSet,
+    pub dead: CardSet,
+}
+
+pub fn calculate_outs(outs: &Outs) -> Result<OutsCalculation, PopError> {
+    if outs.pockets.len() > MAX_OUTS_PLAYERS {
+        return Err(PopError::TooManyPlayers {
+            max: MAX_OUTS_PLAYERS,
+        });
+    }
+    OutsCalculation::calculate(outs.game, &outs.pockets, outs.board, outs.dead)
+}
+
+// The kind of `hand`, written to follow "to", e.g. "a flush" or "two pair".
+fn hand_phrase(hand: &HandEvaluation) -> String {
+    let category = hand.category();
+    let name = category.name().to_lowercase();
+    match category {
+        HandCategory::HighCard
+        | HandCategory::TwoPair
+        | HandCategory::ThreeOfAKind
+        | HandCategory::FourOfAKind => name,
+        _ => format!("a {}", name),
+    }
+}
+
+pub fn print_outs(outs: &Outs, calculation: &OutsCalculation) {
+    let street = if outs.board.len() == 3 {
+        "turn"
+    } else {
+        "river"
+    };
+    println!("{} on {}, every {} card", outs.game, outs.board, street);
+    if !outs.dead.is_empty() {
+        println!("Dead: {}", outs.dead);
+    }
+    println!();
+
+    let names = calculation
+        .pockets
+        .iter()
+        .map(|p| p.to_string())
+        .collect::<Vec<String>>();
+    let width = names
+        .iter()
+        .map(|n| n.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(7)
+        + 2;
+
+    print!("{:<6}", "Card");
+    for name in &names {
+        print!("{:<width$}", name, width = width);
+    }
+    println!("Favourite");
+
+    print!("{:<6}", "Now");
+    for equity in &calculation.equities {
+        print!(
+            "{:<width$}",
+            format!("{:.2}%", equity * 100.0),
+            width = width
+        );
+    }
+    println!();
+
+    for next in &calculation.next_cards {
+        print!("{:<6}", next.card.to_string());
+        for equity in &next.equities {
+            print!(
+                "{:<width$}",
+                format!("{:.2}%", equity * 100.0),
+                width = width
+            );
+        }
+        match next.favourite() {
+            Some(player) => println!("{}", names[player]),
+            None => println!("-"),
+        }
+    }
+
+    for (player, name) in names.iter().enumerate() {
+        let favourite_count = calculation
+            .next_cards
+            .iter()
+            .filter(|n| n.favourite() == Some(player))
+            .count();
+        println!();
+        println!(
+            "{}: the favourite after {} of {} cards",
+            name,
+            favourite_count,
+            calculation.next_cards.len()
+        );
+
+        let groups = calculation.outs(player);
+        if groups.is_empty() {
+            println!("\tNo card improves {}", name);
+        }
+        for group in groups {
+            let beaten_by = group
+                .beaten_by
+                .iter()
+                .map(|(opponent, hand, count)| {
+                    format!(
+                        "{} of which also give {} {}",
+                        count,
+                        names[*opponent],
+                        hand_phrase(hand)
+                    )
+                })
+                .collect::<Vec<String>>();
+            let cards = group
+                .cards
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<String>>();
+            print!(
+                "\t{} {} to {}",
+                group.cards.len(),
+                if group.cards.len() == 1 {
+                    "out"
+                } else {
+                    "outs"
+                },
+                hand_phrase(&group.hand)
+            );
+            for text in beaten_by {
+                print!(", {}", text);
+            }
+            println!(" ({})", cards.join(" "));
+        }
+    }
+}
+
+pub fn print_outs_help(executable_name: &str) {
+    println!(
+        "{} outs: Show how every next card changes a showdown",
+        executable_name
+    );
+    println!(
+        "Usage: {} outs <pocket> [vs <pocket>]* on <flop or turn> [dead <card>+] [--game <game>]",
+        executable_name
+    );
+    println!();
+    println!("Deals every card that can come next on the flop or turn, and prints each");
+    println!("player's equity after it and which player is then the favourite. Equity is the");
+    println!("share of the pot won on average, where a split pot counts as a share of it.");
+    println!(
+        "You must give between 2 and {} exact pockets, not ranges.",
+        MAX_OUTS_PLAYERS
+    );
+    println!();
+    println!("The cards that give a player a better kind of hand are then grouped by that hand,");
+    println!("e.g. `9 outs to a flush, 2 of which also give Q♠Q♦ a full house`, which counts");
+    println!("the outs that still leave an opponent with the better hand.");
+    println!();
+    println!("Pockets, cards and --game are written the same way as for `showdown`.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::card::Rank;
+
+    #[test]
+    fn test_hand_phrase() {
+        let flush = HandEvaluation::Flush {
+            ranks_sorted_desc: [Rank::Ace, Rank::Ten, Rank::Eight, Rank::Five, Rank::Two],
+        };
+        let two_pair = HandEvaluation::TwoPair {
+            higher_rank: Rank::King,
+            lower_rank: Rank::Nine,
+            kicker: Rank::Ace,
+        };
+
+        assert_eq!(hand_phrase(&flush), "a flush");
+        assert_eq!(hand_phrase(&two_pair), "two pair");
+    }
+}
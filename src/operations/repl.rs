@@ -0,0 +1,135 @@
+use std::io::{BufRead, Write};
+use std::process::ExitCode;
+
+use crate::{
+    operations::showdown::{ShowdownSpec, calculate_odds_from_showdown, print_showdown_odds},
+    ui::{
+        argparser::ArgStream,
+        input::{build_showdown, parse_repl_command},
+    },
+};
+
+pub enum ReplCommand {
+    // Replaces the current showdown, keeping the old one for `undo`.
+    Change(ShowdownSpec),
+    Undo,
+    Show,
+    Help,
+    Quit,
+}
+
+fn print_state(spec: &ShowdownSpec) {
+    if let Some(game) = spec.game {
+        println!("Game:    {}", game);
+    }
+    let players = spec
+        .pockets
+        .iter()
+        .enumerate()
+        .map(|(i, range)| format!("{}. {}", i + 1, range))
+        .collect::<Vec<String>>();
+    println!("Players: {}", players.join("  "));
+    println!("Board:   {}", spec.board);
+    if !spec.dead.is_empty() {
+        println!("Dead:    {}", spec.dead);
+    }
+    println!();
+}
+
+// Prints the showdown and its equities. Nothing is calculated until there are two players.
+fn show(spec: &ShowdownSpec) {
+    print_state(spec);
+    if spec.pockets.len() < 2 {
+        println!("Add at least 2 players with `add player <range>` to see their equities");
+        return;
+    }
+    if let Ok(showdown) = build_showdown(spec.clone()) {
+        match calculate_odds_from_showdown(&showdown) {
+            Ok(odds) => print_showdown_odds(&showdown, &odds),
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
+// Reads commands from stdin until `quit` or the end of the input, recalculating the equities
+// after every change. Changes that make the showdown impossible to deal are rejected.
+pub fn run_repl() -> ExitCode {
+    println!("Type `help` for a list of commands");
+
+    let mut spec = ShowdownSpec::default();
+    let mut history = Vec::<ShowdownSpec>::new();
+    let mut lines = std::io::stdin().lock().lines();
+
+    loop {
+        print!("> ");
+        let _ = std::io::stdout().flush();
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => break,
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut stream = ArgStream::from(line.split_whitespace().map(String::from));
+        let command = match parse_repl_command(&mut stream, &spec) {
+            Ok(_) if !stream.is_empty() => {
+                println!("Unexpected trailing input in '{}'", line.trim());
+                continue;
+            }
+            Ok(command) => command,
+            Err(_) => continue,
+        };
+
+        match command {
+            ReplCommand::Change(changed) => {
+                if changed.pockets.len() >= 2 && build_showdown(changed.clone()).is_err() {
+                    continue;
+                }
+                history.push(std::mem::replace(&mut spec, changed));
+                show(&spec);
+            }
+            ReplCommand::Undo => match history.pop() {
+                Some(previous) => {
+                    spec = previous;
+                    show(&spec);
+                }
+                None => println!("Nothing to undo"),
+            },
+            ReplCommand::Show => show(&spec),
+            ReplCommand::Help => print_repl_commands(),
+            ReplCommand::Quit => break,
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn print_repl_commands() {
+    println!("\tplayers <range> [vs <range>]*  replace every player");
+    println!("\tadd player <range>             add a player");
+    println!("\tremove player <n>              remove the nth player");
+    println!("\tboard <card>+                  replace the board");
+    println!("\tboard +<card>+                 deal more cards to the board, e.g. `board +Kh`");
+    println!("\tboard clear                    remove every card from the board");
+    println!("\tdead <card>+, dead +<card>+, dead clear");
+    println!("\t                               the same for the dead cards");
+    println!("\t--<option> <value>             set a `showdown` option, e.g. `--game omaha`");
+    println!("\treset                          start over with no players or cards");
+    println!("\tundo                           take back the last change");
+    println!("\tshow                           print the showdown and its equities again");
+    println!("\tquit                           leave the REPL");
+}
+
+pub fn print_repl_help(executable_name: &str) {
+    println!(
+        "{} repl: Analyze a showdown interactively, street by street",
+        executable_name
+    );
+    println!("Usage: {} repl", executable_name);
+    println!();
+    println!("Reads commands from stdin, one per line, and recalculates the equities after");
+    println!("every change. Ranges, cards and options are written the same way as for");
+    println!("`{} showdown`. The commands are:", executable_name);
+    print_repl_commands();
+}
@@ -1,92 +1,666 @@
 use crate::{
-    analysis::odds::OddsCalculation, cards::cardset::CardSet, util::array::MonomorphizedArray,
+    analysis::{
+        evaluate_hand::HandRanking,
+        game::Game,
+        hand_distribution::HandDistribution,
+        hi_lo::HiLoOutcome,
+        math::SatisfactionFraction,
+        monte_carlo::{MonteCarloConfig, StoppingRule},
+        odds::{CalculationMethod, RangeOddsCalculation},
+        search_space::count_runouts,
+    },
+    cards::{cardset::CardSet, hand_range::HandRange},
+    error::PopError,
+    ui::{
+        json::Json,
+        output::{OutputFormat, SeparatedRow, format_typed_cards},
+    },
+    util::random::Random,
 };
 
+// Showdowns with more runouts than this are sampled instead of enumerated.
+pub const EXACT_RUNOUT_LIMIT: usize = 20_000_000;
+
+// The standard error automatically sampled showdowns aim for, about 0.05 percentage points.
+pub const DEFAULT_STANDARD_ERROR: f64 = 0.0005;
+
 pub struct Showdown {
-    pub pockets: MonomorphizedArray<CardSet>,
+    pub game: Game,
+    pub pockets: Vec<HandRange>,
+    pub board: CardSet,
+    // Cards that are out of play, e.g. a folded hand that was shown or an exposed burn card.
+    pub dead: CardSet,
+    pub format: OutputFormat,
+    // Forces sampling with this stopping rule, even if the showdown is small enough to enumerate.
+    pub stopping_rule: Option<StoppingRule>,
+    pub seed: Option<u64>,
+}
+
+// A showdown as it was written, before it's checked and its game is picked. See
+// `build_showdown`.
+#[derive(Debug, Clone, Default)]
+pub struct ShowdownSpec {
+    // Picked from the size of the first pocket if not given.
+    pub game: Option<Game>,
+    pub pockets: Vec<HandRange>,
     pub board: CardSet,
+    pub dead: CardSet,
+    pub format: OutputFormat,
+    pub stopping_rule: Option<StoppingRule>,
+    pub seed: Option<u64>,
+}
+
+pub fn calculate_odds_from_showdown(
+    showdown: &Showdown,
+) -> Result<Vec<RangeOddsCalculation>, PopError> {
+    let ranges = showdown.pockets.as_slice();
+    let stopping_rule = match showdown.stopping_rule {
+        Some(rule) => rule,
+        None if count_runouts(
+            showdown.game,
+            ranges,
+            showdown.board,
+            showdown.dead,
+            EXACT_RUNOUT_LIMIT + 1,
+        ) > EXACT_RUNOUT_LIMIT =>
+        {
+            StoppingRule::StandardError(DEFAULT_STANDARD_ERROR)
+        }
+        // Weights too uneven to count exactly are sampled too.
+        None => match RangeOddsCalculation::calculate(
+            showdown.game,
+            ranges,
+            showdown.board,
+            showdown.dead,
+        ) {
+            Err(PopError::WeightOverflow) => StoppingRule::StandardError(DEFAULT_STANDARD_ERROR),
+            odds => return odds,
+        },
+    };
+
+    let config = MonteCarloConfig {
+        stopping_rule,
+        seed: showdown.seed.unwrap_or_else(Random::seed_from_time),
+    };
+    RangeOddsCalculation::sample(showdown.game, ranges, showdown.board, showdown.dead, config)
+}
+
+pub fn print_showdown_odds(showdown: &Showdown, odds: &[RangeOddsCalculation]) {
+    match showdown.format {
+        OutputFormat::Text => print_showdown_text(showdown.game, odds),
+        OutputFormat::Json => println!("{}", showdown_json(showdown, odds)),
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            let separator = showdown.format.separator().unwrap();
+            println!("{}", showdown_header(separator));
+            for row in showdown_rows(1, showdown, odds, separator) {
+                println!("{}", row);
+            }
+        }
+    }
 }
 
-pub fn calculate_odds_from_showdown(showdown: &Showdown) -> Vec<OddsCalculation> {
-    match showdown.pockets {
-        MonomorphizedArray::Len2(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len3(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len4(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len5(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len6(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len7(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len8(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len9(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len10(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len11(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len12(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len13(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len14(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len15(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len16(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len17(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len18(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len19(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len20(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len21(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len22(a) => OddsCalculation::calculate(&a, showdown.board),
-        MonomorphizedArray::Len23(a) => OddsCalculation::calculate(&a, showdown.board),
-        _ => panic!("Must have 2-23 pockets to calculate odds for showdown"),
-    }
-}
-
-pub fn print_odds(odds: &OddsCalculation) {
-    println!("{}", odds.pocket);
-    println!("Win:  {:.2}%", odds.outcome.win_ratio().percentage());
-    println!("Draw: {:.2}%", odds.outcome.draw_ratio().percentage());
-    println!("Loss: {:.2}%", odds.outcome.loss_ratio().percentage());
+pub fn print_showdown_text(game: Game, odds: &[RangeOddsCalculation]) {
+    if let Some(CalculationMethod::MonteCarlo { seed }) = odds.first().map(|o| o.method) {
+        println!(
+            "Estimated from {} random runouts (seed {})",
+            odds[0].outcome.total_hand_count(),
+            seed
+        );
+        println!();
+    }
+
+    for player in odds {
+        match &player.hi_lo {
+            Some(hi_lo) => print_hi_lo_odds(game, player, hi_lo),
+            None => print_odds(game, player),
+        }
+        println!();
+    }
+}
+
+pub fn print_odds(game: Game, odds: &RangeOddsCalculation) {
+    println!("{}", odds.range);
+    match odds.method {
+        CalculationMethod::Exhaustive => {
+            println!("Win:  {:.2}%", odds.outcome.win_ratio().percentage());
+            println!("Draw: {:.2}%", odds.outcome.draw_ratio().percentage());
+            println!("Loss: {:.2}%", odds.outcome.loss_ratio().percentage());
+        }
+        CalculationMethod::MonteCarlo { .. } => {
+            for (label, ratio) in [
+                ("Win: ", odds.outcome.win_ratio()),
+                ("Draw:", odds.outcome.draw_ratio()),
+                ("Loss:", odds.outcome.loss_ratio()),
+            ] {
+                println!(
+                    "{} {:.2}% ± {:.2}%",
+                    label,
+                    ratio.percentage(),
+                    ratio.margin_of_error_percentage()
+                );
+            }
+        }
+    }
     println!("");
+    print_hand_distribution(game.hand_ranking(), &odds.hand_distribution);
+}
+
+// Prints the odds of a split-pot game, where the pot is divided between the best high and the
+// best low hand.
+pub fn print_hi_lo_odds(game: Game, odds: &RangeOddsCalculation, hi_lo: &HiLoOutcome) {
+    println!("{}", odds.range);
+    println!("Pot share:    {:.2}%", hi_lo.pot_share_percentage());
+    for (label, ratio) in [
+        ("Scoop:       ", hi_lo.scoop_ratio()),
+        ("High only:   ", hi_lo.high_only_ratio()),
+        ("Low only:    ", hi_lo.low_only_ratio()),
+        ("High and low:", hi_lo.high_and_low_ratio()),
+        ("Quartered:   ", hi_lo.quartered_ratio()),
+        ("Loss:        ", hi_lo.loss_ratio()),
+        ("Made low:    ", hi_lo.low_made_ratio()),
+    ] {
+        print_ratio(label, ratio, odds.method);
+    }
+    println!();
+    print_hand_distribution(game.hand_ranking(), &odds.hand_distribution);
+}
+
+pub fn print_ratio(label: &str, ratio: SatisfactionFraction, method: CalculationMethod) {
+    match method {
+        CalculationMethod::Exhaustive => println!("{} {:.2}%", label, ratio.percentage()),
+        CalculationMethod::MonteCarlo { .. } => println!(
+            "{} {:.2}% ± {:.2}%",
+            label,
+            ratio.percentage(),
+            ratio.margin_of_error_percentage()
+        ),
+    }
+}
+
+pub fn print_hand_distribution(ranking: HandRanking, hand_distribution: &HandDistribution) {
     println!("Hand distribution:");
+    for (category, percentage) in hand_distribution.percentages_by_rank(ranking) {
+        println!("{:<17}{:.2}%", format!("{}:", category.name()), percentage);
+    }
+}
+
+fn ratios_json(
+    ratios: [(&'static str, SatisfactionFraction); 3],
+    value: fn(&SatisfactionFraction) -> f64,
+) -> Json {
+    Json::Object(
+        ratios
+            .into_iter()
+            .map(|(name, ratio)| (name, Json::Float(value(&ratio))))
+            .collect(),
+    )
+}
+
+fn hand_distribution_json(hd: &HandDistribution) -> Json {
+    Json::Object(vec![
+        ("straight_flushes", Json::count(hd.straight_flushes)),
+        ("four_of_a_kinds", Json::count(hd.four_of_a_kinds)),
+        ("full_houses", Json::count(hd.full_houses)),
+        ("flushes", Json::count(hd.flushes)),
+        ("straights", Json::count(hd.straights)),
+        ("three_of_a_kinds", Json::count(hd.three_of_a_kinds)),
+        ("two_pairs", Json::count(hd.two_pairs)),
+        ("pairs", Json::count(hd.pairs)),
+        ("high_cards", Json::count(hd.high_cards)),
+    ])
+}
+
+fn hi_lo_json(hi_lo: &HiLoOutcome) -> Json {
+    Json::Object(vec![
+        ("scoops", Json::count(hi_lo.scoops)),
+        ("high_only", Json::count(hi_lo.high_only)),
+        ("low_only", Json::count(hi_lo.low_only)),
+        ("high_and_low", Json::count(hi_lo.high_and_low)),
+        ("quartered", Json::count(hi_lo.quartered)),
+        ("losses", Json::count(hi_lo.losses)),
+        ("lows_made", Json::count(hi_lo.lows_made)),
+        ("pot_share", Json::Float(hi_lo.pot_share_percentage())),
+    ])
+}
+
+fn player_json(odds: &RangeOddsCalculation) -> Json {
+    let outcome = &odds.outcome;
+    let ratios = [
+        ("win", outcome.win_ratio()),
+        ("draw", outcome.draw_ratio()),
+        ("loss", outcome.loss_ratio()),
+    ];
+
+    let mut fields = vec![
+        ("range", Json::string(odds.range.typed_notation())),
+        ("combos", Json::count(odds.range.len())),
+        ("total", Json::count(outcome.total_hand_count())),
+        ("wins", Json::count(ratios[0].1.satisfying)),
+        ("draws", Json::count(ratios[1].1.satisfying)),
+        ("losses", Json::count(outcome.losses)),
+        (
+            "draws_with",
+            Json::Array(outcome.draws_with.iter().map(|n| Json::count(*n)).collect()),
+        ),
+        (
+            "percentages",
+            ratios_json(ratios, SatisfactionFraction::percentage),
+        ),
+    ];
+    if let CalculationMethod::MonteCarlo { .. } = odds.method {
+        fields.push((
+            "margins_of_error",
+            ratios_json(ratios, SatisfactionFraction::margin_of_error_percentage),
+        ));
+    }
+    fields.push((
+        "hand_distribution",
+        hand_distribution_json(&odds.hand_distribution),
+    ));
+    if let Some(hi_lo) = &odds.hi_lo {
+        fields.push(("hi_lo", hi_lo_json(hi_lo)));
+    }
+    Json::Object(fields)
+}
+
+// The results of a showdown in the schema described by `print_showdown_help`.
+pub fn showdown_json(showdown: &Showdown, odds: &[RangeOddsCalculation]) -> Json {
+    let mut fields = vec![
+        ("game", Json::string(showdown.game.name())),
+        ("board", Json::cards(showdown.board)),
+        ("dead", Json::cards(showdown.dead)),
+    ];
+    match odds.first().map(|o| o.method) {
+        Some(CalculationMethod::MonteCarlo { seed }) => {
+            fields.push(("method", Json::string("monte_carlo")));
+            fields.push(("seed", Json::Integer(seed)));
+        }
+        _ => fields.push(("method", Json::string("exhaustive"))),
+    }
+    fields.push((
+        "players",
+        Json::Array(odds.iter().map(player_json).collect()),
+    ));
+    Json::Object(fields)
+}
+
+// The columns of the CSV and TSV output, one row per player per matchup.
+pub const SHOWDOWN_COLUMNS: [&str; 23] = [
+    "matchup",
+    "player",
+    "range",
+    "board",
+    "dead",
+    "game",
+    "method",
+    "total",
+    "wins",
+    "draws",
+    "losses",
+    "win_percentage",
+    "draw_percentage",
+    "loss_percentage",
+    "straight_flushes",
+    "four_of_a_kinds",
+    "full_houses",
+    "flushes",
+    "straights",
+    "three_of_a_kinds",
+    "two_pairs",
+    "pairs",
+    "high_cards",
+];
+
+pub fn showdown_header(separator: &'static str) -> SeparatedRow {
+    SeparatedRow {
+        values: SHOWDOWN_COLUMNS.map(String::from).to_vec(),
+        separator,
+    }
+}
+
+// The rows of the `matchup`th showdown of a run, numbered from 1.
+pub fn showdown_rows(
+    matchup: usize,
+    showdown: &Showdown,
+    odds: &[RangeOddsCalculation],
+    separator: &'static str,
+) -> Vec<SeparatedRow> {
+    odds.iter()
+        .enumerate()
+        .map(|(i, player)| {
+            let outcome = &player.outcome;
+            let hd = &player.hand_distribution;
+            let method = match player.method {
+                CalculationMethod::Exhaustive => "exhaustive",
+                CalculationMethod::MonteCarlo { .. } => "monte_carlo",
+            };
+            let values = [
+                matchup.to_string(),
+                (i + 1).to_string(),
+                player.range.typed_notation(),
+                format_typed_cards(showdown.board),
+                format_typed_cards(showdown.dead),
+                showdown.game.name().to_string(),
+                method.to_string(),
+                outcome.total_hand_count().to_string(),
+                outcome.win_ratio().satisfying.to_string(),
+                outcome.draw_ratio().satisfying.to_string(),
+                outcome.losses.to_string(),
+                outcome.win_ratio().percentage().to_string(),
+                outcome.draw_ratio().percentage().to_string(),
+                outcome.loss_ratio().percentage().to_string(),
+                hd.straight_flushes.to_string(),
+                hd.four_of_a_kinds.to_string(),
+                hd.full_houses.to_string(),
+                hd.flushes.to_string(),
+                hd.straights.to_string(),
+                hd.three_of_a_kinds.to_string(),
+                hd.two_pairs.to_string(),
+                hd.pairs.to_string(),
+                hd.high_cards.to_string(),
+            ];
+            SeparatedRow {
+                values: values.to_vec(),
+                separator,
+            }
+        })
+        .collect()
+}
+
+pub fn print_showdown_help(executable_name: &str) {
+    println!("{} showdown: Analyze odds for a showdown", executable_name);
     println!(
-        "Straight Flush:  {:.2}%",
-        odds.hand_distribution.straight_flush_percentage()
-    );
-    println!(
-        "Four of a Kind:  {:.2}%",
-        odds.hand_distribution.four_of_a_kind_percentage()
-    );
-    println!(
-        "Full House:      {:.2}%",
-        odds.hand_distribution.full_house_percentage()
-    );
-    println!(
-        "Flush:           {:.2}%",
-        odds.hand_distribution.flush_percentage()
-    );
-    println!(
-        "Straight:        {:.2}%",
-        odds.hand_distribution.straight_percentage()
-    );
-    println!(
-        "Three of a Kind: {:.2}%",
-        odds.hand_distribution.three_of_a_kind_percentage()
+        "Usage: {} showdown <range> [vs <range>]* [on <card>+] [dead <card>+] [--game <game>] [--format <text|json|csv|tsv>] [--trials <n> | --stderr <percent>] [--seed <n>]",
+        executable_name
     );
+    println!();
+    println!("Analyzes the given pockets or ranges on the given board.");
+    println!("You must give at least two pockets and no more than 5 cards on the board.");
+    println!();
+    println!("A range is a comma-separated list of any of the following:");
+    println!("\tAsKs      a specific pocket");
+    println!("\tQQ        a pocket pair");
+    println!("\tAKs, AKo  suited or offsuit combos (AK is both)");
+    println!("\tQQ+, ATs+ a pair and every higher pair, or a hand and every higher kicker");
+    println!("\t22-99     every pair from 22 to 99 (A2s-A5s works the same way)");
+    println!("\tAs??, KxQx a pocket with placeholders, where ?? is any card and Kx is a king");
+    println!("\t          of any suit (???? is a random hand)");
+    println!("Append :<weight> with a weight between 0 and 1 to include combos only partially,");
+    println!("e.g. `QQ+, AKs, AQs:0.5`. Combos that conflict with known cards are removed.");
+    println!();
+    println!("Cards that are known to be out of play, like a folded hand that was shown or an");
+    println!("exposed burn card, go after `dead`. They are never dealt to a pocket or the board.");
+    println!();
     println!(
-        "Two Pair:        {:.2}%",
-        odds.hand_distribution.two_pair_percentage()
+        "Omaha pockets are written as 4 to 6 cards, e.g. `AsAhKsKh`, `As Ah Ks Kh` or `AxAx????`."
     );
+    println!("Each player must use exactly 2 of their cards and exactly 3 from the board.");
+    println!("The game is picked from the size of the pockets, or can be given explicitly:");
+    println!("\t--game <holdem|omaha|omaha8|shortdeck|shortdeck-trips>");
+    println!("Omaha Hi/Lo (omaha8) splits each pot between the best high hand and the best");
+    println!("8-or-better ace-to-five low, and reports scoop, split and pot share figures.");
+    println!("Short deck (shortdeck) is Hold'em without the 2s to 5s, where A-6-7-8-9 is the");
+    println!("lowest straight and a flush beats a full house. shortdeck-trips also ranks three");
+    println!("of a kind above a straight. Short deck has to be given with --game.");
+    println!();
     println!(
-        "Pair:            {:.2}%",
-        odds.hand_distribution.pair_percentage()
+        "Showdowns with more than {} runouts are estimated by sampling random",
+        EXACT_RUNOUT_LIMIT
     );
+    println!("runouts instead of enumerating every one. --trials and --stderr force sampling:");
+    println!("\t--trials <n>        sample exactly n runouts");
+    println!("\t--stderr <percent>  sample until every standard error is at most this many");
     println!(
-        "High Card:       {:.2}%",
-        odds.hand_distribution.high_card_percentage()
+        "\t                    percentage points (the default is {:.2})",
+        DEFAULT_STANDARD_ERROR * 100.0
     );
+    println!("\t--seed <n>          seed the random number generator to reproduce a sampled");
+    println!("\t                    result. It doesn't force sampling, so showdowns that are");
+    println!("\t                    enumerated ignore it");
+    println!("Sampled percentages are shown with a 95% margin of error.");
+    println!();
+    println!("\t--format <text|json|csv|tsv>  print the results as text (the default), as JSON,");
+    println!("\t                    or as a table with a row per player");
+    println!("The JSON output is an object with these fields:");
+    println!("\tgame        the game's --game name");
+    println!("\tboard, dead arrays of cards like \"As\"");
+    println!("\tmethod      \"exhaustive\" or \"monte_carlo\", which also sets \"seed\"");
+    println!("\tplayers     an array with an object per range, in the order given:");
+    println!("\t  range             the range as typed, and combos, its number of pockets");
+    println!("\t  total             the number of runouts counted, weighted by combo weight");
+    println!("\t  wins, draws, losses  how many of those were won, tied and lost");
+    println!("\t  draws_with        draws_with[i] counts runouts split between i + 1 players,");
+    println!("\t                    so draws_with[0] is the wins");
+    println!("\t  percentages       win, draw and loss as percentages");
+    println!("\t  margins_of_error  the 95% margins of the percentages, only when sampled");
+    println!("\t  hand_distribution the runouts that made each hand, e.g. full_houses");
+    println!("\t  hi_lo             omaha8 only: scoops, high_only, low_only, high_and_low,");
+    println!("\t                    quartered, losses, lows_made and pot_share (a percentage)");
+    println!("The CSV and TSV tables start with a header naming the columns: the matchup and");
+    println!("player numbers, the range, board, dead cards, game and method, then the same");
+    println!("counts, percentages and hands as the JSON.");
 }
 
-pub fn print_showdown_help(executable_name: &str) {
-    println!("{} showdown: Analyze odds for a showdown", executable_name);
-    println!(
-        "Usage: {} showdown <card><card> [vs <card><card>]* [on <card>+]",
-        executable_name
-    );
-    println!();
-    println!("Analyzes the given pockets on the given board.");
-    println!("You must give at least two pockets and no more than 5 cards on the board.");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::cardset::testing::cards;
+
+    fn showdown(
+        game: Game,
+        pockets: &[&str],
+        board: &str,
+        stopping_rule: Option<StoppingRule>,
+    ) -> Showdown {
+        Showdown {
+            game,
+            pockets: pockets.iter().map(|p| HandRange::from(cards(p))).collect(),
+            board: cards(board),
+            dead: cards("2d"),
+            format: OutputFormat::Json,
+            stopping_rule,
+            seed: Some(7),
+        }
+    }
+
+    #[test]
+    fn test_showdown_json_golden() {
+        let showdown = showdown(Game::Holdem, &["As Ks", "Qc Qd"], "Qh 7h 2c 3d 9s", None);
+        let odds = calculate_odds_from_showdown(&showdown).unwrap();
+
+        assert_eq!(
+            showdown_json(&showdown, &odds).to_string(),
+            r#"{
+  "game": "holdem",
+  "board": [
+    "Qh",
+    "9s",
+    "7h",
+    "3d",
+    "2c"
+  ],
+  "dead": [
+    "2d"
+  ],
+  "method": "exhaustive",
+  "players": [
+    {
+      "range": "AsKs",
+      "combos": 1,
+      "total": 1,
+      "wins": 0,
+      "draws": 0,
+      "losses": 1,
+      "draws_with": [
+        0,
+        0
+      ],
+      "percentages": {
+        "win": 0,
+        "draw": 0,
+        "loss": 100
+      },
+      "hand_distribution": {
+        "straight_flushes": 0,
+        "four_of_a_kinds": 0,
+        "full_houses": 0,
+        "flushes": 0,
+        "straights": 0,
+        "three_of_a_kinds": 0,
+        "two_pairs": 0,
+        "pairs": 0,
+        "high_cards": 1
+      }
+    },
+    {
+      "range": "QdQc",
+      "combos": 1,
+      "total": 1,
+      "wins": 1,
+      "draws": 0,
+      "losses": 0,
+      "draws_with": [
+        1,
+        0
+      ],
+      "percentages": {
+        "win": 100,
+        "draw": 0,
+        "loss": 0
+      },
+      "hand_distribution": {
+        "straight_flushes": 0,
+        "four_of_a_kinds": 0,
+        "full_houses": 0,
+        "flushes": 0,
+        "straights": 0,
+        "three_of_a_kinds": 1,
+        "two_pairs": 0,
+        "pairs": 0,
+        "high_cards": 0
+      }
+    }
+  ]
+}"#
+        );
+    }
+
+    // Sampled hi-lo showdowns add the seed, the margins of error and the split-pot outcome.
+    #[test]
+    fn test_showdown_json_golden_sampled_hi_lo() {
+        let showdown = showdown(
+            Game::OmahaHiLo,
+            &["Ah 2h Kc Kd", "Qs Qd Js Jd"],
+            "3c 5d 8h Kh 9s",
+            Some(StoppingRule::Trials(100)),
+        );
+        let odds = calculate_odds_from_showdown(&showdown).unwrap();
+
+        assert_eq!(
+            showdown_json(&showdown, &odds).to_string(),
+            r#"{
+  "game": "omaha8",
+  "board": [
+    "Kh",
+    "9s",
+    "8h",
+    "5d",
+    "3c"
+  ],
+  "dead": [
+    "2d"
+  ],
+  "method": "monte_carlo",
+  "seed": 7,
+  "players": [
+    {
+      "range": "AhKdKc2h",
+      "combos": 1,
+      "total": 100,
+      "wins": 100,
+      "draws": 0,
+      "losses": 0,
+      "draws_with": [
+        100,
+        0
+      ],
+      "percentages": {
+        "win": 100,
+        "draw": 0,
+        "loss": 0
+      },
+      "margins_of_error": {
+        "win": 0,
+        "draw": 0,
+        "loss": 0
+      },
+      "hand_distribution": {
+        "straight_flushes": 0,
+        "four_of_a_kinds": 0,
+        "full_houses": 0,
+        "flushes": 0,
+        "straights": 0,
+        "three_of_a_kinds": 100,
+        "two_pairs": 0,
+        "pairs": 0,
+        "high_cards": 0
+      },
+      "hi_lo": {
+        "scoops": 100,
+        "high_only": 0,
+        "low_only": 0,
+        "high_and_low": 0,
+        "quartered": 0,
+        "losses": 0,
+        "lows_made": 100,
+        "pot_share": 100
+      }
+    },
+    {
+      "range": "QsQdJsJd",
+      "combos": 1,
+      "total": 100,
+      "wins": 0,
+      "draws": 0,
+      "losses": 100,
+      "draws_with": [
+        0,
+        0
+      ],
+      "percentages": {
+        "win": 0,
+        "draw": 0,
+        "loss": 100
+      },
+      "margins_of_error": {
+        "win": 0,
+        "draw": 0,
+        "loss": 0
+      },
+      "hand_distribution": {
+        "straight_flushes": 0,
+        "four_of_a_kinds": 0,
+        "full_houses": 0,
+        "flushes": 0,
+        "straights": 0,
+        "three_of_a_kinds": 0,
+        "two_pairs": 0,
+        "pairs": 100,
+        "high_cards": 0
+      },
+      "hi_lo": {
+        "scoops": 0,
+        "high_only": 0,
+        "low_only": 0,
+        "high_and_low": 0,
+        "quartered": 0,
+        "losses": 100,
+        "lows_made": 0,
+        "pot_share": 0
+      }
+    }
+  ]
+}"#
+        );
+    }
 }
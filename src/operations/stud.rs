@@ -0,0 +1,125 @@
+use crate::{
+    analysis::{
+        evaluate_hand::HandRanking,
+        monte_carlo::{MonteCarloConfig, StoppingRule},
+        odds::CalculationMethod,
+        stud::{StudGame, StudHand, StudOddsCalculation, check_streets_left, count_stud_deals},
+    },
+    cards::cardset::CardSet,
+    error::PopError,
+    operations::showdown::{
+        DEFAULT_STANDARD_ERROR, EXACT_RUNOUT_LIMIT, print_hand_distribution, print_ratio,
+    },
+    util::random::Random,
+};
+
+// Stud tables seat at most 8 players.
+pub const MAX_STUD_PLAYERS: usize = 8;
+
+pub struct Stud {
+    pub game: StudGame,
+    pub hands: Vec<StudHand>,
+    // Cards that were seen and can't be dealt, e.g. folded upcards.
+    pub dead: CardSet,
+    // Forces sampling with this stopping rule, even if the deal is small enough to enumerate.
+    pub stopping_rule: Option<StoppingRule>,
+    pub seed: Option<u64>,
+    // The number of cards every player has yet to be dealt, if given with `--streets`. The hands
+    // must then have been dealt the rest.
+    pub streets_left: Option<usize>,
+}
+
+pub fn calculate_odds_from_stud(stud: &Stud) -> Result<Vec<StudOddsCalculation>, PopError> {
+    let hands = stud.hands.as_slice();
+    if hands.len() > MAX_STUD_PLAYERS {
+        return Err(PopError::TooManyPlayers {
+            max: MAX_STUD_PLAYERS,
+        });
+    }
+    if let Some(streets_left) = stud.streets_left {
+        check_streets_left(hands, streets_left)?;
+    }
+
+    let stopping_rule = match stud.stopping_rule {
+        Some(rule) => rule,
+        None if count_stud_deals(hands, stud.dead)? > EXACT_RUNOUT_LIMIT => {
+            StoppingRule::StandardError(DEFAULT_STANDARD_ERROR)
+        }
+        None => return StudOddsCalculation::calculate(stud.game, hands, stud.dead),
+    };
+
+    let config = MonteCarloConfig {
+        stopping_rule,
+        seed: stud.seed.unwrap_or_else(Random::seed_from_time),
+    };
+    StudOddsCalculation::sample(stud.game, hands, stud.dead, config)
+}
+
+pub fn print_stud_odds(stud: &Stud, odds: &[StudOddsCalculation]) {
+    match stud.hands[0].streets_left() {
+        0 => println!("{} showdown", stud.game),
+        1 => println!("{} with 1 street to come", stud.game),
+        n => println!("{} with {} streets to come", stud.game, n),
+    }
+    if let Some(CalculationMethod::MonteCarlo { seed }) = odds.first().map(|o| o.method) {
+        println!(
+            "Estimated from {} random deals (seed {})",
+            odds[0].outcome.total_hand_count(),
+            seed
+        );
+    }
+    println!();
+
+    for player in odds {
+        println!("{}", player.hand);
+        print_ratio("Win: ", player.outcome.win_ratio(), player.method);
+        print_ratio("Draw:", player.outcome.draw_ratio(), player.method);
+        print_ratio("Loss:", player.outcome.loss_ratio(), player.method);
+        if let Some(hand_distribution) = &player.hand_distribution {
+            println!();
+            print_hand_distribution(HandRanking::Standard, hand_distribution);
+        }
+        println!();
+    }
+}
+
+pub fn print_stud_help(executable_name: &str) {
+    println!(
+        "{} stud: Analyze odds for seven card stud or razz",
+        executable_name
+    );
+    println!(
+        "Usage: {} stud <hand> [vs <hand>]* [dead <card>+] [--game <stud|razz>] [--streets <n>] [--trials <n> | --stderr <percent>] [--seed <n>]",
+        executable_name
+    );
+    println!();
+    println!("Analyzes the given stud hands, dealing each player the rest of their 7 cards.");
+    println!("You must give between 2 and {} hands.", MAX_STUD_PLAYERS);
+    println!();
+    println!("A hand is written as its down cards, a '/', and its up cards, e.g. `AsKd/7c`.");
+    println!("Write ?? for a down card you can't see, e.g. `????/Qh` for an opponent.");
+    println!("Every hand must be on the same street, with 2 down cards (3 on seventh street)");
+    println!("and the rest up.");
+    println!();
+    println!("Folded upcards and any other cards that can't be dealt go after `dead`.");
+    println!();
+    println!("\t--game <stud|razz>  stud is won by the best high hand (the default), and razz");
+    println!("\t                    by the best ace-to-five low, where straights and flushes");
+    println!("\t                    don't count and there is no qualifier");
+    println!("\t--streets <n>       the number of streets still to come, from 4 on third street");
+    println!("\t                    to 0 on seventh. The hands must have been dealt the rest");
+    println!();
+    println!(
+        "Deals with more than {} possibilities are estimated by sampling. The sampling",
+        EXACT_RUNOUT_LIMIT
+    );
+    println!("options are the same as for `showdown`:");
+    println!("\t--trials <n>        sample exactly n deals");
+    println!("\t--stderr <percent>  sample until every standard error is at most this many");
+    println!(
+        "\t                    percentage points (the default is {:.2})",
+        DEFAULT_STANDARD_ERROR * 100.0
+    );
+    println!("\t--seed <n>          seed the random number generator to reproduce a sampled");
+    println!("\t                    result. It doesn't force sampling");
+}
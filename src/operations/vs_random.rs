@@ -0,0 +1,105 @@
+use crate::{
+    analysis::{
+        game::Game,
+        math::n_choose_r,
+        odds::{CalculationMethod, RangeOddsCalculation},
+    },
+    cards::{cardset::CardSet, hand_range::HandRange},
+    error::PopError,
+    operations::showdown::{Showdown, calculate_odds_from_showdown, print_hi_lo_odds, print_odds},
+};
+
+// One less than the most players a showdown can have.
+pub const MAX_OPPONENTS: usize = 22;
+
+pub struct VsRandom {
+    // The player's range against `opponents` random ranges.
+    pub showdown: Showdown,
+    pub opponents: usize,
+}
+
+// The number of runouts an exhaustive calculation of `range` against `opponents` random pockets
+// would evaluate. Unlike `count_runouts`, this doesn't visit the matchups, which is slow when
+// there are many random pockets.
+pub fn count_runouts_vs_random(
+    game: Game,
+    range: &HandRange,
+    opponents: usize,
+    board: CardSet,
+    dead: CardSet,
+) -> usize {
+    let pocket_size = range.combos()[0].pocket.len();
+    let mut undealt = (game.deck().cards() - board - dead).len() - pocket_size;
+    let mut runouts = range.without(board | dead).len();
+    for _ in 0..opponents {
+        runouts = runouts.saturating_mul(n_choose_r(undealt, pocket_size));
+        undealt -= pocket_size;
+    }
+    runouts.saturating_mul(n_choose_r(undealt, 5 - board.len()))
+}
+
+// The odds of the player, leaving out the random opponents.
+pub fn calculate_vs_random(vs_random: &VsRandom) -> Result<RangeOddsCalculation, PopError> {
+    Ok(calculate_odds_from_showdown(&vs_random.showdown)?.swap_remove(0))
+}
+
+pub fn print_vs_random(vs_random: &VsRandom, odds: &RangeOddsCalculation) {
+    let game = vs_random.showdown.game;
+    let equity = match &odds.hi_lo {
+        Some(hi_lo) => hi_lo.pot_share_percentage(),
+        None => odds.outcome.equity() * 100.0,
+    };
+    println!(
+        "{} vs {} random {}: {:.2}% equity",
+        odds.range,
+        vs_random.opponents,
+        if vs_random.opponents == 1 {
+            "opponent"
+        } else {
+            "opponents"
+        },
+        equity
+    );
+    if !vs_random.showdown.board.is_empty() {
+        println!("Board: {}", vs_random.showdown.board);
+    }
+    if !vs_random.showdown.dead.is_empty() {
+        println!("Dead: {}", vs_random.showdown.dead);
+    }
+    if let CalculationMethod::MonteCarlo { seed } = odds.method {
+        println!(
+            "Estimated from {} random runouts (seed {})",
+            odds.outcome.total_hand_count(),
+            seed
+        );
+    }
+    println!();
+
+    match &odds.hi_lo {
+        Some(hi_lo) => print_hi_lo_odds(game, odds, hi_lo),
+        None => print_odds(game, odds),
+    }
+}
+
+pub fn print_vs_random_help(executable_name: &str) {
+    println!(
+        "{} vs-random: Calculate the odds of a pocket against random hands",
+        executable_name
+    );
+    println!(
+        "Usage: {} vs-random <range> <opponents> [on <card>+] [dead <card>+] [--game <game>] [--trials <n> | --stderr <percent>] [--seed <n>]",
+        executable_name
+    );
+    println!();
+    println!(
+        "Deals between 1 and {} opponents a random pocket each, e.g. `{} vs-random 76s 5`",
+        MAX_OPPONENTS, executable_name
+    );
+    println!("for how often 76s wins against 5 random hands. Prints the win, draw and loss odds");
+    println!("and the hands made. Equity is the share of the pot won on average, where a split");
+    println!("pot counts as a share of it.");
+    println!();
+    println!("Every runout and every pocket the opponents could hold is enumerated when there are");
+    println!("few enough of them, and sampled otherwise. The range, cards and options are written");
+    println!("the same way as for `showdown`.");
+}
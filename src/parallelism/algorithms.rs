@@ -90,42 +90,6 @@ fn parallel_map_with_parallelism<T: Sync, U: Send, F: Fn(&T) -> U + Send + Sync>
     output
 }
 
-fn into_parallel_map_with_parallelism<T: Sync, U: Send, F: Fn(T) -> U + Send + Sync>(
-    mut vec: Vec<T>,
-    max_parallelism: NonZero<usize>,
-    mapper: F,
-) -> Vec<U> {
-    let subranges = SubrangeIterator::from_range(0..vec.len(), max_parallelism);
-
-    let mut output = Vec::<U>::new();
-    output.reserve_exact(vec.len());
-    unsafe { output.set_len(vec.len()) };
-
-    let input_ptr = SendSyncRawPtr {
-        ptr: vec.as_mut_ptr(),
-    };
-
-    let output_ptr = SendSyncRawPtr {
-        ptr: output.as_mut_ptr(),
-    };
-
-    thread::scope(|s| {
-        for subrange in subranges {
-            s.spawn(|| {
-                for i in subrange {
-                    unsafe {
-                        let output = output_ptr + i;
-                        let input = input_ptr + i;
-                        output.set(mapper(input.get()))
-                    }
-                }
-            });
-        }
-    });
-
-    output
-}
-
 fn into_parallel_reduce_with_parallelism<T: Send + Sync, F: Fn(T, T) -> T + Send + Sync>(
     vec: Vec<T>,
     max_parallelism: NonZero<usize>,
@@ -178,34 +142,6 @@ fn into_parallel_reduce_with_parallelism<T: Send + Sync, F: Fn(T, T) -> T + Send
     Some(current)
 }
 
-fn parallel_map_chunks_then_combine_with_parallelism<
-    A: Sync,
-    B: Send,
-    C,
-    M: Fn(&[A]) -> B + Send + Sync,
-    R: FnMut(C, B) -> C,
->(
-    slice: &[A],
-    max_parallelism: NonZero<usize>,
-    mapper: M,
-    seed: C,
-    mut combiner: R,
-) -> C {
-    let subranges = SubrangeIterator::from_range(0..slice.len(), max_parallelism);
-
-    let chunks = thread::scope(|s| {
-        let threads = subranges.map(|subrange| s.spawn(|| mapper(&slice[subrange])));
-        threads.map(|t| t.join().unwrap()).collect::<Vec<B>>()
-    });
-
-    let mut result = seed;
-    for chunk in chunks {
-        result = combiner(result, chunk);
-    }
-
-    result
-}
-
 pub fn parallel_map<T: Sync, U: Send, F: Fn(&T) -> U + Send + Sync>(
     slice: &[T],
     mapper: F,
@@ -213,13 +149,6 @@ pub fn parallel_map<T: Sync, U: Send, F: Fn(&T) -> U + Send + Sync>(
     parallel_map_with_parallelism(slice, get_parallelism_from_os(), mapper)
 }
 
-pub fn into_parallel_map<T: Sync, U: Send, F: Fn(T) -> U + Send + Sync>(
-    vec: Vec<T>,
-    mapper: F,
-) -> Vec<U> {
-    into_parallel_map_with_parallelism(vec, get_parallelism_from_os(), mapper)
-}
-
 pub fn into_parallel_reduce<T: Send + Sync, F: Fn(T, T) -> T + Send + Sync>(
     vec: Vec<T>,
     reducer: F,
@@ -227,27 +156,6 @@ pub fn into_parallel_reduce<T: Send + Sync, F: Fn(T, T) -> T + Send + Sync>(
     into_parallel_reduce_with_parallelism(vec, get_parallelism_from_os(), reducer)
 }
 
-pub fn parallel_map_chunks_then_combine<
-    A: Sync,
-    B: Send,
-    C,
-    M: Fn(&[A]) -> B + Send + Sync,
-    R: FnMut(C, B) -> C,
->(
-    slice: &[A],
-    mapper: M,
-    seed: C,
-    combiner: R,
-) -> C {
-    parallel_map_chunks_then_combine_with_parallelism(
-        slice,
-        get_parallelism_from_os(),
-        mapper,
-        seed,
-        combiner,
-    )
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -331,20 +239,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_into_parallel_map_with_parallelism() {
-        let nums = (0..20).into_iter().collect::<Vec<i32>>();
-
-        let doubled = into_parallel_map_with_parallelism(nums, NonZero::new(3).unwrap(), |x| x * 2);
-
-        assert_eq!(
-            doubled,
-            vec![
-                0, 2, 4, 6, 8, 10, 12, 14, 16, 18, 20, 22, 24, 26, 28, 30, 32, 34, 36, 38
-            ]
-        );
-    }
-
     #[test]
     fn test_into_parallel_reduce_with_parallelism() {
         let nums = (0..20).into_iter().collect::<Vec<i32>>();
@@ -364,19 +258,4 @@ mod tests {
 
         assert_eq!(sum, None);
     }
-
-    #[test]
-    fn test_parallel_map_chunks_then_combine_with_parallelism() {
-        let nums = (0..20).into_iter().collect::<Vec<i32>>();
-
-        let result = parallel_map_chunks_then_combine_with_parallelism(
-            nums.as_slice(),
-            NonZero::new(3).unwrap(),
-            |x| x.iter().sum::<i32>().to_string(),
-            69,
-            |a, c| a + c.parse::<i32>().unwrap(),
-        );
-
-        assert_eq!(result, 190 + 69);
-    }
 }
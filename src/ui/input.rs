@@ -1,14 +1,40 @@
 use std::process::ExitCode;
+use std::str::FromStr;
 
+use crate::analysis::game::{ALL_GAMES, Game, MAX_POCKET_SIZE};
+use crate::analysis::monte_carlo::{MAX_TRIALS, StoppingRule};
+use crate::analysis::odds::MAX_PLAYERS;
+use crate::analysis::preflop_matrix::HandClass;
+use crate::analysis::search_space::{has_matchup, undealt_cards};
+use crate::analysis::stud::{
+    ALL_STUD_GAMES, MAX_UP_CARDS, STUD_HAND_SIZE, StudGame, StudHand, cards_to_deal,
+};
 use crate::cards::card::{Card, Rank, Suit};
 use crate::cards::cardset::CardSet;
-use crate::operations::showdown::Showdown;
+use crate::cards::hand_range::{
+    CardPattern, FULL_WEIGHT, HandRange, Suitedness, WeightedPocket, pair_combos,
+    placeholder_combos, random_range, ranks_between, unpaired_combos,
+};
+use crate::error::PopError;
+use crate::operations::Operation;
+use crate::operations::batch::Batch;
+use crate::operations::board::{Board, DEFAULT_TOP_HANDS};
+use crate::operations::compare::Compare;
+use crate::operations::matrix::Matrix;
+use crate::operations::outs::{MAX_OUTS_PLAYERS, Outs};
+use crate::operations::repl::ReplCommand;
+use crate::operations::showdown::{
+    DEFAULT_STANDARD_ERROR, EXACT_RUNOUT_LIMIT, Showdown, ShowdownSpec,
+};
+use crate::operations::stud::{MAX_STUD_PLAYERS, Stud};
+use crate::operations::vs_random::{MAX_OPPONENTS, VsRandom, count_runouts_vs_random};
 use crate::ui::argparser::{ArgStream, TakeMode};
-use crate::ui::output::{print_help, print_unrecognized_operation};
-use crate::util::array::monomorphize;
+use crate::ui::output::{
+    ALL_OUTPUT_FORMATS, OutputFormat, print_help, print_unrecognized_operation,
+};
 
-pub fn stream_card(take_char: &mut dyn FnMut(TakeMode) -> Option<char>) -> Option<Card> {
-    let rank = match take_char(TakeMode::Consume)?.to_ascii_uppercase() {
+pub fn stream_rank(take_char: &mut dyn FnMut(TakeMode) -> Option<char>) -> Option<Rank> {
+    Some(match take_char(TakeMode::Consume)?.to_ascii_uppercase() {
         '2' => Rank::Two,
         '3' => Rank::Three,
         '4' => Rank::Four,
@@ -27,9 +53,11 @@ pub fn stream_card(take_char: &mut dyn FnMut(TakeMode) -> Option<char>) -> Optio
             _ => return None,
         },
         _ => return None,
-    };
+    })
+}
 
-    let suit = match take_char(TakeMode::Consume)?.to_ascii_lowercase() {
+pub fn stream_suit(take_char: &mut dyn FnMut(TakeMode) -> Option<char>) -> Option<Suit> {
+    Some(match take_char(TakeMode::Consume)?.to_ascii_lowercase() {
         'c' => Suit::Club,
         '♣' => Suit::Club,
         'd' => Suit::Diamond,
@@ -39,8 +67,12 @@ pub fn stream_card(take_char: &mut dyn FnMut(TakeMode) -> Option<char>) -> Optio
         's' => Suit::Spade,
         '♠' => Suit::Spade,
         _ => return None,
-    };
+    })
+}
 
+pub fn stream_card(take_char: &mut dyn FnMut(TakeMode) -> Option<char>) -> Option<Card> {
+    let rank = stream_rank(take_char)?;
+    let suit = stream_suit(take_char)?;
     Some(Card { rank, suit })
 }
 
@@ -89,67 +121,340 @@ pub fn stream_drain(take_char: &mut dyn FnMut(TakeMode) -> Option<char>) -> Opti
     Some(ret)
 }
 
-pub fn parse_pockets(stream: &mut ArgStream) -> Result<Vec<CardSet>, ExitCode> {
-    let mut pockets = Vec::<CardSet>::new();
+// A card, `Kx` for a king of unknown suit or `??` for a card that isn't known at all.
+pub fn stream_card_pattern(
+    take_char: &mut dyn FnMut(TakeMode) -> Option<char>,
+) -> Option<CardPattern> {
+    if take_char(TakeMode::Peek) == Some('?') {
+        take_char(TakeMode::Consume);
+        return match take_char(TakeMode::Consume)? {
+            '?' => Some(CardPattern::Any),
+            _ => None,
+        };
+    }
 
-    loop {
-        if !pockets.is_empty() {
-            match stream.try_parse(|t| stream_literal_ignorecase("vs", t)) {
-                Some(_) => {}
-                None => {
-                    break;
+    let rank = stream_rank(take_char)?;
+    if take_char(TakeMode::Peek).map(|c| c.to_ascii_lowercase()) == Some('x') {
+        take_char(TakeMode::Consume);
+        return Some(CardPattern::Rank(rank));
+    }
+    let suit = stream_suit(take_char)?;
+    Some(CardPattern::Known(Card { rank, suit }))
+}
+
+// Two cards, optionally separated by whitespace and/or a comma, e.g. `AsKs` or `As, Ks`.
+// Either card can be a placeholder, e.g. `As??` or `KxQx`.
+pub fn stream_explicit_pocket(
+    take_char: &mut dyn FnMut(TakeMode) -> Option<char>,
+) -> Option<[CardPattern; 2]> {
+    let first_card = stream_card_pattern(take_char)?;
+    stream_whitespace(take_char);
+    if take_char(TakeMode::Peek) == Some(',') {
+        take_char(TakeMode::Consume);
+    }
+    stream_whitespace(take_char);
+    let second_card = stream_card_pattern(take_char)?;
+
+    Some([first_card, second_card])
+}
+
+fn stream_suitedness(take_char: &mut dyn FnMut(TakeMode) -> Option<char>) -> Suitedness {
+    match take_char(TakeMode::Peek).map(|c| c.to_ascii_lowercase()) {
+        Some('s') => {
+            take_char(TakeMode::Consume);
+            Suitedness::Suited
+        }
+        Some('o') => {
+            take_char(TakeMode::Consume);
+            Suitedness::Offsuit
+        }
+        _ => Suitedness::Any,
+    }
+}
+
+fn format_rank_pair(high: Rank, low: Rank, suitedness: Suitedness) -> String {
+    let suffix = match suitedness {
+        Suitedness::Suited => "s",
+        Suitedness::Offsuit => "o",
+        Suitedness::Any => "",
+    };
+    format!("{}{}{}", high, low, suffix)
+}
+
+// A group of pockets in range notation: `QQ`, `AKs`, `KQo`, `T9`, `QQ+`, `ATs+`, `22-99` or
+// `A2s-A5s`. Returns the normalized notation along with the pockets it expands to.
+pub fn stream_range_spec(
+    take_char: &mut dyn FnMut(TakeMode) -> Option<char>,
+) -> Option<(String, Vec<CardSet>)> {
+    let first = stream_rank(take_char)?;
+    let second = stream_rank(take_char)?;
+    let suitedness = stream_suitedness(take_char);
+
+    let (high, low) = if first >= second {
+        (first, second)
+    } else {
+        (second, first)
+    };
+
+    if high == low && suitedness != Suitedness::Any {
+        return None;
+    }
+
+    let mut notation = format_rank_pair(high, low, suitedness);
+
+    let kickers: Vec<Rank> = match take_char(TakeMode::Peek) {
+        Some('+') => {
+            take_char(TakeMode::Consume);
+            notation.push('+');
+            if high == low {
+                ranks_between(low, Rank::Ace).collect()
+            } else {
+                ranks_between(low, high).filter(|r| *r != high).collect()
+            }
+        }
+        Some('-') => {
+            take_char(TakeMode::Consume);
+            let end_first = stream_rank(take_char)?;
+            let end_second = stream_rank(take_char)?;
+            let end_suitedness = stream_suitedness(take_char);
+
+            if end_suitedness != Suitedness::Any && end_suitedness != suitedness {
+                return None;
+            }
+
+            let (end_high, end_low) = if end_first >= end_second {
+                (end_first, end_second)
+            } else {
+                (end_second, end_first)
+            };
+
+            notation.push('-');
+            notation.push_str(&format_rank_pair(end_high, end_low, suitedness));
+
+            if high == low {
+                if end_high != end_low {
+                    return None;
+                }
+                ranks_between(low.min(end_low), low.max(end_low)).collect()
+            } else {
+                if end_high != high || end_low == end_high {
+                    return None;
                 }
+                ranks_between(low.min(end_low), low.max(end_low)).collect()
+            }
+        }
+        _ => vec![low],
+    };
+
+    let pockets = kickers
+        .into_iter()
+        .flat_map(|kicker| {
+            if high == low {
+                pair_combos(kicker)
+            } else {
+                unpaired_combos(high, kicker, suitedness)
+            }
+        })
+        .collect();
+
+    Some((notation, pockets))
+}
+
+// A decimal weight between 0 (exclusive) and 1 (inclusive), in units of 1/FULL_WEIGHT.
+pub fn stream_weight(take_char: &mut dyn FnMut(TakeMode) -> Option<char>) -> Option<usize> {
+    let mut text = String::new();
+    while let Some(c) = take_char(TakeMode::Peek) {
+        if !c.is_ascii_digit() && c != '.' {
+            break;
+        }
+        text.push(c);
+        take_char(TakeMode::Consume);
+    }
+
+    let weight = text.parse::<f64>().ok()?;
+    if weight <= 0.0 || weight > 1.0 {
+        return None;
+    }
+
+    Some(((weight * FULL_WEIGHT as f64).round() as usize).max(1))
+}
+
+// An explicit pocket, which is usually 2 cards written like `stream_explicit_pocket` accepts.
+// Omaha pockets continue with up to 4 more cards, optionally separated by whitespace, e.g.
+// `AsKsQhJh` or `As Ks Qh Jh`.
+fn parse_explicit_pocket(stream: &mut ArgStream) -> Option<Vec<CardPattern>> {
+    let mut cards = stream.try_parse(stream_explicit_pocket)?.to_vec();
+    while cards.len() < MAX_POCKET_SIZE {
+        match stream.try_parse(|t| {
+            stream_whitespace(t);
+            stream_card_pattern(t)
+        }) {
+            Some(card) => cards.push(card),
+            None => break,
+        }
+    }
+    Some(cards)
+}
+
+// A comma-separated list of explicit pockets and range specs, each optionally followed by
+// `:<weight>`, e.g. `QQ+, AKs, AQo:0.5`.
+pub fn parse_range(stream: &mut ArgStream) -> Result<HandRange, ExitCode> {
+    read_range(stream).map_err(|message| {
+        eprintln!("{}", message);
+        ExitCode::FAILURE
+    })
+}
+
+// Reads one range, returning why it's invalid instead of printing it.
+fn read_range(stream: &mut ArgStream) -> Result<HandRange, String> {
+    let mut notation = Vec::<String>::new();
+    let mut combos = Vec::<WeightedPocket>::new();
+
+    loop {
+        if !notation.is_empty() {
+            if stream
+                .try_parse(|t| stream_literal_ignorecase(",", t))
+                .is_none()
+            {
+                break;
             }
             stream.try_parse(stream_whitespace);
         }
 
-        let first_card = match stream.try_parse(stream_card) {
-            Some(c) => c,
-            None => {
-                println!(
-                    "Expected a card, but got {}",
-                    stream.try_parse(stream_token).unwrap_or("EOF".to_string())
-                );
-                return Err(ExitCode::FAILURE);
+        let (mut text, pockets) = match parse_explicit_pocket(stream) {
+            Some(patterns) => {
+                let cards = patterns
+                    .iter()
+                    .filter_map(|p| match p {
+                        CardPattern::Known(card) => Some(*card),
+                        _ => None,
+                    })
+                    .collect::<Vec<Card>>();
+                if CardSet::from(cards.as_slice()).len() != cards.len() {
+                    let duplicate = (1..cards.len())
+                        .find(|i| cards[..*i].contains(&cards[*i]))
+                        .unwrap();
+                    return Err(format!(
+                        "Cannot have duplicate cards ({})",
+                        cards[duplicate]
+                    ));
+                }
+
+                if cards.len() == patterns.len() {
+                    let pocket = CardSet::from(cards.as_slice());
+                    (pocket.to_string(), vec![pocket])
+                } else {
+                    // Placeholders expand to every pocket they could stand for, so the unknown
+                    // cards are enumerated or sampled along with the runout.
+                    let text = patterns.iter().map(|p| p.to_string()).collect::<String>();
+                    let pockets = placeholder_combos(patterns.as_slice());
+                    if pockets.is_empty() {
+                        return Err(format!("{} doesn't match any pocket", text));
+                    }
+                    (text, pockets)
+                }
             }
+            None => match stream.try_parse(stream_range_spec) {
+                Some(v) => v,
+                None => {
+                    return Err(format!(
+                        "Expected a pocket or a range, but got {}",
+                        stream.try_parse(stream_token).unwrap_or("EOF".to_string())
+                    ));
+                }
+            },
         };
 
-        stream.try_parse(stream_whitespace);
-        stream.try_parse(|t| stream_literal_ignorecase(",", t));
-        stream.try_parse(stream_whitespace);
-
-        let second_card = match stream.try_parse(stream_card) {
-            Some(c) => c,
-            None => {
-                println!(
-                    "Each pocket must have 2 cards (got {})",
-                    stream.try_parse(stream_token).unwrap_or("EOF".to_string())
-                );
-                return Err(ExitCode::FAILURE);
+        let weight = if stream
+            .try_parse(|t| stream_literal_ignorecase(":", t))
+            .is_some()
+        {
+            match stream.try_parse(stream_weight) {
+                Some(w) => {
+                    text.push_str(&format!(":{}", w as f64 / FULL_WEIGHT as f64));
+                    w
+                }
+                None => {
+                    return Err(format!(
+                        "Expected a weight between 0 and 1 after '{}:', but got {}",
+                        text,
+                        stream.try_parse(stream_token).unwrap_or("EOF".to_string())
+                    ));
+                }
             }
+        } else {
+            FULL_WEIGHT
         };
 
+        notation.push(text);
+        combos.extend(
+            pockets
+                .into_iter()
+                .map(|pocket| WeightedPocket { pocket, weight }),
+        );
         stream.try_parse(stream_whitespace);
+    }
+
+    Ok(HandRange::new(notation.join(","), combos))
+}
+
+// Parses a range written the way it's typed on the command line, e.g. "AKs:0.5, QQ+".
+impl FromStr for HandRange {
+    type Err = PopError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut stream = ArgStream::from(s.split_whitespace().map(String::from));
+        let range = read_range(&mut stream).map_err(PopError::InvalidRange)?;
+        match stream.try_parse(stream_token) {
+            Some(rest) => Err(PopError::InvalidRange(format!(
+                "Expected the range to end after {}, but got {}",
+                range, rest
+            ))),
+            None => Ok(range),
+        }
+    }
+}
+
+pub fn parse_pockets(stream: &mut ArgStream) -> Result<Vec<HandRange>, ExitCode> {
+    let mut pockets = Vec::<HandRange>::new();
 
-        pockets.push(CardSet::from(&[first_card, second_card]));
+    loop {
+        if !pockets.is_empty() {
+            match stream.try_parse(|t| stream_literal_ignorecase("vs", t)) {
+                Some(_) => {}
+                None => {
+                    break;
+                }
+            }
+            stream.try_parse(stream_whitespace);
+        }
+
+        pockets.push(parse_range(stream)?);
+        stream.try_parse(stream_whitespace);
     }
 
-    if pockets.len() > 23 {
-        println!("Cannot have more than 23 pockets (have {})", pockets.len());
+    if pockets.len() > MAX_PLAYERS {
+        eprintln!(
+            "Cannot have more than {} pockets (have {})",
+            MAX_PLAYERS,
+            pockets.len()
+        );
         Err(ExitCode::FAILURE)
     } else if pockets.len() < 2 {
-        println!("Cannot have less than 2 pockets (have {})", pockets.len());
+        eprintln!("Cannot have less than 2 pockets (have {})", pockets.len());
         Err(ExitCode::FAILURE)
     } else {
         Ok(pockets)
     }
 }
 
-pub fn parse_board(stream: &mut ArgStream) -> Result<CardSet, ExitCode> {
+// One or more distinct cards, optionally separated by whitespace and/or commas.
+pub fn parse_cards(stream: &mut ArgStream) -> Result<CardSet, ExitCode> {
     let mut board = CardSet::new();
 
     loop {
         let empty_is_acceptable = if !board.is_empty() {
+            stream.try_parse(stream_whitespace);
             let comma_present = stream
                 .try_parse(|t| stream_literal_ignorecase(",", t))
                 .is_some();
@@ -165,7 +470,7 @@ pub fn parse_board(stream: &mut ArgStream) -> Result<CardSet, ExitCode> {
                 if empty_is_acceptable {
                     break;
                 } else {
-                    println!(
+                    eprintln!(
                         "Expected a card, but got {}",
                         stream.try_parse(stream_token).unwrap_or("EOF".to_string())
                     );
@@ -175,77 +480,1465 @@ pub fn parse_board(stream: &mut ArgStream) -> Result<CardSet, ExitCode> {
         };
 
         if board.has(card) {
-            println!("Cannot have duplicate cards ({})", card);
+            eprintln!("Cannot have duplicate cards ({})", card);
             return Err(ExitCode::FAILURE);
         }
 
         board += card;
     }
 
+    Ok(board)
+}
+
+fn check_board(board: CardSet) -> Result<(), ExitCode> {
     if board.len() > 5 {
-        println!(
+        eprintln!(
             "The board cannot have more than 5 cards (has {}: {})",
             board.len(),
             board
         );
         return Err(ExitCode::FAILURE);
     }
+    Ok(())
+}
 
+pub fn parse_board(stream: &mut ArgStream) -> Result<CardSet, ExitCode> {
+    let board = parse_cards(stream)?;
+    check_board(board)?;
     Ok(board)
 }
 
-pub fn parse_showdown(mut stream: &mut ArgStream) -> Result<Showdown, ExitCode> {
-    let pockets = parse_pockets(&mut stream)?;
+// The value after an option like `--trials`, which must parse as a `T` that satisfies `valid`.
+fn parse_option_value<T: FromStr>(
+    stream: &mut ArgStream,
+    option: &str,
+    expected: &str,
+    valid: impl Fn(&T) -> bool,
+) -> Result<T, ExitCode> {
     stream.try_parse(stream_whitespace);
-    let board = match stream.try_parse(|t| stream_literal_ignorecase("on", t)) {
-        Some(_) => parse_board(&mut stream)?,
-        None => CardSet::new(),
+    let token = stream.try_parse(stream_token);
+    match token.as_deref().map(|t| t.parse::<T>()) {
+        Some(Ok(v)) if valid(&v) => Ok(v),
+        _ => {
+            eprintln!(
+                "Expected {} after '--{}', but got {}",
+                expected,
+                option,
+                token.unwrap_or("EOF".to_string())
+            );
+            Err(ExitCode::FAILURE)
+        }
+    }
+}
+
+// Parses the value of `--trials`, `--stderr` or `--seed`. Returns false if `option` is none of
+// those.
+fn parse_sampling_option(
+    stream: &mut ArgStream,
+    option: Option<&str>,
+    stopping_rule: &mut Option<StoppingRule>,
+    seed: &mut Option<u64>,
+) -> Result<bool, ExitCode> {
+    match option {
+        Some("trials") => {
+            let trials = parse_option_value(stream, "trials", "a positive integer", |n| {
+                *n > 0 && *n <= MAX_TRIALS
+            })?;
+            *stopping_rule = Some(StoppingRule::Trials(trials));
+        }
+        Some("stderr") => {
+            let percent = parse_option_value(
+                stream,
+                "stderr",
+                "a percentage greater than 0",
+                |p: &f64| *p > 0.0 && *p <= 100.0,
+            )?;
+            *stopping_rule = Some(StoppingRule::StandardError(percent / 100.0));
+        }
+        Some("seed") => {
+            *seed = Some(parse_option_value(
+                stream,
+                "seed",
+                "a non-negative integer",
+                |_: &u64| true,
+            )?);
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+// Parses a game's name for `--game`.
+struct GameName(Game);
+
+impl FromStr for GameName {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Game::from_name(s).map(GameName).ok_or(())
+    }
+}
+
+// Every pocket must be one that can be dealt in `game`, and every player must have the same
+// number of hole cards.
+fn check_pocket_sizes(game: Game, pockets: &[HandRange]) -> Result<(), ExitCode> {
+    let mut size = None;
+    for range in pockets {
+        for combo in range.combos() {
+            let len = combo.pocket.len();
+            if !game.pocket_sizes().contains(&len) {
+                let sizes = game.pocket_sizes();
+                if sizes.start() == sizes.end() {
+                    eprintln!(
+                        "{} pockets must have {} cards, but {} has {}",
+                        game,
+                        sizes.start(),
+                        range,
+                        len
+                    );
+                } else {
+                    eprintln!(
+                        "{} pockets must have {} to {} cards, but {} has {}",
+                        game,
+                        sizes.start(),
+                        sizes.end(),
+                        range,
+                        len
+                    );
+                }
+                return Err(ExitCode::FAILURE);
+            }
+            if *size.get_or_insert(len) != len {
+                eprintln!(
+                    "Every pocket must have the same number of cards, but {} has {}",
+                    range, len
+                );
+                return Err(ExitCode::FAILURE);
+            }
+        }
+    }
+    Ok(())
+}
+
+// Removes the combos that use a card outside of `game`'s deck. The board and every range must
+// still be dealable afterwards.
+fn restrict_to_deck(
+    game: Game,
+    pockets: Vec<HandRange>,
+    board: CardSet,
+) -> Result<Vec<HandRange>, ExitCode> {
+    let deck = game.deck();
+    let missing = CardSet::universe() - deck.cards();
+
+    if let Some(card) = (board & missing).iter_desc().next() {
+        eprintln!(
+            "{} is played without cards below {}, but the board has {}",
+            game,
+            deck.lowest_rank(),
+            card
+        );
+        return Err(ExitCode::FAILURE);
+    }
+
+    pockets
+        .into_iter()
+        .map(|range| match range.without(missing) {
+            r if r.is_empty() => {
+                eprintln!(
+                    "{} has no pockets that can be dealt in {}, which is played without cards below {}",
+                    range,
+                    game,
+                    deck.lowest_rank()
+                );
+                Err(ExitCode::FAILURE)
+            }
+            r => Ok(r),
+        })
+        .collect()
+}
+
+// Parses the value of `--game`, `--format` or a sampling option into `spec`. Returns false if
+// `option` is none of those.
+pub fn parse_showdown_option(
+    stream: &mut ArgStream,
+    option: Option<&str>,
+    spec: &mut ShowdownSpec,
+) -> Result<bool, ExitCode> {
+    match option {
+        Some("game") => {
+            let names = ALL_GAMES.map(|g| g.name()).join(", ");
+            let GameName(game) = parse_option_value(
+                stream,
+                "game",
+                &format!("one of {}", names),
+                |_: &GameName| true,
+            )?;
+            spec.game = Some(game);
+        }
+        Some("format") => {
+            let names = ALL_OUTPUT_FORMATS.map(|f| f.name()).join(", ");
+            spec.format = parse_option_value(
+                stream,
+                "format",
+                &format!("one of {}", names),
+                |_: &OutputFormat| true,
+            )?;
+        }
+        other => {
+            return parse_sampling_option(stream, other, &mut spec.stopping_rule, &mut spec.seed);
+        }
+    }
+    Ok(true)
+}
+
+// Every option a showdown takes. The operations written like a showdown take some of them.
+const SHOWDOWN_OPTIONS: [&str; 5] = ["game", "format", "trials", "stderr", "seed"];
+
+// Parses `--<option> <value>` pairs until the next argument isn't an option. Options that aren't
+// in `allowed` are rejected.
+pub fn parse_showdown_options(
+    stream: &mut ArgStream,
+    spec: &mut ShowdownSpec,
+    allowed: &[&str],
+) -> Result<(), ExitCode> {
+    loop {
+        stream.try_parse(stream_whitespace);
+        if stream
+            .try_parse(|t| stream_literal_ignorecase("--", t))
+            .is_none()
+        {
+            return Ok(());
+        }
+
+        let option = stream.try_parse(stream_token);
+        let parsed = match option.as_deref() {
+            Some(o) if allowed.contains(&o) => parse_showdown_option(stream, Some(o), spec)?,
+            _ => false,
+        };
+        if !parsed {
+            eprintln!("Unrecognized option '--{}'", option.unwrap_or_default());
+            return Err(ExitCode::FAILURE);
+        }
+    }
+}
+
+// Parses what follows the pockets of a showdown into `spec`: `on <board>`, then `dead <cards>`
+// if `allowed` has "dead", then the options in `allowed`.
+fn parse_showdown_clauses(
+    stream: &mut ArgStream,
+    spec: &mut ShowdownSpec,
+    allowed: &[&str],
+) -> Result<(), ExitCode> {
+    stream.try_parse(stream_whitespace);
+    if stream
+        .try_parse(|t| stream_literal_ignorecase("on", t))
+        .is_some()
+    {
+        stream.try_parse(stream_whitespace);
+        spec.board = parse_board(stream)?;
+    }
+    stream.try_parse(stream_whitespace);
+    if allowed.contains(&"dead")
+        && stream
+            .try_parse(|t| stream_literal_ignorecase("dead", t))
+            .is_some()
+    {
+        stream.try_parse(stream_whitespace);
+        spec.dead = parse_cards(stream)?;
+    }
+
+    parse_showdown_options(stream, spec, allowed)
+}
+
+// Checks that the showdown described by `spec` can be dealt, and picks its game.
+pub fn build_showdown(spec: ShowdownSpec) -> Result<Showdown, ExitCode> {
+    let ShowdownSpec {
+        game,
+        pockets,
+        board,
+        dead,
+        format,
+        stopping_rule,
+        seed,
+    } = spec;
+
+    check_board(board)?;
+    if let Some(card) = (board & dead).iter_desc().next() {
+        eprintln!("Cannot have duplicate cards ({})", card);
+        return Err(ExitCode::FAILURE);
+    }
+
+    // Without `--game`, the size of the first pocket decides the game.
+    let game = match game {
+        Some(g) => g,
+        None => pockets[0]
+            .combos()
+            .first()
+            .and_then(|c| Game::for_pocket_size(c.pocket.len()))
+            .unwrap_or(Game::Holdem),
     };
+    check_pocket_sizes(game, pockets.as_slice())?;
+    let pockets = restrict_to_deck(game, pockets, board)?;
+
+    for range in &pockets {
+        if range.without(board).is_empty() {
+            eprintln!(
+                "{} has no pockets left that don't use a card on the board",
+                range
+            );
+            return Err(ExitCode::FAILURE);
+        }
+        if range.without(board | dead).is_empty() {
+            eprintln!("{} has no pockets left that don't use a dead card", range);
+            return Err(ExitCode::FAILURE);
+        }
+    }
+
+    if !has_matchup(pockets.as_slice(), board | dead) {
+        eprintln!("The given pockets cannot be dealt without duplicate cards");
+        return Err(ExitCode::FAILURE);
+    }
 
     Ok(Showdown {
-        pockets: monomorphize(pockets.into_iter()).unwrap(),
-        board: board,
+        game,
+        pockets,
+        board,
+        dead,
+        format,
+        stopping_rule,
+        seed,
     })
 }
 
-pub fn parse_input<I: Iterator<Item = String>>(args: I) -> Result<Showdown, ExitCode> {
-    let mut args = args.peekable();
-
-    let executable_name = match args.next() {
-        Some(arg) => arg,
-        None => "pop".to_string(),
+pub fn parse_showdown(stream: &mut ArgStream) -> Result<Showdown, ExitCode> {
+    let mut spec = ShowdownSpec {
+        pockets: parse_pockets(stream)?,
+        ..ShowdownSpec::default()
     };
+    let mut allowed = vec!["dead"];
+    allowed.extend(SHOWDOWN_OPTIONS);
+    parse_showdown_clauses(stream, &mut spec, &allowed)?;
+    build_showdown(spec)
+}
 
-    let operation = match args.next() {
-        Some(op) => op,
-        None => {
-            print_help(&executable_name, None);
-            return Err(ExitCode::SUCCESS);
-        }
+// Exact pockets on a flop or turn, written like a showdown with only `--game` as an option.
+pub fn parse_outs(stream: &mut ArgStream) -> Result<Outs, ExitCode> {
+    let mut spec = ShowdownSpec {
+        pockets: parse_pockets(stream)?,
+        ..ShowdownSpec::default()
     };
+    parse_showdown_clauses(stream, &mut spec, &["dead", "game"])?;
 
-    if args.peek() == Some(&"--help".to_string()) || args.peek() == Some(&"-h".to_string()) {
-        print_help(executable_name.as_str(), Some(operation.as_str()));
-        return Err(ExitCode::SUCCESS);
+    if spec.pockets.len() > MAX_OUTS_PLAYERS {
+        eprintln!(
+            "Cannot have more than {} pockets (have {})",
+            MAX_OUTS_PLAYERS,
+            spec.pockets.len()
+        );
+        return Err(ExitCode::FAILURE);
+    }
+    if let Some(range) = spec.pockets.iter().find(|r| r.single_pocket().is_none()) {
+        eprintln!("Outs need exact pockets, but {} is a range", range);
+        return Err(ExitCode::FAILURE);
+    }
+    if spec.board.len() != 3 && spec.board.len() != 4 {
+        eprintln!(
+            "Outs need a flop or a turn on the board, but the board has {} cards",
+            spec.board.len()
+        );
+        return Err(ExitCode::FAILURE);
     }
 
-    let mut stream = ArgStream::from(args);
+    let pockets = spec
+        .pockets
+        .iter()
+        .map(|r| r.single_pocket().unwrap())
+        .collect::<Vec<CardSet>>();
+    let showdown = build_showdown(spec)?;
+    Ok(Outs {
+        game: showdown.game,
+        pockets,
+        board: showdown.board,
+        dead: showdown.dead,
+    })
+}
 
-    let value = match operation.as_str() {
-        "showdown" => parse_showdown(&mut stream),
+// A range against a number of random pockets, then a board, dead cards and options like a
+// showdown's, except for `--format`.
+pub fn parse_vs_random(stream: &mut ArgStream) -> Result<VsRandom, ExitCode> {
+    let range = parse_range(stream)?;
+    stream.try_parse(stream_whitespace);
+    let token = stream.try_parse(stream_token);
+    let opponents = match token.as_deref().map(|t| t.parse::<usize>()) {
+        Some(Ok(n)) if (1..=MAX_OPPONENTS).contains(&n) => n,
         _ => {
-            print_unrecognized_operation(&executable_name, &operation);
-            Err(ExitCode::FAILURE)
+            eprintln!(
+                "Expected a number of opponents from 1 to {}, but got {}",
+                MAX_OPPONENTS,
+                token.unwrap_or("EOF".to_string())
+            );
+            return Err(ExitCode::FAILURE);
         }
     };
 
-    if stream.is_empty() {
-        value
-    } else {
-        println!(
-            "Unexpected trailing input: '{}'",
-            stream.try_parse(stream_drain).unwrap()
+    let pocket_size = range.combos()[0].pocket.len();
+    let mut spec = ShowdownSpec::default();
+    parse_showdown_clauses(
+        stream,
+        &mut spec,
+        &["dead", "game", "trials", "stderr", "seed"],
+    )?;
+
+    // Checked before the showdown is built, which would search every way to deal the pockets.
+    // Anything else wrong with the player's pockets, the board or the dead cards is reported
+    // when it's built.
+    let game = spec
+        .game
+        .or(Game::for_pocket_size(pocket_size))
+        .unwrap_or(Game::Holdem);
+    let missing = CardSet::universe() - game.deck().cards();
+    let undealt = range
+        .without(spec.board | spec.dead | missing)
+        .combos()
+        .first()
+        .and_then(|c| undealt_cards(&[c.pocket], spec.board, spec.dead, game).ok());
+    if undealt.is_some_and(|u| opponents * pocket_size + 5 - spec.board.len() > u.len()) {
+        eprintln!(
+            "There aren't enough cards left to deal {} opponents in {}",
+            opponents, game
         );
-        Err(ExitCode::FAILURE)
+        return Err(ExitCode::FAILURE);
+    }
+
+    spec.pockets = std::iter::once(range.clone())
+        .chain(std::iter::repeat_n(random_range(pocket_size), opponents))
+        .collect();
+    let mut showdown = build_showdown(spec)?;
+
+    // The showdown would pick between enumerating and sampling by visiting every matchup, which
+    // takes a while with many random pockets, so the choice is made here instead.
+    let range = range.without(CardSet::universe() - game.deck().cards());
+    if showdown.stopping_rule.is_none()
+        && count_runouts_vs_random(game, &range, opponents, showdown.board, showdown.dead)
+            > EXACT_RUNOUT_LIMIT
+    {
+        showdown.stopping_rule = Some(StoppingRule::StandardError(DEFAULT_STANDARD_ERROR));
+    }
+
+    Ok(VsRandom {
+        showdown,
+        opponents,
+    })
+}
+
+// A flop, turn or river, then `--top <n>`.
+pub fn parse_board_operation(stream: &mut ArgStream) -> Result<Board, ExitCode> {
+    let board = parse_board(stream)?;
+    if board.len() < 3 {
+        eprintln!(
+            "Expected a flop, turn or river, but the board has {} cards",
+            board.len()
+        );
+        return Err(ExitCode::FAILURE);
+    }
+
+    let mut top = DEFAULT_TOP_HANDS;
+    loop {
+        stream.try_parse(stream_whitespace);
+        if stream
+            .try_parse(|t| stream_literal_ignorecase("--", t))
+            .is_none()
+        {
+            break;
+        }
+
+        match stream.try_parse(stream_token).as_deref() {
+            Some("top") => {
+                top = parse_option_value(stream, "top", "a positive integer", |n| *n > 0)?;
+            }
+            other => {
+                eprintln!("Unrecognized option '--{}'", other.unwrap_or(""));
+                return Err(ExitCode::FAILURE);
+            }
+        }
+    }
+
+    Ok(Board { board, top })
+}
+
+// Exact pockets on a full board, then `--game`.
+pub fn parse_compare(stream: &mut ArgStream) -> Result<Compare, ExitCode> {
+    let mut spec = ShowdownSpec {
+        pockets: parse_pockets(stream)?,
+        ..ShowdownSpec::default()
+    };
+    parse_showdown_clauses(stream, &mut spec, &["game"])?;
+
+    if let Some(range) = spec.pockets.iter().find(|r| r.single_pocket().is_none()) {
+        eprintln!("Compare needs exact pockets, but {} is a range", range);
+        return Err(ExitCode::FAILURE);
+    }
+    if spec.board.len() != 5 {
+        eprintln!(
+            "Compare needs a full board, but the board has {} cards",
+            spec.board.len()
+        );
+        return Err(ExitCode::FAILURE);
+    }
+
+    let pockets = spec
+        .pockets
+        .iter()
+        .map(|r| r.single_pocket().unwrap())
+        .collect();
+    let showdown = build_showdown(spec)?;
+    Ok(Compare {
+        game: showdown.game,
+        pockets,
+        board: showdown.board,
+    })
+}
+
+// `+<cards>` to add cards to `current`, `clear` to remove them all, or `<cards>` to replace them.
+fn parse_card_change(stream: &mut ArgStream, current: CardSet) -> Result<CardSet, ExitCode> {
+    stream.try_parse(stream_whitespace);
+    if stream
+        .try_parse(|t| stream_literal_ignorecase("clear", t))
+        .is_some()
+    {
+        return Ok(CardSet::new());
+    }
+    if stream
+        .try_parse(|t| stream_literal_ignorecase("+", t))
+        .is_none()
+    {
+        return parse_cards(stream);
+    }
+
+    stream.try_parse(stream_whitespace);
+    let added = parse_cards(stream)?;
+    if added.is_empty() {
+        eprintln!(
+            "Expected a card after '+', but got {}",
+            stream.try_parse(stream_token).unwrap_or("EOF".to_string())
+        );
+        return Err(ExitCode::FAILURE);
+    }
+    if let Some(card) = (current & added).iter_desc().next() {
+        eprintln!("Cannot have duplicate cards ({})", card);
+        return Err(ExitCode::FAILURE);
+    }
+    Ok(current | added)
+}
+
+// A command typed into the REPL, which may change `spec`, the current showdown.
+pub fn parse_repl_command(
+    stream: &mut ArgStream,
+    spec: &ShowdownSpec,
+) -> Result<ReplCommand, ExitCode> {
+    stream.try_parse(stream_whitespace);
+    // Options are written the same way as for `showdown`, e.g. `--game omaha8 --seed 3`.
+    if stream
+        .try_parse(|t| (t(TakeMode::Peek) == Some('-')).then_some(()))
+        .is_some()
+    {
+        let mut changed = spec.clone();
+        parse_showdown_options(stream, &mut changed, &SHOWDOWN_OPTIONS)?;
+        return Ok(ReplCommand::Change(changed));
+    }
+
+    let command = stream.try_parse(stream_token).unwrap_or_default();
+    stream.try_parse(stream_whitespace);
+    let mut changed = spec.clone();
+    match command.to_ascii_lowercase().as_str() {
+        "show" => return Ok(ReplCommand::Show),
+        "undo" => return Ok(ReplCommand::Undo),
+        "help" => return Ok(ReplCommand::Help),
+        "quit" | "exit" => return Ok(ReplCommand::Quit),
+        "reset" => changed = ShowdownSpec::default(),
+        "players" => changed.pockets = parse_pockets(stream)?,
+        "add" => {
+            stream.try_parse(|t| stream_literal_ignorecase("player", t));
+            stream.try_parse(stream_whitespace);
+            changed.pockets.push(parse_range(stream)?);
+            if changed.pockets.len() > MAX_PLAYERS {
+                eprintln!(
+                    "Cannot have more than {} pockets (have {})",
+                    MAX_PLAYERS,
+                    changed.pockets.len()
+                );
+                return Err(ExitCode::FAILURE);
+            }
+        }
+        "remove" => {
+            stream.try_parse(|t| stream_literal_ignorecase("player", t));
+            stream.try_parse(stream_whitespace);
+            let token = stream.try_parse(stream_token);
+            match token.as_deref().map(|t| t.parse::<usize>()) {
+                Some(Ok(n)) if n >= 1 && n <= changed.pockets.len() => {
+                    changed.pockets.remove(n - 1);
+                }
+                _ => {
+                    eprintln!(
+                        "Expected a player number between 1 and {}, but got {}",
+                        changed.pockets.len(),
+                        token.unwrap_or("EOF".to_string())
+                    );
+                    return Err(ExitCode::FAILURE);
+                }
+            }
+        }
+        "board" => {
+            changed.board = parse_card_change(stream, spec.board)?;
+            check_board(changed.board)?;
+        }
+        "dead" => changed.dead = parse_card_change(stream, spec.dead)?,
+        other => {
+            eprintln!(
+                "Unrecognized command '{}'. Type `help` for a list of commands",
+                other
+            );
+            return Err(ExitCode::FAILURE);
+        }
+    }
+    Ok(ReplCommand::Change(changed))
+}
+
+// A stud card, or `??` for a down card that isn't known.
+fn stream_stud_card(take_char: &mut dyn FnMut(TakeMode) -> Option<char>) -> Option<Option<Card>> {
+    if take_char(TakeMode::Peek) == Some('?') {
+        take_char(TakeMode::Consume);
+        return match take_char(TakeMode::Consume) {
+            Some('?') => Some(None),
+            _ => None,
+        };
+    }
+    stream_card(take_char).map(Some)
+}
+
+// A stud hand's down cards, a `/` and its up cards, e.g. `AsKd/7c` or `????/Qh8h`.
+fn parse_stud_hand(stream: &mut ArgStream) -> Result<StudHand, ExitCode> {
+    let mut hand = StudHand {
+        down: CardSet::new(),
+        up: CardSet::new(),
+        unknown_down: 0,
+    };
+
+    while let Some(card) = stream.try_parse(|t| {
+        stream_whitespace(t);
+        stream_stud_card(t)
+    }) {
+        match card {
+            Some(c) if hand.down.has(c) => {
+                eprintln!("Cannot have duplicate cards ({})", c);
+                return Err(ExitCode::FAILURE);
+            }
+            Some(c) => hand.down += c,
+            None => hand.unknown_down += 1,
+        }
+    }
+
+    if stream
+        .try_parse(|t| stream_literal_ignorecase("/", t))
+        .is_none()
+    {
+        eprintln!(
+            "Expected down cards, a '/' and up cards, but got {}",
+            stream.try_parse(stream_token).unwrap_or("EOF".to_string())
+        );
+        return Err(ExitCode::FAILURE);
+    }
+
+    while let Some(card) = stream.try_parse(|t| {
+        stream_whitespace(t);
+        stream_card(t)
+    }) {
+        if hand.known().has(card) {
+            eprintln!("Cannot have duplicate cards ({})", card);
+            return Err(ExitCode::FAILURE);
+        }
+        hand.up += card;
+    }
+
+    Ok(hand)
+}
+
+// Every hand must be on the same street with the right number of down and up cards, and no card
+// can be seen twice.
+fn check_stud_hands(hands: &[StudHand], dead: CardSet) -> Result<(), ExitCode> {
+    const STREETS: [&str; 5] = ["Third", "Fourth", "Fifth", "Sixth", "Seventh"];
+
+    let dealt = hands[0].dealt();
+    if !(3..=STUD_HAND_SIZE).contains(&dealt) {
+        eprintln!(
+            "Stud hands must have 3 to {} cards, but {} has {}",
+            STUD_HAND_SIZE, hands[0], dealt
+        );
+        return Err(ExitCode::FAILURE);
+    }
+
+    let up = (dealt - 2).min(MAX_UP_CARDS);
+    let mut seen = dead;
+    for hand in hands {
+        if hand.dealt() != dealt {
+            eprintln!(
+                "Every stud hand must have the same number of cards, but {} has {}",
+                hand,
+                hand.dealt()
+            );
+            return Err(ExitCode::FAILURE);
+        }
+        if hand.up.len() != up {
+            eprintln!(
+                "{} street hands have {} down and {} up cards, but {} has {} up",
+                STREETS[dealt - 3],
+                dealt - up,
+                up,
+                hand,
+                hand.up.len()
+            );
+            return Err(ExitCode::FAILURE);
+        }
+        if let Some(card) = (seen & hand.known()).iter_desc().next() {
+            eprintln!("Cannot have duplicate cards ({})", card);
+            return Err(ExitCode::FAILURE);
+        }
+        seen |= hand.known();
+    }
+
+    let needed = hands.iter().map(cards_to_deal).sum::<usize>();
+    let left = CardSet::universe().len() - seen.len();
+    if needed > left {
+        eprintln!(
+            "Dealing every hand {} cards needs {} more cards, but only {} are left",
+            STUD_HAND_SIZE, needed, left
+        );
+        return Err(ExitCode::FAILURE);
+    }
+    Ok(())
+}
+
+// Parses a stud game's name for `--game`.
+struct StudGameName(StudGame);
+
+impl FromStr for StudGameName {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        StudGame::from_name(s).map(StudGameName).ok_or(())
+    }
+}
+
+pub fn parse_stud(stream: &mut ArgStream) -> Result<Stud, ExitCode> {
+    let mut hands = Vec::<StudHand>::new();
+    loop {
+        if !hands.is_empty() {
+            stream.try_parse(stream_whitespace);
+            if stream
+                .try_parse(|t| stream_literal_ignorecase("vs", t))
+                .is_none()
+            {
+                break;
+            }
+            stream.try_parse(stream_whitespace);
+        }
+        hands.push(parse_stud_hand(stream)?);
+    }
+
+    if hands.len() < 2 || hands.len() > MAX_STUD_PLAYERS {
+        eprintln!(
+            "Must have 2 to {} stud hands (have {})",
+            MAX_STUD_PLAYERS,
+            hands.len()
+        );
+        return Err(ExitCode::FAILURE);
+    }
+
+    stream.try_parse(stream_whitespace);
+    let dead = match stream.try_parse(|t| stream_literal_ignorecase("dead", t)) {
+        Some(_) => {
+            stream.try_parse(stream_whitespace);
+            parse_cards(stream)?
+        }
+        None => CardSet::new(),
+    };
+
+    let mut game = StudGame::High;
+    let mut streets_left = None;
+    let mut stopping_rule = None;
+    let mut seed = None;
+
+    loop {
+        stream.try_parse(stream_whitespace);
+        if stream
+            .try_parse(|t| stream_literal_ignorecase("--", t))
+            .is_none()
+        {
+            break;
+        }
+
+        match stream.try_parse(stream_token).as_deref() {
+            Some("game") => {
+                let names = ALL_STUD_GAMES.map(|g| g.name()).join(", ");
+                let StudGameName(g) = parse_option_value(
+                    stream,
+                    "game",
+                    &format!("one of {}", names),
+                    |_: &StudGameName| true,
+                )?;
+                game = g;
+            }
+            Some("streets") => {
+                let most = STUD_HAND_SIZE - 3;
+                streets_left = Some(parse_option_value(
+                    stream,
+                    "streets",
+                    &format!("a number of streets from 0 to {}", most),
+                    |n: &usize| *n <= most,
+                )?);
+            }
+            other => {
+                if !parse_sampling_option(stream, other, &mut stopping_rule, &mut seed)? {
+                    eprintln!("Unrecognized option '--{}'", other.unwrap_or(""));
+                    return Err(ExitCode::FAILURE);
+                }
+            }
+        }
+    }
+
+    check_stud_hands(hands.as_slice(), dead)?;
+
+    Ok(Stud {
+        game,
+        streets_left,
+        hands,
+        dead,
+        stopping_rule,
+        seed,
+    })
+}
+
+// The file to read showdowns from, followed by `--format`.
+pub fn parse_batch(stream: &mut ArgStream) -> Result<Batch, ExitCode> {
+    let path = match stream.try_parse(stream_token) {
+        Some(path) if !path.starts_with("--") => path,
+        other => {
+            eprintln!(
+                "Expected a file or - for stdin, but got {}",
+                other.unwrap_or("EOF".to_string())
+            );
+            return Err(ExitCode::FAILURE);
+        }
+    };
+
+    let mut format = OutputFormat::Text;
+    loop {
+        stream.try_parse(stream_whitespace);
+        if stream
+            .try_parse(|t| stream_literal_ignorecase("--", t))
+            .is_none()
+        {
+            break;
+        }
+
+        match stream.try_parse(stream_token).as_deref() {
+            Some("format") => {
+                let names = ALL_OUTPUT_FORMATS.map(|f| f.name()).join(", ");
+                format = parse_option_value(
+                    stream,
+                    "format",
+                    &format!("one of {}", names),
+                    |_: &OutputFormat| true,
+                )?;
+            }
+            other => {
+                eprintln!("Unrecognized option '--{}'", other.unwrap_or(""));
+                return Err(ExitCode::FAILURE);
+            }
+        }
+    }
+
+    Ok(Batch { path, format })
+}
+
+// A starting hand such as `AA`, `AKs` or `AKo`. Unpaired hands must say whether they're suited.
+fn stream_hand_class(take_char: &mut dyn FnMut(TakeMode) -> Option<char>) -> Option<HandClass> {
+    let first = stream_rank(take_char)?;
+    let second = stream_rank(take_char)?;
+    let (higher_rank, lower_rank) = (first.max(second), first.min(second));
+    match (higher_rank == lower_rank, stream_suitedness(take_char)) {
+        (true, Suitedness::Any) => Some(HandClass::PocketPair { rank: higher_rank }),
+        (false, Suitedness::Suited) => Some(HandClass::Suited {
+            higher_rank,
+            lower_rank,
+        }),
+        (false, Suitedness::Offsuit) => Some(HandClass::Offsuit {
+            higher_rank,
+            lower_rank,
+        }),
+        _ => None,
+    }
+}
+
+fn parse_hand_class(stream: &mut ArgStream) -> Result<HandClass, ExitCode> {
+    match stream.try_parse(stream_hand_class) {
+        Some(class) => Ok(class),
+        None => {
+            eprintln!(
+                "Expected a starting hand such as AA, AKs or AKo, but got {}",
+                stream.try_parse(stream_token).unwrap_or("EOF".to_string())
+            );
+            Err(ExitCode::FAILURE)
+        }
+    }
+}
+
+pub fn parse_matrix(stream: &mut ArgStream) -> Result<Matrix, ExitCode> {
+    let command = stream.try_parse(stream_token);
+    if !matches!(command.as_deref(), Some("build") | Some("lookup")) {
+        eprintln!(
+            "Expected build or lookup, but got {}",
+            command.unwrap_or("EOF".to_string())
+        );
+        return Err(ExitCode::FAILURE);
+    }
+
+    stream.try_parse(stream_whitespace);
+    let path = match stream.try_parse(stream_token) {
+        Some(path) if !path.starts_with("--") => path,
+        other => {
+            eprintln!(
+                "Expected a file, but got {}",
+                other.unwrap_or("EOF".to_string())
+            );
+            return Err(ExitCode::FAILURE);
+        }
+    };
+
+    if command.as_deref() == Some("lookup") {
+        stream.try_parse(stream_whitespace);
+        let hero = parse_hand_class(stream)?;
+        stream.try_parse(stream_whitespace);
+        if stream
+            .try_parse(|t| stream_literal_ignorecase("vs", t))
+            .is_none()
+        {
+            eprintln!("Expected vs after {}", hero);
+            return Err(ExitCode::FAILURE);
+        }
+        stream.try_parse(stream_whitespace);
+        let villain = parse_hand_class(stream)?;
+        return Ok(Matrix::Lookup {
+            path,
+            hero,
+            villain,
+        });
+    }
+
+    let mut cells = None;
+    loop {
+        stream.try_parse(stream_whitespace);
+        if stream
+            .try_parse(|t| stream_literal_ignorecase("--", t))
+            .is_none()
+        {
+            break;
+        }
+
+        match stream.try_parse(stream_token).as_deref() {
+            Some("cells") => {
+                cells = Some(parse_option_value(
+                    stream,
+                    "cells",
+                    "a number of matchups",
+                    |_: &usize| true,
+                )?);
+            }
+            other => {
+                eprintln!("Unrecognized option '--{}'", other.unwrap_or(""));
+                return Err(ExitCode::FAILURE);
+            }
+        }
+    }
+
+    Ok(Matrix::Build { path, cells })
+}
+
+// Fails if anything is left in `stream` after an operation was parsed.
+fn expect_end_of_input(stream: &mut ArgStream) -> Result<(), ExitCode> {
+    if stream.is_empty() {
+        Ok(())
+    } else {
+        eprintln!(
+            "Unexpected trailing input: '{}'",
+            stream.try_parse(stream_drain).unwrap()
+        );
+        Err(ExitCode::FAILURE)
+    }
+}
+
+// A showdown written on a single line of a batch, without the `showdown` operation.
+pub fn parse_showdown_line(line: &str) -> Result<Showdown, ExitCode> {
+    let mut stream = ArgStream::from(line.split_whitespace().map(String::from));
+    let showdown = parse_showdown(&mut stream)?;
+    expect_end_of_input(&mut stream)?;
+    Ok(showdown)
+}
+
+pub fn parse_input<I: Iterator<Item = String>>(args: I) -> Result<Operation, ExitCode> {
+    let mut args = args.peekable();
+
+    let executable_name = match args.next() {
+        Some(arg) => arg,
+        None => "pop".to_string(),
+    };
+
+    let operation = match args.next() {
+        Some(op) => op,
+        None => {
+            print_help(&executable_name, None);
+            return Err(ExitCode::SUCCESS);
+        }
+    };
+
+    if args.peek() == Some(&"--help".to_string()) || args.peek() == Some(&"-h".to_string()) {
+        print_help(executable_name.as_str(), Some(operation.as_str()));
+        return Err(ExitCode::SUCCESS);
+    }
+
+    let mut stream = ArgStream::from(args);
+
+    let value = match operation.as_str() {
+        "showdown" => parse_showdown(&mut stream).map(Operation::Showdown),
+        "stud" => parse_stud(&mut stream).map(Operation::Stud),
+        "batch" => parse_batch(&mut stream).map(Operation::Batch),
+        "repl" => Ok(Operation::Repl),
+        "outs" => parse_outs(&mut stream).map(Operation::Outs),
+        "matrix" => parse_matrix(&mut stream).map(Operation::Matrix),
+        "vs-random" => parse_vs_random(&mut stream).map(Operation::VsRandom),
+        "board" => parse_board_operation(&mut stream).map(Operation::Board),
+        "compare" => parse_compare(&mut stream).map(Operation::Compare),
+        _ => {
+            print_unrecognized_operation(&executable_name, &operation);
+            Err(ExitCode::FAILURE)
+        }
+    }?;
+
+    expect_end_of_input(&mut stream)?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(text: &str) -> ArgStream {
+        ArgStream::from(text.split(' ').map(|s| s.to_string()))
+    }
+
+    fn combo_count(text: &str) -> usize {
+        let mut stream = stream(text);
+        let range = parse_range(&mut stream).unwrap();
+        assert!(stream.is_empty());
+        range.len()
+    }
+
+    #[test]
+    fn test_parse_range_explicit_pocket() {
+        let mut stream = stream("As, Ks");
+        let range = parse_range(&mut stream).unwrap();
+
+        assert_eq!(
+            range.single_pocket(),
+            Some(CardSet::from(&[Card::ACE_SPADE, Card::KING_SPADE]))
+        );
+    }
+
+    #[test]
+    fn test_parse_range_placeholders() {
+        assert_eq!(combo_count("????"), 1326);
+        assert_eq!(combo_count("As??"), 51);
+        assert_eq!(combo_count("Kx??"), 6 + 4 * 48);
+        assert_eq!(combo_count("KxQx"), 16);
+        assert_eq!(combo_count("AxAx, KK"), 12);
+        assert_eq!(combo_count("?? ?? ?? ??"), 270725);
+        assert_eq!(
+            parse_range(&mut stream("Kx??")).unwrap().to_string(),
+            "Kx??"
+        );
+
+        assert!(parse_range(&mut stream("As??As")).is_err());
+        assert!(parse_range(&mut stream("AxAxAxAxAx")).is_err());
+        assert!(parse_range(&mut stream("A?")).is_err());
+
+        let showdown = parse_showdown(&mut stream("AsKs vs ???? on 2c 3d 4h")).unwrap();
+        assert_eq!(showdown.game, Game::Holdem);
+        let showdown = parse_showdown(&mut stream("AsAhKsKh vs ????????")).unwrap();
+        assert_eq!(showdown.game, Game::Omaha);
+    }
+
+    #[test]
+    fn test_parse_range_specs() {
+        assert_eq!(combo_count("QQ"), 6);
+        assert_eq!(combo_count("AKs"), 4);
+        assert_eq!(combo_count("AKo"), 12);
+        assert_eq!(combo_count("KA"), 16);
+        assert_eq!(combo_count("QQ+"), 18);
+        assert_eq!(combo_count("ATs+"), 16);
+        assert_eq!(combo_count("22-99"), 48);
+        assert_eq!(combo_count("99-22"), 48);
+        assert_eq!(combo_count("A2s-A5s"), 16);
+        assert_eq!(combo_count("QQ+, AKs,AsKs"), 22);
+    }
+
+    #[test]
+    fn test_parse_range_rejects_bad_specs() {
+        assert!(parse_range(&mut stream("QQs")).is_err());
+        assert!(parse_range(&mut stream("22-AK")).is_err());
+        assert!(parse_range(&mut stream("A2s-K5s")).is_err());
+        assert!(parse_range(&mut stream("AsAs")).is_err());
+        assert!(parse_range(&mut stream("AKs:1.5")).is_err());
+    }
+
+    #[test]
+    fn test_parse_range_weights() {
+        let range = parse_range(&mut stream("AKs:0.5,QQ")).unwrap();
+
+        assert_eq!(range.to_string(), "AKs:0.5,QQ");
+        assert_eq!(range.len(), 10);
+        for combo in range.combos() {
+            let expected = if combo.pocket.iter_desc().next().unwrap().rank == Rank::Ace {
+                1
+            } else {
+                2
+            };
+            assert_eq!(combo.weight, expected);
+        }
+    }
+
+    #[test]
+    fn test_range_from_str() {
+        let range = "AKs:0.5, QQ+".parse::<HandRange>().unwrap();
+        assert_eq!(range.to_string(), "AKs:0.5,QQ+");
+        assert_eq!(range.len(), 22);
+
+        assert_eq!(
+            "QQs".parse::<HandRange>(),
+            Err(PopError::InvalidRange(
+                "Expected a pocket or a range, but got QQs".to_string()
+            ))
+        );
+        assert!("AKs vs QQ".parse::<HandRange>().is_err());
+    }
+
+    #[test]
+    fn test_parse_pockets_with_ranges() {
+        let pockets = parse_pockets(&mut stream("AhKh vs QQ+, AKs vs 22-55")).unwrap();
+
+        assert_eq!(pockets.len(), 3);
+        assert_eq!(pockets[0].len(), 1);
+        assert_eq!(pockets[1].len(), 22);
+        assert_eq!(pockets[2].len(), 24);
+    }
+
+    #[test]
+    fn test_parse_showdown_sampling_options() {
+        let showdown =
+            parse_showdown(&mut stream("AhKh vs QQ on 2c 3d 4h --trials 500 --seed 7")).unwrap();
+
+        assert_eq!(showdown.board.len(), 3);
+        assert_eq!(showdown.stopping_rule, Some(StoppingRule::Trials(500)));
+        assert_eq!(showdown.seed, Some(7));
+
+        let showdown = parse_showdown(&mut stream("AhKh vs QQ --stderr 0.1")).unwrap();
+        assert_eq!(
+            showdown.stopping_rule,
+            Some(StoppingRule::StandardError(0.001))
+        );
+        assert_eq!(showdown.seed, None);
+
+        assert!(parse_showdown(&mut stream("AhKh vs QQ --trials 0")).is_err());
+        assert!(parse_showdown(&mut stream("AhKh vs QQ --seed")).is_err());
+        assert!(parse_showdown(&mut stream("AhKh vs QQ --bogus 1")).is_err());
+
+        let showdown = parse_showdown(&mut stream("AhKh vs QQ --format json")).unwrap();
+        assert_eq!(showdown.format, OutputFormat::Json);
+        assert!(parse_showdown(&mut stream("AhKh vs QQ --format xml")).is_err());
+    }
+
+    #[test]
+    fn test_parse_showdown_dead_cards() {
+        let showdown = parse_showdown(&mut stream("AhKh vs QQ on 2c 3d 4h dead Qs 5d")).unwrap();
+        assert_eq!(showdown.board.len(), 3);
+        assert_eq!(showdown.dead.len(), 2);
+
+        let showdown = parse_showdown(&mut stream("AhKh vs QQ dead Qs --seed 3")).unwrap();
+        assert_eq!(showdown.dead.len(), 1);
+        assert_eq!(showdown.seed, Some(3));
+
+        // A dead card on the board, a dead card in a pocket and a range with every combo dead.
+        assert!(parse_showdown(&mut stream("AhKh vs QQ on 2c 3d 4h dead 2c")).is_err());
+        assert!(parse_showdown(&mut stream("AhKh vs QQ dead Kh")).is_err());
+        assert!(parse_showdown(&mut stream("AhKh vs QsQd dead Qd")).is_err());
+    }
+
+    #[test]
+    fn test_parse_showdown_omaha() {
+        let showdown = parse_showdown(&mut stream("AsAhKsKh vs Qc Qd Jc Jd on 2c 3d 4h")).unwrap();
+        assert_eq!(showdown.game, Game::Omaha);
+        assert_eq!(showdown.pockets.len(), 2);
+        assert_eq!(showdown.pockets[1].single_pocket().unwrap().len(), 4);
+
+        let showdown = parse_showdown(&mut stream("AsKs vs QQ --game holdem")).unwrap();
+        assert_eq!(showdown.game, Game::Holdem);
+
+        assert!(parse_showdown(&mut stream("AsAhKsKh vs QQ")).is_err());
+        assert!(parse_showdown(&mut stream("AsAhKsKh vs QcQdJcJd --game holdem")).is_err());
+        assert!(parse_showdown(&mut stream("AsAhKsKh vs QcQdJcJdTc")).is_err());
+        assert!(parse_showdown(&mut stream("AsAhKsKsQh vs QcQdJcJd")).is_err());
+        assert!(parse_showdown(&mut stream("AsKs vs QQ --game stud")).is_err());
+
+        let showdown = parse_showdown(&mut stream("AsAh2s3h vs QcQdJcJd --game omaha8")).unwrap();
+        assert_eq!(showdown.game, Game::OmahaHiLo);
+        assert!(parse_showdown(&mut stream("AsKs vs QQ --game omaha8")).is_err());
+    }
+
+    #[test]
+    fn test_parse_showdown_short_deck() {
+        let showdown = parse_showdown(&mut stream("AsKs vs 22+ --game shortdeck")).unwrap();
+        assert_eq!(
+            showdown.game,
+            Game::ShortDeck {
+                trips_beat_straights: false
+            }
+        );
+        assert_eq!(showdown.pockets.len(), 2);
+        // Only 66 to AA are left in 22+.
+        assert_eq!(showdown.pockets[1].len(), 9 * 6);
+
+        assert!(parse_showdown(&mut stream("AsKs vs 22-55 --game shortdeck")).is_err());
+        assert!(parse_showdown(&mut stream("AsKs vs QQ on 5c 7d 8h --game shortdeck")).is_err());
+        assert!(parse_showdown(&mut stream("As2s vs QQ --game shortdeck-trips")).is_err());
+    }
+
+    #[test]
+    fn test_parse_batch() {
+        let batch = parse_batch(&mut stream("matchups.txt --format csv")).unwrap();
+        assert_eq!(batch.path, "matchups.txt");
+        assert_eq!(batch.format, OutputFormat::Csv);
+
+        let batch = parse_batch(&mut stream("-")).unwrap();
+        assert_eq!(batch.path, "-");
+        assert_eq!(batch.format, OutputFormat::Text);
+
+        assert!(parse_batch(&mut stream("--format csv")).is_err());
+        assert!(parse_batch(&mut stream("matchups.txt --trials 5")).is_err());
+    }
+
+    #[test]
+    fn test_parse_showdown_line() {
+        let showdown = parse_showdown_line("  AsKs vs QQ\ton Qc 2c 3c --seed 1 ").unwrap();
+        assert_eq!(showdown.board.len(), 3);
+        assert_eq!(showdown.seed, Some(1));
+
+        assert!(parse_showdown_line("AsKs vs QQ on Qc 2c 3c extra").is_err());
+        assert!(parse_showdown_line("AsKs").is_err());
+    }
+
+    #[test]
+    fn test_parse_outs() {
+        let outs = parse_outs(&mut stream(
+            "AhKh vs QsQd on Qh 7h 2c dead 3s --game holdem",
+        ))
+        .unwrap();
+        assert_eq!(outs.game, Game::Holdem);
+        assert_eq!(outs.pockets.len(), 2);
+        assert_eq!(outs.board.len(), 3);
+        assert_eq!(outs.dead.len(), 1);
+
+        assert!(parse_outs(&mut stream("AhKh vs QQ on Qh 7h 2c")).is_err());
+        assert!(parse_outs(&mut stream("AhKh vs QsQd")).is_err());
+        assert!(parse_outs(&mut stream("AhKh vs QsQd on Qh 7h 2c 3s 4d")).is_err());
+        assert!(parse_outs(&mut stream("AhKh vs QsQd on Qh 7h 2c --seed 1")).is_err());
+    }
+
+    #[test]
+    fn test_parse_compare() {
+        let compare = parse_compare(&mut stream("AsKs vs AhKh vs QcQd on Kd 9c 9h 4s 2c")).unwrap();
+        assert_eq!(compare.game, Game::Holdem);
+        assert_eq!(compare.pockets.len(), 3);
+        assert_eq!(compare.board.len(), 5);
+
+        let compare = parse_compare(&mut stream(
+            "AsKs2d3d vs QcQd7h8h on Kd 9c 9h 4s 2c --game omaha8",
+        ))
+        .unwrap();
+        assert_eq!(compare.game, Game::OmahaHiLo);
+
+        assert!(parse_compare(&mut stream("AsKs vs QQ on Kd 9c 9h 4s 2c")).is_err());
+        assert!(parse_compare(&mut stream("AsKs vs QcQd on Kd 9c 9h 4s")).is_err());
+        assert!(parse_compare(&mut stream("AsKs vs QcQd on Kd 9c 9h 4s 2c --seed 1")).is_err());
+        assert!(parse_compare(&mut stream("AsKs vs KdQd on Kd 9c 9h 4s 2c")).is_err());
+    }
+
+    #[test]
+    fn test_parse_matrix() {
+        match parse_matrix(&mut stream("lookup equities.csv AKs vs qq")) {
+            Ok(Matrix::Lookup {
+                path,
+                hero,
+                villain,
+            }) => {
+                assert_eq!(path, "equities.csv");
+                assert_eq!(hero.to_string(), "AKs");
+                assert_eq!(villain.to_string(), "QQ");
+            }
+            _ => panic!("Expected a lookup"),
+        }
+        assert!(matches!(
+            parse_matrix(&mut stream("build equities.csv --cells 10")),
+            Ok(Matrix::Build {
+                cells: Some(10),
+                ..
+            })
+        ));
+
+        assert!(parse_matrix(&mut stream("lookup equities.csv AK vs QQ")).is_err());
+        assert!(parse_matrix(&mut stream("lookup equities.csv QQs vs AKo")).is_err());
+        assert!(parse_matrix(&mut stream("build --cells 10")).is_err());
+        assert!(parse_matrix(&mut stream("equities.csv")).is_err());
+    }
+
+    #[test]
+    fn test_parse_vs_random() {
+        let vs_random = parse_vs_random(&mut stream("76s 5 on Kd 7c 2h --seed 3")).unwrap();
+        assert_eq!(vs_random.opponents, 5);
+        assert_eq!(vs_random.showdown.pockets.len(), 6);
+        assert_eq!(vs_random.showdown.board.len(), 3);
+        // Far too many runouts to enumerate.
+        assert!(vs_random.showdown.stopping_rule.is_some());
+
+        let vs_random = parse_vs_random(&mut stream("AsKs 1 on Qs 7h 2d")).unwrap();
+        assert!(vs_random.showdown.stopping_rule.is_none());
+
+        assert!(parse_vs_random(&mut stream("AsKs 0")).is_err());
+        assert!(parse_vs_random(&mut stream("AsKs 23")).is_err());
+        assert!(parse_vs_random(&mut stream("AsKs")).is_err());
+        assert!(parse_vs_random(&mut stream("AsKs 2 --format json")).is_err());
+        assert!(parse_vs_random(&mut stream("AsKsQsJs 12")).is_err());
+
+        // With every opponent, the pockets and the board use all but one card of the deck.
+        let vs_random = parse_vs_random(&mut stream(&format!(
+            "AsKs {} dead 2c --trials 10",
+            MAX_OPPONENTS
+        )))
+        .unwrap();
+        assert_eq!(vs_random.showdown.pockets.len(), MAX_OPPONENTS + 1);
+        assert!(
+            parse_vs_random(&mut stream(&format!(
+                "AsKs {} dead 2c 3c --trials 10",
+                MAX_OPPONENTS
+            )))
+            .is_err()
+        );
+        assert!(
+            parse_vs_random(&mut stream(&format!("AsKs {} on 2c 3c 4c", MAX_OPPONENTS))).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_parse_board_operation() {
+        let board = parse_board_operation(&mut stream("Kd 7c 2h --top 3")).unwrap();
+        assert_eq!(board.board.len(), 3);
+        assert_eq!(board.top, 3);
+        assert_eq!(
+            parse_board_operation(&mut stream("Kd7c2h9s")).unwrap().top,
+            DEFAULT_TOP_HANDS
+        );
+
+        assert!(parse_board_operation(&mut stream("Kd 7c")).is_err());
+        assert!(parse_board_operation(&mut stream("Kd 7c 2h --top 0")).is_err());
+    }
+
+    fn change(text: &str, spec: &ShowdownSpec) -> ShowdownSpec {
+        match parse_repl_command(&mut stream(text), spec) {
+            Ok(ReplCommand::Change(changed)) => changed,
+            _ => panic!("Expected '{}' to change the showdown", text),
+        }
+    }
+
+    #[test]
+    fn test_parse_repl_commands() {
+        let spec = change("players AsKs vs QQ", &ShowdownSpec::default());
+        assert_eq!(spec.pockets.len(), 2);
+        let spec = change("add player JJ+", &spec);
+        assert_eq!(spec.pockets.len(), 3);
+        let spec = change("remove player 2", &spec);
+        assert_eq!(spec.pockets[1].to_string(), "JJ+");
+
+        let spec = change("board Qc 2c 3c", &spec);
+        let spec = change("board +Kh", &spec);
+        assert_eq!(spec.board.len(), 4);
+        let spec = change("dead +2d", &spec);
+        assert_eq!(spec.dead.len(), 1);
+        assert!(change("board clear", &spec).board.is_empty());
+
+        let spec = change("--game omaha --seed 5", &spec);
+        assert_eq!(spec.game, Some(Game::Omaha));
+        assert_eq!(spec.seed, Some(5));
+
+        assert!(matches!(
+            parse_repl_command(&mut stream("undo"), &spec),
+            Ok(ReplCommand::Undo)
+        ));
+        assert!(parse_repl_command(&mut stream("board +Qc"), &spec).is_err());
+        assert!(parse_repl_command(&mut stream("board +5h 6h"), &spec).is_err());
+        assert!(parse_repl_command(&mut stream("remove player 3"), &spec).is_err());
+        assert!(parse_repl_command(&mut stream("deal"), &spec).is_err());
+    }
+
+    #[test]
+    fn test_repl_add_player_rejects_too_many_players() {
+        let players = vec!["22+"; MAX_PLAYERS - 1].join(" vs ");
+        let spec = change(&format!("players {}", players), &ShowdownSpec::default());
+        let spec = change("add player AKs", &spec);
+        assert_eq!(spec.pockets.len(), MAX_PLAYERS);
+
+        assert!(parse_repl_command(&mut stream("add player AKo"), &spec).is_err());
+    }
+
+    #[test]
+    fn test_parse_stud() {
+        let stud = parse_stud(&mut stream("AsKd/7c vs ????/Qh dead 2c 3d --game razz")).unwrap();
+        assert_eq!(stud.game, StudGame::Razz);
+        assert_eq!(stud.streets_left, None);
+        assert_eq!(stud.hands[0].streets_left(), 4);
+        assert_eq!(stud.dead.len(), 2);
+        assert_eq!(stud.hands.len(), 2);
+        assert_eq!(stud.hands[0].up.len(), 1);
+        assert_eq!(stud.hands[1].unknown_down, 2);
+        assert_eq!(stud.hands[1].to_string(), "(????) Q♥");
+
+        let stud = parse_stud(&mut stream("As Kd/7c 8c vs Qs Qd/2h 3h --trials 100")).unwrap();
+        assert_eq!(stud.game, StudGame::High);
+        assert_eq!(stud.streets_left, None);
+        assert_eq!(stud.hands[0].streets_left(), 3);
+        assert_eq!(stud.stopping_rule, Some(StoppingRule::Trials(100)));
+
+        let stud = parse_stud(&mut stream("AsKd/7c vs QsQd/2h --streets 4")).unwrap();
+        assert_eq!(stud.streets_left, Some(4));
+        assert!(parse_stud(&mut stream("AsKd/7c vs QsQd/2h --streets 5")).is_err());
+    }
+
+    #[test]
+    fn test_parse_stud_rejects_bad_hands() {
+        // Different streets, too many up cards, a missing '/', a duplicate and a dead card that
+        // is in a hand.
+        assert!(parse_stud(&mut stream("AsKd/7c vs QsQd/2h3h")).is_err());
+        assert!(parse_stud(&mut stream("As/Kd7c vs Qs/Qd2h")).is_err());
+        assert!(parse_stud(&mut stream("AsKd7c vs QsQd/2h")).is_err());
+        assert!(parse_stud(&mut stream("AsKd/7c vs AsQd/2h")).is_err());
+        assert!(parse_stud(&mut stream("AsKd/7c vs QsQd/2h dead 7c")).is_err());
+        assert!(parse_stud(&mut stream("AsKd/7c")).is_err());
     }
 }
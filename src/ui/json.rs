@@ -0,0 +1,134 @@
+use std::fmt::{Display, Write};
+
+use crate::cards::{card::Card, cardset::CardSet};
+
+// A JSON value, written out by `Display` with 2-space indentation. Object keys keep the order
+// they were given in, so the output is stable.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Integer(u64),
+    Float(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl Json {
+    pub fn string(text: impl Display) -> Self {
+        Json::String(text.to_string())
+    }
+
+    pub fn count(count: usize) -> Self {
+        Json::Integer(count as u64)
+    }
+
+    // A card written the way it's typed, e.g. "As".
+    pub fn card(card: Card) -> Self {
+        Json::String(format!("{}{}", card.rank, card.suit.letter()))
+    }
+
+    // The cards in descending order.
+    pub fn cards(cards: CardSet) -> Self {
+        Json::Array(cards.iter_desc().map(Json::card).collect())
+    }
+
+    fn write(&self, f: &mut std::fmt::Formatter<'_>, indent: usize) -> std::fmt::Result {
+        match self {
+            Json::Integer(n) => write!(f, "{}", n),
+            // JSON has no NaN or infinity.
+            Json::Float(x) if !x.is_finite() => f.write_str("null"),
+            Json::Float(x) => write!(f, "{}", x),
+            Json::String(s) => write_string(f, s),
+            Json::Array(items) if items.is_empty() => f.write_str("[]"),
+            Json::Array(items) => {
+                f.write_str("[\n")?;
+                for (i, item) in items.iter().enumerate() {
+                    write_indent(f, indent + 1)?;
+                    item.write(f, indent + 1)?;
+                    f.write_str(if i + 1 < items.len() { ",\n" } else { "\n" })?;
+                }
+                write_indent(f, indent)?;
+                f.write_char(']')
+            }
+            Json::Object(fields) if fields.is_empty() => f.write_str("{}"),
+            Json::Object(fields) => {
+                f.write_str("{\n")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    write_indent(f, indent + 1)?;
+                    write_string(f, key)?;
+                    f.write_str(": ")?;
+                    value.write(f, indent + 1)?;
+                    f.write_str(if i + 1 < fields.len() { ",\n" } else { "\n" })?;
+                }
+                write_indent(f, indent)?;
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+fn write_indent(f: &mut std::fmt::Formatter<'_>, indent: usize) -> std::fmt::Result {
+    for _ in 0..indent {
+        f.write_str("  ")?;
+    }
+    Ok(())
+}
+
+fn write_string(f: &mut std::fmt::Formatter<'_>, s: &str) -> std::fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_scalars() {
+        assert_eq!(Json::count(42).to_string(), "42");
+        assert_eq!(Json::Float(12.5).to_string(), "12.5");
+        assert_eq!(Json::Float(f64::NAN).to_string(), "null");
+        assert_eq!(
+            Json::string("A\"b\\c\n\u{1}♠").to_string(),
+            "\"A\\\"b\\\\c\\n\\u0001♠\""
+        );
+        assert_eq!(Json::card(Card::ACE_SPADE).to_string(), "\"As\"");
+    }
+
+    #[test]
+    fn test_json_nesting() {
+        let json = Json::Object(vec![
+            (
+                "board",
+                Json::cards(CardSet::from(&[Card::TWO_CLUB, Card::KING_HEART])),
+            ),
+            ("dead", Json::Array(vec![])),
+            (
+                "players",
+                Json::Array(vec![Json::Object(vec![("wins", Json::count(3))])]),
+            ),
+        ]);
+
+        assert_eq!(
+            json.to_string(),
+            "{\n  \"board\": [\n    \"Kh\",\n    \"2c\"\n  ],\n  \"dead\": [],\n  \"players\": [\n    {\n      \"wins\": 3\n    }\n  ]\n}"
+        );
+    }
+}
@@ -1,3 +1,4 @@
 pub mod argparser;
 pub mod input;
+pub mod json;
 pub mod output;
@@ -1,4 +1,65 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::cards::cardset::CardSet;
+use crate::operations::batch::print_batch_help;
+use crate::operations::board::print_board_help;
+use crate::operations::compare::print_compare_help;
+use crate::operations::matrix::print_matrix_help;
+use crate::operations::outs::print_outs_help;
+use crate::operations::repl::print_repl_help;
 use crate::operations::showdown::print_showdown_help;
+use crate::operations::stud::print_stud_help;
+use crate::operations::vs_random::print_vs_random_help;
+
+// How an operation prints its results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+    Csv,
+    Tsv,
+}
+
+pub const ALL_OUTPUT_FORMATS: [OutputFormat; 4] = [
+    OutputFormat::Text,
+    OutputFormat::Json,
+    OutputFormat::Csv,
+    OutputFormat::Tsv,
+];
+
+impl OutputFormat {
+    // The name used for the format on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Tsv => "tsv",
+        }
+    }
+
+    // The separator between values, for formats that print a table.
+    pub fn separator(&self) -> Option<&'static str> {
+        match self {
+            OutputFormat::Csv => Some(","),
+            OutputFormat::Tsv => Some("\t"),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ALL_OUTPUT_FORMATS
+            .into_iter()
+            .find(|f| f.name().eq_ignore_ascii_case(s))
+            .ok_or(())
+    }
+}
 
 pub fn format_separated_values<
     T,
@@ -22,11 +83,66 @@ pub fn format_separated_values<
     Ok(())
 }
 
+// A row of a CSV or TSV table. CSV values are quoted if they contain a comma, a quote or a line
+// break. TSV has no quoting, so tabs and line breaks in values are replaced with spaces.
+pub struct SeparatedRow {
+    pub values: Vec<String>,
+    pub separator: &'static str,
+}
+
+impl Display for SeparatedRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        format_separated_values(self.values.iter(), self.separator, f, |value, f| {
+            if self.separator != "," {
+                f.write_str(&value.replace(['\t', '\n', '\r'], " "))
+            } else if value.contains([',', '"', '\n', '\r']) {
+                write!(f, "\"{}\"", value.replace('"', "\"\""))
+            } else {
+                f.write_str(value)
+            }
+        })
+    }
+}
+
+// Cards written the way they're typed, in descending order, e.g. `Qc4d3c`.
+pub fn format_typed_cards(cards: CardSet) -> String {
+    cards
+        .iter_desc()
+        .map(|c| format!("{}{}", c.rank, c.suit.letter()))
+        .collect()
+}
+
 pub fn print_basic_help(executable_name: &str) {
     println!("{}: Poker Odds Program", executable_name);
     println!("Usage: {} <operation> [...]", executable_name);
     println!(
-        "\t{} showdown <card><card> [vs <card><card>]* [on <card>+]",
+        "\t{} showdown <range> [vs <range>]* [on <card>+] [dead <card>+] [--game <holdem|omaha|omaha8|shortdeck|shortdeck-trips>] [--format <text|json|csv|tsv>] [--trials <n> | --stderr <percent>] [--seed <n>]",
+        executable_name
+    );
+    println!(
+        "\t{} stud <hand> [vs <hand>]* [dead <card>+] [--game <stud|razz>] [--streets <n>] [--trials <n> | --stderr <percent>] [--seed <n>]",
+        executable_name
+    );
+    println!(
+        "\t{} batch <file> [--format <text|json|csv|tsv>]",
+        executable_name
+    );
+    println!("\t{} repl", executable_name);
+    println!(
+        "\t{} outs <pocket> [vs <pocket>]* on <flop or turn> [dead <card>+]",
+        executable_name
+    );
+    println!(
+        "\t{} vs-random <range> <opponents> [on <card>+] [dead <card>+]",
+        executable_name
+    );
+    println!("\t{} board <card>+ [--top <n>]", executable_name);
+    println!(
+        "\t{} compare <pocket> [vs <pocket>]+ on <card>{{5}}",
+        executable_name
+    );
+    println!(
+        "\t{} matrix <build <file> [--cells <n>] | lookup <file> <hand> vs <hand>>",
         executable_name
     );
     println!();
@@ -46,6 +162,46 @@ pub fn print_help(executable_name: &str, operation: Option<&str>) {
         None => print_basic_help(executable_name),
         Some("--help") => print_basic_help(executable_name),
         Some("showdown") => print_showdown_help(executable_name),
+        Some("stud") => print_stud_help(executable_name),
+        Some("batch") => print_batch_help(executable_name),
+        Some("repl") => print_repl_help(executable_name),
+        Some("outs") => print_outs_help(executable_name),
+        Some("matrix") => print_matrix_help(executable_name),
+        Some("vs-random") => print_vs_random_help(executable_name),
+        Some("board") => print_board_help(executable_name),
+        Some("compare") => print_compare_help(executable_name),
         Some(op) => print_unrecognized_operation(executable_name, &op),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::card::Card;
+
+    fn row(values: &[&str], separator: &'static str) -> String {
+        SeparatedRow {
+            values: values.iter().map(|v| v.to_string()).collect(),
+            separator,
+        }
+        .to_string()
+    }
+
+    #[test]
+    fn test_separated_rows() {
+        assert_eq!(row(&["a", "b c", "1.5"], ","), "a,b c,1.5");
+        assert_eq!(
+            row(&["QQ+,AKs", "say \"hi\""], ","),
+            "\"QQ+,AKs\",\"say \"\"hi\"\"\""
+        );
+        assert_eq!(row(&["QQ+,AKs", "a\tb"], "\t"), "QQ+,AKs\ta b");
+        assert_eq!(row(&[], ","), "");
+    }
+
+    #[test]
+    fn test_format_typed_cards() {
+        let cards = CardSet::from(&[Card::TWO_CLUB, Card::QUEEN_CLUB, Card::FOUR_DIAMOND]);
+        assert_eq!(format_typed_cards(cards), "Qc4d2c");
+        assert_eq!(format_typed_cards(CardSet::new()), "");
+    }
+}
@@ -0,0 +1,109 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+// A small, seedable xoshiro256** generator.
+//
+// The same seed always produces the same sequence, which makes sampled results reproducible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Random {
+    state: [u64; 4],
+}
+
+impl Random {
+    pub fn seeded(seed: u64) -> Self {
+        let mut sm = seed;
+        Self {
+            state: [
+                splitmix64(&mut sm),
+                splitmix64(&mut sm),
+                splitmix64(&mut sm),
+                splitmix64(&mut sm),
+            ],
+        }
+    }
+
+    // An independent generator for the `stream`th piece of work seeded with `seed`.
+    pub fn seeded_stream(seed: u64, stream: u64) -> Self {
+        let mut sm = stream;
+        Self::seeded(seed ^ splitmix64(&mut sm))
+    }
+
+    pub fn seed_from_time() -> u64 {
+        match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(d) => d.as_nanos() as u64,
+            Err(_) => 0,
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.state[1] << 17;
+
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= t;
+        self.state[3] = self.state[3].rotate_left(45);
+
+        result
+    }
+
+    // A uniformly distributed number in `0..bound`. Panics if `bound` is 0.
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0);
+        let bound = bound as u64;
+        // Rejects the values that would make the lower residues more likely.
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let value = self.next_u64();
+            if value >= threshold {
+                return (value % bound) as usize;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut a = Random::seeded(69);
+        let mut b = Random::seeded(69);
+
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_streams_differ() {
+        let mut a = Random::seeded_stream(69, 0);
+        let mut b = Random::seeded_stream(69, 1);
+
+        assert_ne!(a.next_u64(), b.next_u64());
+    }
+
+    #[test]
+    fn test_below_is_in_range_and_covers_it() {
+        let mut rng = Random::seeded(1);
+        let mut seen = [0; 7];
+
+        for _ in 0..7000 {
+            let value = rng.below(7);
+            assert!(value < 7);
+            seen[value] += 1;
+        }
+
+        assert!(seen.iter().all(|count| *count > 800 && *count < 1200));
+    }
+}
@@ -0,0 +1,51 @@
+use pop::{
+    CalculationMethod, Card, CardSet, Game, HandRange, OddsCalculation, PopError,
+    RangeOddsCalculation,
+};
+
+fn flop() -> CardSet {
+    CardSet::from(&[Card::QUEEN_HEART, Card::SEVEN_HEART, Card::TWO_CLUB])
+}
+
+#[test]
+fn test_range_odds_through_the_public_api() {
+    let ranges = [
+        "AsKs".parse::<HandRange>().unwrap(),
+        "QQ, JJ".parse().unwrap(),
+    ];
+
+    let odds =
+        RangeOddsCalculation::calculate(Game::Holdem, &ranges, flop(), CardSet::new()).unwrap();
+
+    assert_eq!(odds.len(), 2);
+    assert_eq!(odds[0].method, CalculationMethod::Exhaustive);
+    assert_eq!(odds[1].range.to_string(), "QQ,JJ");
+    let equity = odds.iter().map(|o| o.outcome.equity()).sum::<f64>();
+    assert!((equity - 1.0).abs() < 1e-9);
+    // The overpairs and the set are well ahead of two overcards.
+    assert!(odds[1].outcome.win_ratio().proportion() > 0.7);
+}
+
+#[test]
+fn test_pocket_odds_through_the_public_api() {
+    let pockets = [
+        CardSet::from(&[Card::ACE_SPADE, Card::ACE_DIAMOND]),
+        CardSet::from(&[Card::KING_SPADE, Card::KING_DIAMOND]),
+    ];
+
+    let odds = OddsCalculation::calculate(Game::Holdem, &pockets, flop(), CardSet::new()).unwrap();
+
+    // Every turn and river from the 45 cards left.
+    for player in &odds {
+        assert_eq!(player.outcome.total_hand_count(), 990);
+    }
+    assert!(odds[0].outcome.equity() > odds[1].outcome.equity());
+}
+
+#[test]
+fn test_invalid_range_is_an_error() {
+    assert!(matches!(
+        "AKs:1.5".parse::<HandRange>(),
+        Err(PopError::InvalidRange(_))
+    ));
+}
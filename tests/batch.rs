@@ -0,0 +1,49 @@
+use std::process::{Command, Output};
+
+// Runs `pop batch` on `lines`, written to a file named after `name`.
+fn run_batch(name: &str, lines: &[&str], format: &str) -> Output {
+    let path = std::env::temp_dir().join(format!("pop-batch-{}-{}.txt", name, std::process::id()));
+    std::fs::write(&path, lines.join("\n")).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_pop"))
+        .args(["batch", path.to_str().unwrap(), "--format", format])
+        .output()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    output
+}
+
+// A line that doesn't parse must only show up on stderr, so the output of the other lines is
+// exactly what it would be without it.
+#[test]
+fn test_bad_lines_leave_the_output_parseable() {
+    for format in ["json", "csv", "tsv"] {
+        let with_bad_line = run_batch(
+            &format!("bad-{}", format),
+            &[
+                "AsKs vs QcQd on Qh 7h 2c 3d",
+                "AsKs vs",
+                "AhAd vs 7c6c on 5c 4c 2h Kd",
+            ],
+            format,
+        );
+        let without = run_batch(
+            &format!("good-{}", format),
+            &[
+                "AsKs vs QcQd on Qh 7h 2c 3d",
+                "# AsKs vs",
+                "AhAd vs 7c6c on 5c 4c 2h Kd",
+            ],
+            format,
+        );
+
+        assert!(!with_bad_line.status.success());
+        assert!(without.status.success());
+        assert_eq!(
+            String::from_utf8(with_bad_line.stdout).unwrap(),
+            String::from_utf8(without.stdout).unwrap()
+        );
+        let errors = String::from_utf8(with_bad_line.stderr).unwrap();
+        assert!(errors.contains("Skipped line 2: AsKs vs"));
+        assert!(errors.contains("1 line(s) could not be parsed"));
+    }
+}